@@ -0,0 +1,12 @@
+#![no_main]
+
+use efivar::boot::BootEntry;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(entry) = BootEntry::parse(data.to_vec()) {
+        // whatever we managed to parse must be printable and written back as-is
+        let _ = entry.file_path_list.to_string();
+        assert_eq!(entry.to_bytes().unwrap(), data);
+    }
+});
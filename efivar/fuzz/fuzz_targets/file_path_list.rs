@@ -0,0 +1,13 @@
+#![no_main]
+
+use efivar::boot::{FilePathList, ParseCursor};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(file_path_list) = FilePathList::parse(&mut ParseCursor::new(data)) {
+        // whatever we managed to parse must be printable and written back as-is
+        let _ = file_path_list.to_text(true, true);
+        let _ = file_path_list.to_text(false, false);
+        assert_eq!(file_path_list.to_bytes().unwrap(), data);
+    }
+});
@@ -1,23 +1,27 @@
 //! This module contains the custom iterator used to loop lazily over boot entries
 
-use crate::{boot::BootVarFormat, efi::Variable, VarReader};
+use std::vec;
 
-use super::{BootEntry, BootVarReader, BootVariable};
+use crate::{efi::Variable, VarReader};
 
-/// Loop over boot entries. On each iteration, a variable data will be queried from the OS
+use super::{BootEntry, BootVariable};
+
+/// Loop over boot entries, or other load options. On each iteration, a variable data will be queried from the OS
 pub struct BootEntriesIterator<'a> {
-    ids: Vec<u16>,
+    /// the ID and variable of each option, in order
+    options: vec::IntoIter<(u16, Variable)>,
     var_reader: &'a dyn VarReader,
 }
 
 impl<'a> BootEntriesIterator<'a> {
     pub(in super::super) fn new(
         var_reader: &'a impl VarReader,
-    ) -> crate::Result<BootEntriesIterator<'a>> {
-        Ok(BootEntriesIterator {
-            ids: var_reader.get_boot_order()?,
+        options: Vec<(u16, Variable)>,
+    ) -> BootEntriesIterator<'a> {
+        BootEntriesIterator {
+            options: options.into_iter(),
             var_reader,
-        })
+        }
     }
 }
 
@@ -25,10 +29,8 @@ impl<'a> Iterator for BootEntriesIterator<'a> {
     type Item = (Result<BootVariable, crate::Error>, Variable);
 
     fn next(&mut self) -> Option<Self::Item> {
-        let id = self.ids.pop();
-        let id = id?;
+        let (id, var) = self.options.next()?;
 
-        let var = Variable::new(&id.boot_var_format());
         let boot_var_res =
             BootEntry::read(self.var_reader, &var).map(|entry| BootVariable { entry, id });
 
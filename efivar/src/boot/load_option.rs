@@ -0,0 +1,122 @@
+//! This module contains the kinds of variables that hold a load option (EFI_LOAD_OPTION)
+
+use std::fmt::Display;
+
+use uuid::Uuid;
+
+use crate::efi::Variable;
+
+/// Kind of a load option variable. All of them share the [`crate::boot::BootEntry`] layout,
+/// and only differ by the prefix of their name. See spec, 3.1.3
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LoadOptionKind {
+    /// `Boot####`, ordered by `BootOrder`
+    Boot,
+    /// `Driver####`, ordered by `DriverOrder`
+    Driver,
+    /// `SysPrep####`, ordered by `SysPrepOrder`
+    SysPrep,
+    /// `PlatformRecovery####`, tried in increasing ID order
+    PlatformRecovery,
+    /// `OsRecovery####`, stored in the vendor namespaces listed by `OsRecoveryOrder`
+    OsRecovery,
+}
+
+impl LoadOptionKind {
+    pub const ALL: [LoadOptionKind; 5] = [
+        LoadOptionKind::Boot,
+        LoadOptionKind::Driver,
+        LoadOptionKind::SysPrep,
+        LoadOptionKind::PlatformRecovery,
+        LoadOptionKind::OsRecovery,
+    ];
+
+    /// prefix of the variable names, before the 4 hex digits of the ID
+    pub fn prefix(self) -> &'static str {
+        match self {
+            LoadOptionKind::Boot => "Boot",
+            LoadOptionKind::Driver => "Driver",
+            LoadOptionKind::SysPrep => "SysPrep",
+            LoadOptionKind::PlatformRecovery => "PlatformRecovery",
+            LoadOptionKind::OsRecovery => "OsRecovery",
+        }
+    }
+
+    /// variable holding the order of the options, as a list of IDs.
+    ///
+    /// `PlatformRecovery####` options have no order variable, and `OsRecoveryOrder` holds a list of
+    /// vendor GUIDs instead of IDs, so both return None
+    pub fn order_variable(self) -> Option<Variable> {
+        match self {
+            LoadOptionKind::Boot | LoadOptionKind::Driver | LoadOptionKind::SysPrep => {
+                Some(Variable::new(&format!("{}Order", self.prefix())))
+            }
+            LoadOptionKind::PlatformRecovery | LoadOptionKind::OsRecovery => None,
+        }
+    }
+
+    /// name of the variable holding the option with that ID (e.g. `Driver0001`)
+    pub fn variable_name(self, id: u16) -> String {
+        format!("{}{id:04X}", self.prefix())
+    }
+
+    /// variable holding the option with that ID, in the EFI namespace
+    pub fn variable(self, id: u16) -> Variable {
+        Variable::new(&self.variable_name(id))
+    }
+
+    /// variable holding the option with that ID in a vendor namespace, as `OsRecovery####`
+    /// options are
+    pub fn vendor_variable(self, id: u16, vendor: Uuid) -> Variable {
+        Variable::new_with_vendor(&self.variable_name(id), vendor)
+    }
+
+    /// Returns the ID of the option if this variable name is one of this kind. Else, return None
+    pub fn option_id(self, name: &str) -> Option<u16> {
+        option_id(self.prefix(), name)
+    }
+}
+
+/// Parse the ID of a `<prefix>####` variable name, such as `Boot0001` or `Key0001`
+pub(crate) fn option_id(prefix: &str, name: &str) -> Option<u16> {
+    let id = name.strip_prefix(prefix)?;
+    if id.len() == 4 && id.chars().all(|c| c.is_ascii_hexdigit()) {
+        u16::from_str_radix(id, 16).ok()
+    } else {
+        None
+    }
+}
+
+impl Display for LoadOptionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.prefix())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LoadOptionKind;
+
+    #[test]
+    fn variable_names() {
+        assert_eq!(LoadOptionKind::Driver.variable_name(0x1A), "Driver001A");
+        assert_eq!(
+            LoadOptionKind::SysPrep.order_variable().unwrap().name(),
+            "SysPrepOrder"
+        );
+        assert_eq!(LoadOptionKind::PlatformRecovery.order_variable(), None);
+    }
+
+    #[test]
+    fn option_ids() {
+        assert_eq!(LoadOptionKind::Driver.option_id("Driver001A"), Some(0x1A));
+        assert_eq!(
+            LoadOptionKind::PlatformRecovery.option_id("PlatformRecovery0000"),
+            Some(0)
+        );
+        assert_eq!(LoadOptionKind::Driver.option_id("DriverOrder"), None);
+        assert_eq!(LoadOptionKind::Driver.option_id("Driver+001"), None);
+        assert_eq!(LoadOptionKind::Boot.option_id("Driver0001"), None);
+        assert_eq!(LoadOptionKind::SysPrep.option_id("SysPrep00001"), None);
+    }
+}
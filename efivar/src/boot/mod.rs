@@ -1,12 +1,16 @@
 //! This module handles everything related to boot entries
 
 mod boot_entry_iter;
+pub(crate) mod load_option;
+mod network;
 pub(crate) mod parse;
 mod reader;
 mod writer;
 
+pub use load_option::LoadOptionKind;
+pub use network::{parse_mac_address, NetworkBootPath};
 pub use parse::*;
-pub use parse::{EFIHardDrive, EFIHardDriveType, FilePath, FilePathList};
+pub use parse::{EFIHardDrive, FilePath, FilePathList, PartitionSignature};
 pub use reader::BootVarReader;
 pub use writer::BootVarWriter;
 
@@ -0,0 +1,272 @@
+//! This module builds device paths for network boot entries (PXE and UEFI HTTP boot)
+
+use std::{
+    convert::TryInto,
+    net::{Ipv4Addr, Ipv6Addr},
+};
+
+use crate::Error;
+
+use super::{Acpi, DevicePath, FilePathList, Ipv4, Ipv6, MacAddr, Pci, Uri};
+
+/// Builds the device path of network boot entries for a network interface.
+///
+/// Firmware matches the MAC address node against its NICs, and uses the PCI path (if any)
+/// to find the device. Entries look like
+/// `PciRoot(0x0)/Pci(0x3,0x0)/MAC(525400123456,0x1)/IPv4(0.0.0.0,0x0,DHCP,0.0.0.0,0.0.0.0,0.0.0.0)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct NetworkBootPath {
+    /// device path of the network controller, e.g. `PciRoot(0x0)/Pci(0x3,0x0)`
+    pub device_path: Vec<DevicePath>,
+    pub mac: [u8; 6],
+}
+
+/// parse a MAC address written as `52:54:00:12:34:56`
+pub fn parse_mac_address(text: &str) -> crate::Result<[u8; 6]> {
+    let invalid = || Error::InvalidMacAddress {
+        mac: text.to_owned(),
+    };
+
+    let bytes = text
+        .trim()
+        .split([':', '-'])
+        .map(|byte| match byte.len() {
+            2 => u8::from_str_radix(byte, 16).map_err(|_| invalid()),
+            _ => Err(invalid()),
+        })
+        .collect::<crate::Result<Vec<u8>>>()?;
+
+    bytes.try_into().map_err(|_| invalid())
+}
+
+impl NetworkBootPath {
+    pub fn new(mac: [u8; 6]) -> NetworkBootPath {
+        NetworkBootPath {
+            device_path: vec![],
+            mac,
+        }
+    }
+
+    /// set the device path of the network controller
+    pub fn with_device_path(mut self, device_path: Vec<DevicePath>) -> NetworkBootPath {
+        self.device_path = device_path;
+        self
+    }
+
+    /// build the path of a network interface, using its MAC address and PCI location from sysfs
+    #[cfg(target_os = "linux")]
+    pub fn from_interface(interface: &str) -> crate::Result<NetworkBootPath> {
+        Self::from_sysfs(std::path::Path::new("/sys"), interface)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn from_sysfs(sysfs: &std::path::Path, interface: &str) -> crate::Result<NetworkBootPath> {
+        let net_path = sysfs.join("class/net").join(interface);
+
+        let address = std::fs::read_to_string(net_path.join("address")).map_err(|_| {
+            Error::NetworkInterfaceNotFound {
+                interface: interface.to_owned(),
+            }
+        })?;
+        let mac = parse_mac_address(&address)?;
+
+        // e.g. /sys/devices/pci0000:00/0000:00:1c.0/0000:03:00.0
+        let device_path = match std::fs::canonicalize(net_path.join("device")) {
+            Ok(device) => pci_device_path(sysfs, &device)?,
+            // virtual interfaces do not have a device
+            Err(_) => vec![],
+        };
+
+        Ok(NetworkBootPath::new(mac).with_device_path(device_path))
+    }
+
+    fn mac_node(&self) -> DevicePath {
+        DevicePath::MacAddr(MacAddr::from_ethernet(self.mac))
+    }
+
+    fn ipv4_node() -> DevicePath {
+        DevicePath::Ipv4(Ipv4 {
+            local_address: Ipv4Addr::UNSPECIFIED,
+            remote_address: Ipv4Addr::UNSPECIFIED,
+            local_port: 0,
+            remote_port: 0,
+            protocol: 0,
+            static_address: false,
+            gateway_address: Ipv4Addr::UNSPECIFIED,
+            subnet_mask: Ipv4Addr::UNSPECIFIED,
+        })
+    }
+
+    fn ipv6_node() -> DevicePath {
+        DevicePath::Ipv6(Ipv6 {
+            local_address: Ipv6Addr::UNSPECIFIED,
+            remote_address: Ipv6Addr::UNSPECIFIED,
+            local_port: 0,
+            remote_port: 0,
+            protocol: 0,
+            address_origin: 0,
+            prefix_length: 0x40,
+            gateway_address: Ipv6Addr::UNSPECIFIED,
+        })
+    }
+
+    fn build(&self, nodes: Vec<DevicePath>) -> FilePathList {
+        let mut device_path = self.device_path.clone();
+        device_path.push(self.mac_node());
+        device_path.extend(nodes);
+
+        FilePathList::new(device_path)
+    }
+
+    /// file path list of a PXE boot over IPv4, using DHCP
+    pub fn pxe_ipv4(&self) -> FilePathList {
+        self.build(vec![Self::ipv4_node()])
+    }
+
+    /// file path list of a PXE boot over IPv6
+    pub fn pxe_ipv6(&self) -> FilePathList {
+        self.build(vec![Self::ipv6_node()])
+    }
+
+    /// file path list of an UEFI HTTP boot from an URI.
+    /// An empty URI lets the firmware get it from DHCP
+    pub fn http(&self, uri: &str, ipv6: bool) -> FilePathList {
+        self.build(vec![
+            if ipv6 {
+                Self::ipv6_node()
+            } else {
+                Self::ipv4_node()
+            },
+            DevicePath::Uri(Uri {
+                uri: uri.to_owned(),
+            }),
+        ])
+    }
+}
+
+/// get the device path of a PCI device from its sysfs path
+#[cfg(target_os = "linux")]
+fn pci_device_path(
+    sysfs: &std::path::Path,
+    device: &std::path::Path,
+) -> crate::Result<Vec<DevicePath>> {
+    let mut components = match device.strip_prefix(sysfs.join("devices")) {
+        Ok(path) => path.iter().filter_map(|component| component.to_str()),
+        Err(_) => return Ok(vec![]),
+    };
+
+    // the PCI root bridge, e.g. pci0000:00
+    let root = match components.next() {
+        Some(root) if root.starts_with("pci") => root,
+        _ => return Ok(vec![]),
+    };
+    // use the ACPI UID of the bridge, if the firmware gave one
+    let uid = std::fs::read_to_string(sysfs.join("devices").join(root).join("firmware_node/uid"))
+        .ok()
+        .and_then(|uid| uid.trim().parse().ok())
+        .unwrap_or(0);
+    let mut nodes = vec![DevicePath::Acpi(Acpi {
+        hid: 0x0A03_41D0,
+        uid,
+    })];
+
+    // PCI bridges and devices, e.g. 0000:00:1c.0. Stop at the first non-PCI component
+    for component in components {
+        match parse_pci_address(component) {
+            Some((device, function)) => nodes.push(DevicePath::Pci(Pci { function, device })),
+            None => break,
+        }
+    }
+
+    Ok(nodes)
+}
+
+/// parse the device and function of a PCI address, such as `0000:00:1c.0`
+#[cfg(target_os = "linux")]
+fn parse_pci_address(address: &str) -> Option<(u8, u8)> {
+    let mut parts = address.split(':');
+    let (_domain, _bus, device_function) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+    let (device, function) = device_function.split_once('.')?;
+
+    Some((
+        u8::from_str_radix(device, 16).ok()?,
+        u8::from_str_radix(function, 16).ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_mac_address, NetworkBootPath};
+
+    #[test]
+    fn parse_mac() {
+        assert_eq!(
+            parse_mac_address("52:54:00:12:34:56\n").unwrap(),
+            [0x52, 0x54, 0x00, 0x12, 0x34, 0x56]
+        );
+        assert_eq!(
+            parse_mac_address("52-54-00-AB-CD-EF").unwrap(),
+            [0x52, 0x54, 0x00, 0xAB, 0xCD, 0xEF]
+        );
+        assert!(parse_mac_address("52:54:00:12:34").is_err());
+        assert!(parse_mac_address("52:54:00:12:34:56:78").is_err());
+        assert!(parse_mac_address("52:54:00:12:34:5").is_err());
+        assert!(parse_mac_address("52:54:00:12:34:zz").is_err());
+    }
+
+    #[test]
+    fn build_paths() {
+        let path = NetworkBootPath::new([0x52, 0x54, 0x00, 0x12, 0x34, 0x56]).with_device_path(
+            "PciRoot(0x0)/Pci(0x3,0x0)"
+                .parse::<crate::boot::FilePathList>()
+                .unwrap()
+                .instances
+                .remove(0)
+                .nodes,
+        );
+
+        assert_eq!(
+            path.pxe_ipv4().to_string(),
+            "PciRoot(0x0)/Pci(0x3,0x0)/MAC(525400123456,0x1)/IPv4(0.0.0.0,0x0,DHCP,0.0.0.0,0.0.0.0,0.0.0.0)"
+        );
+        assert_eq!(
+            path.pxe_ipv6().to_string(),
+            "PciRoot(0x0)/Pci(0x3,0x0)/MAC(525400123456,0x1)/IPv6(::,0x0,Static,::,0x40,::)"
+        );
+        assert_eq!(
+            path.http("http://192.168.1.1/boot.efi", false).to_string(),
+            "PciRoot(0x0)/Pci(0x3,0x0)/MAC(525400123456,0x1)/IPv4(0.0.0.0,0x0,DHCP,0.0.0.0,0.0.0.0,0.0.0.0)/Uri(http://192.168.1.1/boot.efi)"
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn from_sysfs() {
+        let sysfs = tempfile::tempdir().unwrap();
+        let sysfs = sysfs.path();
+
+        let device = sysfs.join("devices/pci0000:00/0000:00:1c.0/0000:03:00.0");
+        std::fs::create_dir_all(&device).unwrap();
+        std::fs::create_dir_all(sysfs.join("devices/pci0000:00/firmware_node")).unwrap();
+        std::fs::write(sysfs.join("devices/pci0000:00/firmware_node/uid"), "1\n").unwrap();
+
+        let interface = device.join("net/enp3s0");
+        std::fs::create_dir_all(&interface).unwrap();
+        std::fs::write(interface.join("address"), "52:54:00:12:34:56\n").unwrap();
+        std::os::unix::fs::symlink(&device, interface.join("device")).unwrap();
+
+        std::fs::create_dir_all(sysfs.join("class/net")).unwrap();
+        std::os::unix::fs::symlink(&interface, sysfs.join("class/net/enp3s0")).unwrap();
+
+        let path = NetworkBootPath::from_sysfs(sysfs, "enp3s0").unwrap();
+        assert_eq!(
+            path.pxe_ipv4().to_string(),
+            "PciRoot(0x1)/Pci(0x1C,0x0)/Pci(0x0,0x0)/MAC(525400123456,0x1)/IPv4(0.0.0.0,0x0,DHCP,0.0.0.0,0.0.0.0,0.0.0.0)"
+        );
+
+        assert!(NetworkBootPath::from_sysfs(sysfs, "eth0").is_err());
+    }
+}
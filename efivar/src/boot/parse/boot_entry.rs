@@ -1,11 +1,9 @@
 //! This module contains parsing code for a boot entry
 
-use std::{fmt::Display, io::Read};
+use std::fmt::Display;
 
-use byteorder::{LittleEndian, ReadBytesExt};
-
-use super::FilePathList;
-use crate::{efi::Variable, push::PushVecU8, utils::read_nt_utf16_string, Error, VarReader};
+use super::{BootEntryRef, FilePathList, OptionalData};
+use crate::{efi::Variable, push::PushVecU8, Error, VarReader};
 use std::convert::TryFrom;
 
 bitflags::bitflags! {
@@ -29,62 +27,37 @@ impl Display for BootEntryAttributes {
 pub struct BootEntry {
     pub attributes: BootEntryAttributes,
     pub description: String,
-    pub file_path_list: Option<FilePathList>,
+    pub file_path_list: FilePathList,
     pub optional_data: Vec<u8>,
 }
 
 impl BootEntry {
     pub fn read(manager: &(impl ?Sized + VarReader), variable: &Variable) -> crate::Result<Self> {
         let (value, _flags) = manager.read(variable)?;
-        Self::parse(value)
+        Self::parse(value).map_err(|err| err.with_variable(variable))
     }
 
     pub fn parse(value: Vec<u8>) -> crate::Result<Self> {
-        // slice of the buffer
-        // Used so we can move the offset in it with ReadBytesExt functions
-        let mut buf = &value[..];
-
-        let attributes = buf
-            .read_u32::<LittleEndian>()
-            .map_err(|_| Error::VarParseError)?;
-
-        let file_path_list_length = buf
-            .read_u16::<LittleEndian>()
-            .map_err(|_| Error::VarParseError)?;
-
-        let description = read_nt_utf16_string(&mut buf).map_err(crate::Error::StringParseError)?;
-
-        let mut file_path_list_buf = vec![0u8; file_path_list_length.into()];
-        buf.read_exact(&mut file_path_list_buf)
-            .map_err(|_| Error::VarParseError)?;
-
-        let file_path_list = FilePathList::parse(&mut &file_path_list_buf[..])?.into();
+        BootEntry::try_from(BootEntryRef::parse(&value)?)
+    }
 
-        Ok(BootEntry {
-            attributes: BootEntryAttributes::from_bits(attributes).ok_or(Error::VarParseError)?,
-            description,
-            file_path_list,
-            optional_data: buf.to_vec(),
-        })
+    /// decode the optional data of the entry, falling back to [`OptionalData::Binary`]
+    pub fn decoded_optional_data(&self) -> OptionalData {
+        OptionalData::parse(&self.optional_data)
     }
 
-    pub fn to_bytes(&self) -> Vec<u8> {
+    pub fn to_bytes(&self) -> crate::Result<Vec<u8>> {
         let mut bytes: Vec<u8> = vec![];
 
         // append attribute bytes
         bytes.push_u32(self.attributes.bits());
 
         // append file path list length
-        let mut fpl_bytes: Vec<u8> = if let Some(fpl) = &self.file_path_list {
-            fpl.to_bytes()
-        } else {
-            vec![]
-        };
-        bytes.append(
-            &mut u16::try_from(fpl_bytes.len())
-                .expect("length should fit in u16")
-                .to_le_bytes()
-                .to_vec(),
+        let mut fpl_bytes: Vec<u8> = self.file_path_list.to_bytes()?;
+        bytes.push_u16(
+            u16::try_from(fpl_bytes.len()).map_err(|_| Error::ValueTooLarge {
+                what: "file path list",
+            })?,
         );
 
         // append description bytes
@@ -103,6 +76,6 @@ impl BootEntry {
         // append optional data
         bytes.append(&mut self.optional_data.clone());
 
-        bytes
+        Ok(bytes)
     }
 }
@@ -0,0 +1,90 @@
+//! This module contains a borrowed view of a boot entry, that is only decoded on demand
+
+use std::convert::TryFrom;
+
+use super::{BootEntry, BootEntryAttributes, DevicePathIter, FilePathList, ParseCursor};
+
+/// A boot entry (EFI_LOAD_OPTION) borrowed from the variable data it was read from.
+///
+/// Parsing only checks the layout of the entry. The description and the device path nodes are
+/// decoded when they are accessed, and nothing is copied until then.
+#[derive(Debug, Clone, Copy)]
+pub struct BootEntryRef<'a> {
+    attributes: BootEntryAttributes,
+    /// UTF-16 characters of the description, without the terminator
+    description: ParseCursor<'a>,
+    file_path_list: ParseCursor<'a>,
+    optional_data: &'a [u8],
+}
+
+impl<'a> BootEntryRef<'a> {
+    pub fn parse(data: &'a [u8]) -> crate::Result<BootEntryRef<'a>> {
+        let mut buf = ParseCursor::new(data);
+
+        let attributes = buf.read_u32("BootEntry.attributes")?;
+        let file_path_list_length = buf.read_u16("BootEntry.file_path_list_length")?;
+        let description = buf.read_nt_utf16_raw("BootEntry.description")?;
+        let file_path_list =
+            buf.read_cursor(file_path_list_length.into(), "BootEntry.file_path_list")?;
+
+        Ok(BootEntryRef {
+            // keep unknown bits, so that writing the entry back does not lose them
+            attributes: BootEntryAttributes::from_bits_retain(attributes),
+            description,
+            file_path_list,
+            optional_data: buf.read_rest(),
+        })
+    }
+
+    pub fn attributes(&self) -> BootEntryAttributes {
+        self.attributes
+    }
+
+    /// decode the description of the entry
+    pub fn description(&self) -> crate::Result<String> {
+        self.description.decode_utf16("BootEntry.description")
+    }
+
+    /// UTF-16 code units of the description, without decoding them
+    pub fn description_utf16(&self) -> impl Iterator<Item = u16> + 'a {
+        self.description
+            .remaining()
+            .as_chunks::<2>()
+            .0
+            .iter()
+            .map(|&chr| u16::from_le_bytes(chr))
+    }
+
+    /// raw bytes of the file path list
+    pub fn file_path_list_bytes(&self) -> &'a [u8] {
+        self.file_path_list.remaining()
+    }
+
+    /// loop over the nodes of the file path list, including end nodes
+    pub fn device_paths(&self) -> DevicePathIter<'a> {
+        DevicePathIter::new(self.file_path_list)
+    }
+
+    /// decode the whole file path list
+    pub fn file_path_list(&self) -> crate::Result<FilePathList> {
+        let mut buf = self.file_path_list;
+        FilePathList::parse(&mut buf)
+    }
+
+    pub fn optional_data(&self) -> &'a [u8] {
+        self.optional_data
+    }
+}
+
+impl TryFrom<BootEntryRef<'_>> for BootEntry {
+    type Error = crate::Error;
+
+    fn try_from(entry: BootEntryRef<'_>) -> Result<Self, Self::Error> {
+        Ok(BootEntry {
+            attributes: entry.attributes(),
+            description: entry.description()?,
+            file_path_list: entry.file_path_list()?,
+            optional_data: entry.optional_data().to_vec(),
+        })
+    }
+}
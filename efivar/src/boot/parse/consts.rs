@@ -1,3 +1,63 @@
+/// Holds magic numbers of the different subtypes for the device path type HARDWARE_DEVICE_PATH
+#[allow(unused)]
+#[allow(non_snake_case)] // module only holds constants
+pub mod HARDWARE_DEVICE_PATH_SUBTYPE {
+    pub const PCI: u8 = 0x01;
+    pub const PCCARD: u8 = 0x02;
+    pub const MEMORY_MAPPED: u8 = 0x03;
+    pub const VENDOR: u8 = 0x04;
+    pub const CONTROLLER: u8 = 0x05;
+    pub const BMC: u8 = 0x06;
+}
+
+/// Holds magic numbers of the different subtypes for the device path type ACPI_DEVICE_PATH
+#[allow(unused)]
+#[allow(non_snake_case)] // module only holds constants
+pub mod ACPI_DEVICE_PATH_SUBTYPE {
+    pub const ACPI: u8 = 0x01;
+    pub const EXPANDED_ACPI: u8 = 0x02;
+    pub const ADR: u8 = 0x03;
+    pub const NVDIMM: u8 = 0x04;
+}
+
+/// Holds magic numbers of the different subtypes for the device path type MESSAGING_DEVICE_PATH
+#[allow(unused)]
+#[allow(non_snake_case)] // module only holds constants
+pub mod MESSAGING_DEVICE_PATH_SUBTYPE {
+    pub const ATAPI: u8 = 0x01;
+    pub const SCSI: u8 = 0x02;
+    pub const FIBRE_CHANNEL: u8 = 0x03;
+    pub const IEEE_1394: u8 = 0x04;
+    pub const USB: u8 = 0x05;
+    pub const I2O: u8 = 0x06;
+    pub const INFINIBAND: u8 = 0x09;
+    pub const VENDOR: u8 = 0x0A;
+    pub const MAC_ADDRESS: u8 = 0x0B;
+    pub const IPV4: u8 = 0x0C;
+    pub const IPV6: u8 = 0x0D;
+    pub const UART: u8 = 0x0E;
+    pub const USB_CLASS: u8 = 0x0F;
+    pub const USB_WWID: u8 = 0x10;
+    pub const LOGICAL_UNIT: u8 = 0x11;
+    pub const SATA: u8 = 0x12;
+    pub const ISCSI: u8 = 0x13;
+    pub const VLAN: u8 = 0x14;
+    pub const FIBRE_CHANNEL_EX: u8 = 0x15;
+    pub const SAS_EX: u8 = 0x16;
+    pub const NVME_NAMESPACE: u8 = 0x17;
+    pub const URI: u8 = 0x18;
+    pub const UFS: u8 = 0x19;
+    pub const SD: u8 = 0x1A;
+    pub const BLUETOOTH: u8 = 0x1B;
+    pub const WIFI: u8 = 0x1C;
+    pub const EMMC: u8 = 0x1D;
+    pub const BLUETOOTH_LE: u8 = 0x1E;
+    pub const DNS: u8 = 0x1F;
+    pub const NVDIMM: u8 = 0x20;
+    pub const REST_SERVICE: u8 = 0x21;
+    pub const NVME_OF_NAMESPACE: u8 = 0x22;
+}
+
 /// Holds magic numbers of the different subtypes for the device path type MEDIA_DEVICE_PATH
 #[allow(unused)]
 #[allow(non_snake_case)] // module only holds constants
@@ -13,6 +73,13 @@ pub mod MEDIA_DEVICE_PATH_SUBTYPE {
     pub const RAM_DISK_DEVICE_PATH: u8 = 0x09;
 }
 
+/// Holds magic numbers of the different subtypes for the device path type BIOS_BOOT_SPECIFICATION_DEVICE_PATH
+#[allow(unused)]
+#[allow(non_snake_case)] // module only holds constants
+pub mod BIOS_BOOT_SPECIFICATION_DEVICE_PATH_SUBTYPE {
+    pub const BBS_1_01: u8 = 0x01;
+}
+
 /// Holds magic numbers of the different subtypes for the device path type END_OF_HARDWARE_DEVICE_PATH
 #[allow(unused)]
 #[allow(non_snake_case)] // module only holds constants
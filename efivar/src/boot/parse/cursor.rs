@@ -0,0 +1,236 @@
+//! This module contains the cursor used to decode binary structures, keeping track of the offset
+//! so that errors can point to the exact field that failed to parse
+
+use std::convert::TryInto;
+
+use uuid::Uuid;
+
+use crate::ParseError;
+
+/// Reads little-endian values from a buffer, and reports truncated fields as [`ParseError`]s.
+///
+/// Field names are given as `Structure.field` (e.g. `EFIHardDrive.partition_start`)
+#[derive(Debug, Clone, Copy)]
+pub struct ParseCursor<'a> {
+    data: &'a [u8],
+    /// offset of `data` in the buffer being parsed
+    offset: usize,
+}
+
+impl<'a> ParseCursor<'a> {
+    pub fn new(data: &'a [u8]) -> ParseCursor<'a> {
+        ParseCursor { data, offset: 0 }
+    }
+
+    /// a cursor at `offset` of `data`, keeping offsets relative to the whole buffer
+    pub fn at(
+        data: &'a [u8],
+        offset: usize,
+        field: &'static str,
+    ) -> crate::Result<ParseCursor<'a>> {
+        let mut buf = ParseCursor::new(data);
+        buf.read_bytes(offset, field)?;
+        Ok(buf)
+    }
+
+    /// offset of the next byte to read, from the start of the buffer being parsed
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// bytes that have not been read yet
+    pub fn remaining(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn read_bytes(&mut self, len: usize, field: &'static str) -> crate::Result<&'a [u8]> {
+        if len > self.data.len() {
+            return Err(ParseError::truncated(self.offset, field, len, self.data.len()).into());
+        }
+
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        self.offset += len;
+
+        Ok(bytes)
+    }
+
+    /// read all the bytes left
+    pub fn read_rest(&mut self) -> &'a [u8] {
+        let bytes = self.data;
+        self.offset += bytes.len();
+        self.data = &[];
+
+        bytes
+    }
+
+    /// read the next `len` bytes as a cursor of their own, keeping offsets relative to the whole buffer
+    pub fn read_cursor(
+        &mut self,
+        len: usize,
+        field: &'static str,
+    ) -> crate::Result<ParseCursor<'a>> {
+        let offset = self.offset;
+        Ok(ParseCursor {
+            data: self.read_bytes(len, field)?,
+            offset,
+        })
+    }
+
+    pub fn read_array<const N: usize>(&mut self, field: &'static str) -> crate::Result<[u8; N]> {
+        Ok(self
+            .read_bytes(N, field)?
+            .try_into()
+            .expect("read_bytes returns the requested length"))
+    }
+
+    pub fn read_u8(&mut self, field: &'static str) -> crate::Result<u8> {
+        Ok(self.read_array::<1>(field)?[0])
+    }
+
+    pub fn read_u16(&mut self, field: &'static str) -> crate::Result<u16> {
+        Ok(u16::from_le_bytes(self.read_array(field)?))
+    }
+
+    pub fn read_u32(&mut self, field: &'static str) -> crate::Result<u32> {
+        Ok(u32::from_le_bytes(self.read_array(field)?))
+    }
+
+    pub fn read_u64(&mut self, field: &'static str) -> crate::Result<u64> {
+        Ok(u64::from_le_bytes(self.read_array(field)?))
+    }
+
+    /// read a GUID stored in the EFI (mixed-endian) binary layout
+    pub fn read_guid(&mut self, field: &'static str) -> crate::Result<Uuid> {
+        Ok(Uuid::from_bytes_le(self.read_array(field)?))
+    }
+
+    /// read a null-terminated UTF-16 string without decoding it. The returned cursor holds the
+    /// characters of the string, without the terminator
+    pub fn read_nt_utf16_raw(&mut self, field: &'static str) -> crate::Result<ParseCursor<'a>> {
+        let len = self
+            .data
+            .as_chunks::<2>()
+            .0
+            .iter()
+            .position(|chr| chr == &[0x00, 0x00])
+            .ok_or_else(|| {
+                // at least the terminator is missing
+                let available = self.data.len();
+                ParseError::truncated(self.offset, field, (available / 2 + 1) * 2, available)
+            })?;
+
+        let chars = self.read_cursor(len * 2, field)?;
+        self.read_bytes(2, field)?;
+
+        Ok(chars)
+    }
+
+    /// read a null-terminated UTF-16 string
+    pub fn read_nt_utf16_string(&mut self, field: &'static str) -> crate::Result<String> {
+        self.read_nt_utf16_raw(field)?.decode_utf16(field)
+    }
+
+    /// decode all the bytes left as an UTF-16 string, without consuming them
+    pub fn decode_utf16(&self, field: &'static str) -> crate::Result<String> {
+        char::decode_utf16(
+            self.data
+                .as_chunks::<2>()
+                .0
+                .iter()
+                .map(|&chr| u16::from_le_bytes(chr)),
+        )
+        .collect::<Result<String, _>>()
+        .map_err(|_| ParseError::invalid(self.offset, field, "invalid UTF-16 string").into())
+    }
+
+    /// read a null-terminated ASCII string
+    pub fn read_nt_ascii_string(&mut self, field: &'static str) -> crate::Result<String> {
+        let offset = self.offset;
+        let len = self.data.iter().position(|&c| c == 0).ok_or_else(|| {
+            ParseError::truncated(offset, field, self.data.len() + 1, self.data.len())
+        })?;
+
+        let bytes = self.read_bytes(len + 1, field)?;
+        std::str::from_utf8(&bytes[..len])
+            .map(str::to_owned)
+            .map_err(|_| ParseError::invalid(offset, field, "invalid ASCII string").into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, ParseError, ParseErrorKind};
+
+    use super::ParseCursor;
+
+    #[test]
+    fn read_values() {
+        let data = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07];
+        let mut cursor = ParseCursor::new(&data);
+        assert_eq!(cursor.read_u8("Test.a").unwrap(), 0x01);
+        assert_eq!(cursor.read_u16("Test.b").unwrap(), 0x0302);
+        assert_eq!(cursor.read_u32("Test.c").unwrap(), 0x07060504);
+        assert_eq!(cursor.offset(), 7);
+        assert!(cursor.is_empty());
+    }
+
+    #[test]
+    fn read_guid_mixed_endian() {
+        let data = [
+            0x61, 0xDF, 0xE4, 0x8B, 0xCA, 0x93, 0xD2, 0x11, 0xAA, 0x0D, 0x00, 0xE0, 0x98, 0x03,
+            0x2B, 0x8C,
+        ];
+        assert_eq!(
+            ParseCursor::new(&data)
+                .read_guid("Test.guid")
+                .unwrap()
+                .to_string(),
+            "8be4df61-93ca-11d2-aa0d-00e098032b8c"
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        let data = [0x01, 0x02, 0x03];
+        let mut cursor = ParseCursor::new(&data);
+        cursor.read_u8("Test.a").unwrap();
+        let mut sub_cursor = cursor.read_cursor(2, "Test.b").unwrap();
+
+        match sub_cursor.read_u32("Sub.c") {
+            Err(Error::ParseError(err)) => assert_eq!(
+                err,
+                ParseError {
+                    variable: None,
+                    offset: 1,
+                    field: "Sub.c",
+                    kind: ParseErrorKind::Truncated {
+                        expected: 4,
+                        available: 2
+                    }
+                }
+            ),
+            res => panic!("unexpected result {:?}", res),
+        }
+    }
+
+    #[test]
+    fn strings() {
+        let data = [0x41, 0x00, 0x42, 0x00, 0x00, 0x00, 0x43, 0x44, 0x00, 0x45];
+        let mut cursor = ParseCursor::new(&data);
+        assert_eq!(cursor.read_nt_utf16_string("Test.a").unwrap(), "AB");
+        assert_eq!(cursor.read_nt_ascii_string("Test.b").unwrap(), "CD");
+        assert!(cursor.read_nt_ascii_string("Test.c").is_err());
+        assert!(cursor.read_nt_utf16_string("Test.d").is_err());
+
+        // unpaired surrogate
+        let data = [0x00, 0xD8, 0x00, 0x00];
+        assert!(ParseCursor::new(&data)
+            .read_nt_utf16_string("Test.e")
+            .is_err());
+    }
+}
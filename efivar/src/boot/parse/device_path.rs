@@ -1,292 +1,547 @@
 //! This module contains parsing code for a device path, part of a device path list
 
-use std::{convert::TryInto, fmt::Display};
+mod acpi;
+mod bbs;
+mod from_text;
+mod hardware;
+mod media;
+mod messaging;
+mod text;
 
-use byteorder::{LittleEndian, ReadBytesExt};
-use uuid::Uuid;
-
-use crate::{push::PushVecU8, utils::read_nt_utf16_string, Error};
+use std::convert::TryFrom;
 
-use super::consts;
+use uuid::Uuid;
 
+use crate::{push::PushVecU8, Error};
+
+use super::{consts, DevicePathRef, ParseCursor};
+
+pub use acpi::{Acpi, AcpiAdr, ExpandedAcpi};
+pub use bbs::Bbs;
+pub use from_text::device_path_from_text;
+pub use hardware::{Controller, MemoryMapped, Pci};
+pub use media::{
+    CdRom, EFIHardDrive, FilePath, FirmwareFile, FirmwareVolume, PartitionSignature, RamDisk,
+    RelativeOffsetRange,
+};
+pub use messaging::{Ipv4, Ipv6, MacAddr, Nvme, Sata, Scsi, Uri, Usb};
+pub use text::device_path_to_text;
+
+/// A single device path node (EFI_DEVICE_PATH_PROTOCOL)
+///
+/// Nodes that are not understood, or that do not exactly match the layout described in the
+/// specification, are kept as [`DevicePath::Unknown`] so that they re-serialize to the same bytes.
+#[derive(Debug, PartialEq, Clone)]
 pub enum DevicePath {
-    FilePath(FilePath),
+    // Hardware device paths
+    Pci(Pci),
+    MemoryMapped(MemoryMapped),
+    HardwareVendor(VendorDevicePath),
+    Controller(Controller),
+    // ACPI device paths
+    Acpi(Acpi),
+    ExpandedAcpi(ExpandedAcpi),
+    AcpiAdr(AcpiAdr),
+    // Messaging device paths
+    Usb(Usb),
+    Sata(Sata),
+    Nvme(Nvme),
+    Scsi(Scsi),
+    MacAddr(MacAddr),
+    Ipv4(Ipv4),
+    Ipv6(Ipv6),
+    Uri(Uri),
+    MessagingVendor(VendorDevicePath),
+    // Media device paths
     HardDrive(EFIHardDrive),
+    CdRom(CdRom),
+    MediaVendor(VendorDevicePath),
+    FilePath(FilePath),
+    FirmwareFile(FirmwareFile),
+    FirmwareVolume(FirmwareVolume),
+    RelativeOffsetRange(RelativeOffsetRange),
+    RamDisk(RamDisk),
+    // BIOS boot specification device paths
+    Bbs(Bbs),
+    // End of hardware device path
+    EndInstance,
+    EndEntire,
+    /// Any other node, stored as-is
+    Unknown {
+        r#type: u8,
+        subtype: u8,
+        data: Vec<u8>,
+    },
 }
 
+/// Vendor-defined device path node. Used by the hardware, messaging and media vendor subtypes
 #[derive(Debug, PartialEq, Clone)]
-pub enum EFIHardDriveType {
-    Mbr,
-    Gpt,
-    Unknown, // TODO: remove ?
+pub struct VendorDevicePath {
+    pub guid: Uuid,
+    pub data: Vec<u8>,
 }
 
-impl EFIHardDriveType {
-    pub fn parse(sig_type: u8) -> EFIHardDriveType {
-        match sig_type {
-            0x01 => Self::Mbr,
-            0x02 => Self::Gpt,
-            _ => Self::Unknown,
-        }
+impl VendorDevicePath {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<VendorDevicePath> {
+        Ok(VendorDevicePath {
+            guid: buf.read_guid("VendorDevicePath.guid")?,
+            data: buf.read_rest().to_vec(),
+        })
     }
 
-    pub fn as_u8(&self) -> u8 {
-        match self {
-            EFIHardDriveType::Mbr => 0x01,
-            EFIHardDriveType::Gpt => 0x02,
-            EFIHardDriveType::Unknown => panic!(),
-        }
-    }
-}
+    fn to_bytes_raw(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+        bytes.push_guid(&self.guid);
+        bytes.extend_from_slice(&self.data);
 
-impl Display for EFIHardDriveType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            EFIHardDriveType::Mbr => f.write_str("MBR"),
-            EFIHardDriveType::Gpt => f.write_str("GPT"),
-            EFIHardDriveType::Unknown => f.write_str("Unknown"),
-        }
+        bytes
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
-// See spec, 10.3.6.1
-pub struct EFIHardDrive {
-    pub partition_number: u32,
-    pub partition_start: u64,
-    pub partition_size: u64,
-    pub partition_sig: Uuid,
-    pub format: u8,
-    pub sig_type: EFIHardDriveType,
-}
-
-impl EFIHardDrive {
-    pub fn parse(buf: &mut &[u8]) -> crate::Result<EFIHardDrive> {
-        Ok(EFIHardDrive {
-            partition_number: buf
-                .read_u32::<LittleEndian>()
-                .map_err(|_| Error::VarParseError)?,
-            partition_start: buf
-                .read_u64::<LittleEndian>()
-                .map_err(|_| Error::VarParseError)?,
-            partition_size: buf
-                .read_u64::<LittleEndian>()
-                .map_err(|_| Error::VarParseError)?,
-            partition_sig: Uuid::from_fields(
-                buf.read_u32::<LittleEndian>()
-                    .map_err(|_| Error::VarParseError)?,
-                buf.read_u16::<LittleEndian>()
-                    .map_err(|_| Error::VarParseError)?,
-                buf.read_u16::<LittleEndian>()
-                    .map_err(|_| Error::VarParseError)?,
-                &buf.read_u64::<LittleEndian>()
-                    .map_err(|_| Error::VarParseError)?
-                    .to_le_bytes(),
-            ),
-            format: buf.read_u8().map_err(|_| Error::VarParseError)?,
-            sig_type: EFIHardDriveType::parse(buf.read_u8().map_err(|_| Error::VarParseError)?),
-        })
+impl DevicePath {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<DevicePath> {
+        Ok(DevicePathRef::parse(buf)?.to_device_path())
     }
-}
 
-impl Display for EFIHardDrive {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "HD({},{},{})",
-            self.partition_number, self.sig_type, self.partition_sig
-        )
-    }
-}
+    /// Decode the data of a node, falling back to [`DevicePath::Unknown`]
+    pub(super) fn decode(r#type: u8, subtype: u8, mut data: ParseCursor) -> DevicePath {
+        let device_path_data = data.remaining();
 
-impl DevicePath {
-    pub fn parse(buf: &mut &[u8]) -> crate::Result<Option<DevicePath>> {
-        let r#type = buf.read_u8().map_err(|_| Error::VarParseError)?;
-        let subtype = buf.read_u8().map_err(|_| Error::VarParseError)?;
-        let length = buf
-            .read_u16::<LittleEndian>()
-            .map_err(|_| Error::VarParseError)?;
+        // only keep the decoded node if it gives back the exact same bytes
+        let node = Self::parse_known(r#type, subtype, &mut data)
+            .ok()
+            .flatten()
+            .filter(|node| node.to_bytes_raw() == device_path_data);
 
-        let data_size = length - 1 - 1 - 2;
+        node.unwrap_or_else(|| DevicePath::Unknown {
+            r#type,
+            subtype,
+            data: device_path_data.to_vec(),
+        })
+    }
 
-        if data_size as usize > buf.len() {
-            return Err(Error::VarParseError);
-        }
+    /// Decode the data of a node we know about. Returns None for unsupported nodes
+    fn parse_known(
+        r#type: u8,
+        subtype: u8,
+        buf: &mut ParseCursor,
+    ) -> crate::Result<Option<DevicePath>> {
+        use consts::{
+            ACPI_DEVICE_PATH_SUBTYPE as ACPI, BIOS_BOOT_SPECIFICATION_DEVICE_PATH_SUBTYPE as BBS,
+            DEVICE_PATH_TYPE as TYPE, END_OF_HARDWARE_DEVICE_PATH_SUBTYPE as END,
+            HARDWARE_DEVICE_PATH_SUBTYPE as HW, MEDIA_DEVICE_PATH_SUBTYPE as MEDIA,
+            MESSAGING_DEVICE_PATH_SUBTYPE as MSG,
+        };
 
-        let (mut device_path_data, new_buf) = buf.split_at(data_size.into());
-        *buf = new_buf;
-
-        #[allow(clippy::single_match)]
-        match r#type {
-            consts::DEVICE_PATH_TYPE::MEDIA_DEVICE_PATH => match subtype {
-                consts::MEDIA_DEVICE_PATH_SUBTYPE::FILE_PATH => {
-                    return Ok(Some(DevicePath::FilePath(FilePath {
-                        path: read_nt_utf16_string(&mut device_path_data)
-                            .map_err(crate::Error::StringParseError)?,
-                    })));
-                }
-                consts::MEDIA_DEVICE_PATH_SUBTYPE::HARD_DRIVE => {
-                    return Ok(Some(DevicePath::HardDrive(EFIHardDrive::parse(
-                        &mut device_path_data,
-                    )?)));
-                }
-                _ => {}
-            },
-            _ => {}
+        let node = match (r#type, subtype) {
+            (TYPE::HARDWARE_DEVICE_PATH, HW::PCI) => DevicePath::Pci(Pci::parse(buf)?),
+            (TYPE::HARDWARE_DEVICE_PATH, HW::MEMORY_MAPPED) => {
+                DevicePath::MemoryMapped(MemoryMapped::parse(buf)?)
+            }
+            (TYPE::HARDWARE_DEVICE_PATH, HW::VENDOR) => {
+                DevicePath::HardwareVendor(VendorDevicePath::parse(buf)?)
+            }
+            (TYPE::HARDWARE_DEVICE_PATH, HW::CONTROLLER) => {
+                DevicePath::Controller(Controller::parse(buf)?)
+            }
+            (TYPE::ACPI_DEVICE_PATH, ACPI::ACPI) => DevicePath::Acpi(Acpi::parse(buf)?),
+            (TYPE::ACPI_DEVICE_PATH, ACPI::EXPANDED_ACPI) => {
+                DevicePath::ExpandedAcpi(ExpandedAcpi::parse(buf)?)
+            }
+            (TYPE::ACPI_DEVICE_PATH, ACPI::ADR) => DevicePath::AcpiAdr(AcpiAdr::parse(buf)?),
+            (TYPE::MESSAGING_DEVICE_PATH, MSG::USB) => DevicePath::Usb(Usb::parse(buf)?),
+            (TYPE::MESSAGING_DEVICE_PATH, MSG::SATA) => DevicePath::Sata(Sata::parse(buf)?),
+            (TYPE::MESSAGING_DEVICE_PATH, MSG::NVME_NAMESPACE) => {
+                DevicePath::Nvme(Nvme::parse(buf)?)
+            }
+            (TYPE::MESSAGING_DEVICE_PATH, MSG::SCSI) => DevicePath::Scsi(Scsi::parse(buf)?),
+            (TYPE::MESSAGING_DEVICE_PATH, MSG::MAC_ADDRESS) => {
+                DevicePath::MacAddr(MacAddr::parse(buf)?)
+            }
+            (TYPE::MESSAGING_DEVICE_PATH, MSG::IPV4) => DevicePath::Ipv4(Ipv4::parse(buf)?),
+            (TYPE::MESSAGING_DEVICE_PATH, MSG::IPV6) => DevicePath::Ipv6(Ipv6::parse(buf)?),
+            (TYPE::MESSAGING_DEVICE_PATH, MSG::URI) => DevicePath::Uri(Uri::parse(buf)?),
+            (TYPE::MESSAGING_DEVICE_PATH, MSG::VENDOR) => {
+                DevicePath::MessagingVendor(VendorDevicePath::parse(buf)?)
+            }
+            (TYPE::MEDIA_DEVICE_PATH, MEDIA::HARD_DRIVE) => {
+                DevicePath::HardDrive(EFIHardDrive::parse(buf)?)
+            }
+            (TYPE::MEDIA_DEVICE_PATH, MEDIA::CD_ROM) => DevicePath::CdRom(CdRom::parse(buf)?),
+            (TYPE::MEDIA_DEVICE_PATH, MEDIA::VENDOR) => {
+                DevicePath::MediaVendor(VendorDevicePath::parse(buf)?)
+            }
+            (TYPE::MEDIA_DEVICE_PATH, MEDIA::FILE_PATH) => {
+                DevicePath::FilePath(FilePath::parse(buf)?)
+            }
+            (TYPE::MEDIA_DEVICE_PATH, MEDIA::PIWG_FIRMWARE_FILE) => {
+                DevicePath::FirmwareFile(FirmwareFile::parse(buf)?)
+            }
+            (TYPE::MEDIA_DEVICE_PATH, MEDIA::PIWG_FIRMWARE_VOLUME) => {
+                DevicePath::FirmwareVolume(FirmwareVolume::parse(buf)?)
+            }
+            (TYPE::MEDIA_DEVICE_PATH, MEDIA::RELATIVE_OFFSET_RANGE) => {
+                DevicePath::RelativeOffsetRange(RelativeOffsetRange::parse(buf)?)
+            }
+            (TYPE::MEDIA_DEVICE_PATH, MEDIA::RAM_DISK_DEVICE_PATH) => {
+                DevicePath::RamDisk(RamDisk::parse(buf)?)
+            }
+            (TYPE::BIOS_BOOT_SPECIFICATION_DEVICE_PATH, BBS::BBS_1_01) => {
+                DevicePath::Bbs(Bbs::parse(buf)?)
+            }
+            (TYPE::END_OF_HARDWARE_DEVICE_PATH, END::END_THIS_INSTANCE_OF_DEVICE_PATH) => {
+                DevicePath::EndInstance
+            }
+            (TYPE::END_OF_HARDWARE_DEVICE_PATH, END::END_ENTIRE_DEVICE_PATH) => {
+                DevicePath::EndEntire
+            }
+            _ => return Ok(None),
         };
 
-        Ok(None)
+        Ok(Some(node))
     }
-}
-
-fn encap_as_device_path(r#type: u8, r#subtype: u8, mut raw_data: Vec<u8>) -> Vec<u8> {
-    let mut bytes: Vec<u8> = vec![];
-
-    bytes.push_u8(r#type);
-    bytes.push_u8(r#subtype);
-
-    let raw_data_size: u16 = raw_data.len().try_into().expect("length should fit in u16");
-    bytes.push_u16(raw_data_size + 1 + 1 + 2);
 
-    bytes.append(&mut raw_data);
+    /// get the type and subtype of this node
+    pub fn type_and_subtype(&self) -> (u8, u8) {
+        use consts::{
+            ACPI_DEVICE_PATH_SUBTYPE as ACPI, BIOS_BOOT_SPECIFICATION_DEVICE_PATH_SUBTYPE as BBS,
+            DEVICE_PATH_TYPE as TYPE, END_OF_HARDWARE_DEVICE_PATH_SUBTYPE as END,
+            HARDWARE_DEVICE_PATH_SUBTYPE as HW, MEDIA_DEVICE_PATH_SUBTYPE as MEDIA,
+            MESSAGING_DEVICE_PATH_SUBTYPE as MSG,
+        };
 
-    bytes
-}
+        match self {
+            DevicePath::Pci(_) => (TYPE::HARDWARE_DEVICE_PATH, HW::PCI),
+            DevicePath::MemoryMapped(_) => (TYPE::HARDWARE_DEVICE_PATH, HW::MEMORY_MAPPED),
+            DevicePath::HardwareVendor(_) => (TYPE::HARDWARE_DEVICE_PATH, HW::VENDOR),
+            DevicePath::Controller(_) => (TYPE::HARDWARE_DEVICE_PATH, HW::CONTROLLER),
+            DevicePath::Acpi(_) => (TYPE::ACPI_DEVICE_PATH, ACPI::ACPI),
+            DevicePath::ExpandedAcpi(_) => (TYPE::ACPI_DEVICE_PATH, ACPI::EXPANDED_ACPI),
+            DevicePath::AcpiAdr(_) => (TYPE::ACPI_DEVICE_PATH, ACPI::ADR),
+            DevicePath::Usb(_) => (TYPE::MESSAGING_DEVICE_PATH, MSG::USB),
+            DevicePath::Sata(_) => (TYPE::MESSAGING_DEVICE_PATH, MSG::SATA),
+            DevicePath::Nvme(_) => (TYPE::MESSAGING_DEVICE_PATH, MSG::NVME_NAMESPACE),
+            DevicePath::Scsi(_) => (TYPE::MESSAGING_DEVICE_PATH, MSG::SCSI),
+            DevicePath::MacAddr(_) => (TYPE::MESSAGING_DEVICE_PATH, MSG::MAC_ADDRESS),
+            DevicePath::Ipv4(_) => (TYPE::MESSAGING_DEVICE_PATH, MSG::IPV4),
+            DevicePath::Ipv6(_) => (TYPE::MESSAGING_DEVICE_PATH, MSG::IPV6),
+            DevicePath::Uri(_) => (TYPE::MESSAGING_DEVICE_PATH, MSG::URI),
+            DevicePath::MessagingVendor(_) => (TYPE::MESSAGING_DEVICE_PATH, MSG::VENDOR),
+            DevicePath::HardDrive(_) => (TYPE::MEDIA_DEVICE_PATH, MEDIA::HARD_DRIVE),
+            DevicePath::CdRom(_) => (TYPE::MEDIA_DEVICE_PATH, MEDIA::CD_ROM),
+            DevicePath::MediaVendor(_) => (TYPE::MEDIA_DEVICE_PATH, MEDIA::VENDOR),
+            DevicePath::FilePath(_) => (TYPE::MEDIA_DEVICE_PATH, MEDIA::FILE_PATH),
+            DevicePath::FirmwareFile(_) => (TYPE::MEDIA_DEVICE_PATH, MEDIA::PIWG_FIRMWARE_FILE),
+            DevicePath::FirmwareVolume(_) => (TYPE::MEDIA_DEVICE_PATH, MEDIA::PIWG_FIRMWARE_VOLUME),
+            DevicePath::RelativeOffsetRange(_) => {
+                (TYPE::MEDIA_DEVICE_PATH, MEDIA::RELATIVE_OFFSET_RANGE)
+            }
+            DevicePath::RamDisk(_) => (TYPE::MEDIA_DEVICE_PATH, MEDIA::RAM_DISK_DEVICE_PATH),
+            DevicePath::Bbs(_) => (TYPE::BIOS_BOOT_SPECIFICATION_DEVICE_PATH, BBS::BBS_1_01),
+            DevicePath::EndInstance => (
+                TYPE::END_OF_HARDWARE_DEVICE_PATH,
+                END::END_THIS_INSTANCE_OF_DEVICE_PATH,
+            ),
+            DevicePath::EndEntire => (
+                TYPE::END_OF_HARDWARE_DEVICE_PATH,
+                END::END_ENTIRE_DEVICE_PATH,
+            ),
+            DevicePath::Unknown {
+                r#type, subtype, ..
+            } => (*r#type, *subtype),
+        }
+    }
 
-impl EFIHardDrive {
-    /// get bytes representation for a EFIHardDrive, without encapsulating them in a DevicePath structure
+    /// get bytes representation for the data of this node, without the node header
     fn to_bytes_raw(&self) -> Vec<u8> {
-        let mut bytes: Vec<u8> = vec![];
-        bytes.push_u32(self.partition_number);
-        bytes.push_u64(self.partition_start);
-        bytes.push_u64(self.partition_size);
-
-        let (f1, f2, f3, f4) = self.partition_sig.as_fields();
-        bytes.push_u32(f1);
-        bytes.push_u16(f2);
-        bytes.push_u16(f3);
-        bytes.append(&mut f4.to_vec());
-        bytes.push_u8(self.format);
-        bytes.push_u8(self.sig_type.as_u8());
-
-        bytes
+        match self {
+            DevicePath::Pci(node) => node.to_bytes_raw(),
+            DevicePath::MemoryMapped(node) => node.to_bytes_raw(),
+            DevicePath::HardwareVendor(node) => node.to_bytes_raw(),
+            DevicePath::Controller(node) => node.to_bytes_raw(),
+            DevicePath::Acpi(node) => node.to_bytes_raw(),
+            DevicePath::ExpandedAcpi(node) => node.to_bytes_raw(),
+            DevicePath::AcpiAdr(node) => node.to_bytes_raw(),
+            DevicePath::Usb(node) => node.to_bytes_raw(),
+            DevicePath::Sata(node) => node.to_bytes_raw(),
+            DevicePath::Nvme(node) => node.to_bytes_raw(),
+            DevicePath::Scsi(node) => node.to_bytes_raw(),
+            DevicePath::MacAddr(node) => node.to_bytes_raw(),
+            DevicePath::Ipv4(node) => node.to_bytes_raw(),
+            DevicePath::Ipv6(node) => node.to_bytes_raw(),
+            DevicePath::Uri(node) => node.to_bytes_raw(),
+            DevicePath::MessagingVendor(node) => node.to_bytes_raw(),
+            DevicePath::HardDrive(node) => node.to_bytes_raw(),
+            DevicePath::CdRom(node) => node.to_bytes_raw(),
+            DevicePath::MediaVendor(node) => node.to_bytes_raw(),
+            DevicePath::FilePath(node) => node.to_bytes_raw(),
+            DevicePath::FirmwareFile(node) => node.to_bytes_raw(),
+            DevicePath::FirmwareVolume(node) => node.to_bytes_raw(),
+            DevicePath::RelativeOffsetRange(node) => node.to_bytes_raw(),
+            DevicePath::RamDisk(node) => node.to_bytes_raw(),
+            DevicePath::Bbs(node) => node.to_bytes_raw(),
+            DevicePath::EndInstance | DevicePath::EndEntire => vec![],
+            DevicePath::Unknown { data, .. } => data.clone(),
+        }
     }
 
-    /// get bytes representation for a EFIHardDrive, as a DevicePath (EFI_DEVICE_PATH_PROTOCOL) structure
-    pub fn to_bytes_encap(&self) -> Vec<u8> {
-        encap_as_device_path(
-            consts::DEVICE_PATH_TYPE::MEDIA_DEVICE_PATH,
-            consts::MEDIA_DEVICE_PATH_SUBTYPE::HARD_DRIVE,
-            self.to_bytes_raw(),
-        )
+    /// get bytes representation for this node, as a DevicePath (EFI_DEVICE_PATH_PROTOCOL) structure
+    pub fn to_bytes(&self) -> crate::Result<Vec<u8>> {
+        let (r#type, subtype) = self.type_and_subtype();
+        encap_as_device_path(r#type, subtype, self.to_bytes_raw())
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
-pub struct FilePath {
-    /// the UEFI standard seem to use UCS-2 strings (a subset of UTF-16), so Rust UTF8 strings should be able to represent them
-    pub path: String,
-}
-
-impl FilePath {
-    /// get bytes representation for a FilePath, without encapsulating them in a DevicePath structure
-    fn to_bytes_raw(&self) -> Vec<u8> {
-        let utf16_bytes = self.path.encode_utf16();
-        let mut utf8_bytes: Vec<u8> = utf16_bytes
-            .into_iter()
-            .flat_map(|var| var.to_le_bytes())
-            .collect();
+fn encap_as_device_path(
+    r#type: u8,
+    r#subtype: u8,
+    mut raw_data: Vec<u8>,
+) -> crate::Result<Vec<u8>> {
+    let mut bytes: Vec<u8> = vec![];
 
-        // write null termination
-        utf8_bytes.push_u16(0x0000);
+    bytes.push_u8(r#type);
+    bytes.push_u8(r#subtype);
 
-        utf8_bytes
-    }
+    let node_size =
+        u16::try_from(raw_data.len() + 1 + 1 + 2).map_err(|_| Error::ValueTooLarge {
+            what: "device path node",
+        })?;
+    bytes.push_u16(node_size);
 
-    /// get bytes representation for a FilePath, as a DevicePath (EFI_DEVICE_PATH_PROTOCOL) structure
-    pub fn to_bytes_encap(&self) -> Vec<u8> {
-        encap_as_device_path(
-            consts::DEVICE_PATH_TYPE::MEDIA_DEVICE_PATH,
-            consts::MEDIA_DEVICE_PATH_SUBTYPE::FILE_PATH,
-            self.to_bytes_raw(),
-        )
-    }
-}
+    bytes.append(&mut raw_data);
 
-pub fn get_end_device_path_bytes() -> Vec<u8> {
-    encap_as_device_path(
-        consts::DEVICE_PATH_TYPE::END_OF_HARDWARE_DEVICE_PATH,
-        consts::END_OF_HARDWARE_DEVICE_PATH_SUBTYPE::END_ENTIRE_DEVICE_PATH,
-        vec![],
-    )
+    Ok(bytes)
 }
 
 #[cfg(test)]
 mod tests {
-    use std::str::FromStr;
+    use std::{net::Ipv4Addr, str::FromStr};
 
     use uuid::Uuid;
 
-    use super::{EFIHardDrive, EFIHardDriveType};
+    use super::{
+        DevicePath, EFIHardDrive, FirmwareFile, Ipv4, MacAddr, ParseCursor, PartitionSignature,
+        Pci, VendorDevicePath,
+    };
+
+    /// Device path nodes of common boot entries, written by hand from the layouts of the
+    /// specification
+    const NODES: &[&[u8]] = &[
+        // PciRoot(0x0)
+        &[
+            0x02, 0x01, 0x0C, 0x00, 0xD0, 0x41, 0x03, 0x0A, 0x00, 0x00, 0x00, 0x00,
+        ],
+        // Pci(0x1D,0x0)
+        &[0x01, 0x01, 0x06, 0x00, 0x00, 0x1D],
+        // MemoryMapped(0xB,0xFF000000,0xFFFFFFFF)
+        &[
+            0x01, 0x03, 0x18, 0x00, 0x0B, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0x00, 0x00,
+            0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00,
+        ],
+        // Ctrl(0x0)
+        &[0x01, 0x05, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00],
+        // AcpiEx(PNP0A08,PNP0A03,0x0,,,)
+        &[
+            0x02, 0x02, 0x13, 0x00, 0xD0, 0x41, 0x08, 0x0A, 0x00, 0x00, 0x00, 0x00, 0xD0, 0x41,
+            0x03, 0x0A, 0x00, 0x00, 0x00,
+        ],
+        // AcpiAdr(0x80010100)
+        &[0x02, 0x03, 0x08, 0x00, 0x00, 0x01, 0x01, 0x80],
+        // USB(0x3,0x0)
+        &[0x03, 0x05, 0x06, 0x00, 0x03, 0x00],
+        // Sata(0x0,0xFFFF,0x0)
+        &[0x03, 0x12, 0x0A, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0x00, 0x00],
+        // NVMe(0x1,00-25-38-5B-71-B0-9C-38)
+        &[
+            0x03, 0x17, 0x10, 0x00, 0x01, 0x00, 0x00, 0x00, 0x38, 0x9C, 0xB0, 0x71, 0x5B, 0x38,
+            0x25, 0x00,
+        ],
+        // Scsi(0x0,0x0)
+        &[0x03, 0x02, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00],
+        // MAC(525400123456,0x1)
+        &[
+            0x03, 0x0B, 0x25, 0x00, 0x52, 0x54, 0x00, 0x12, 0x34, 0x56, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+        ],
+        // IPv4(0.0.0.0,0x0,DHCP,0.0.0.0,0.0.0.0,0.0.0.0)
+        &[
+            0x03, 0x0C, 0x1B, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ],
+        // IPv6(::,0x0,Static,::,0x40,::)
+        &[
+            0x03, 0x0D, 0x3C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ],
+        // Uri(http://boot/a.efi)
+        &[
+            0x03, 0x18, 0x15, 0x00, 0x68, 0x74, 0x74, 0x70, 0x3A, 0x2F, 0x2F, 0x62, 0x6F, 0x6F,
+            0x74, 0x2F, 0x61, 0x2E, 0x65, 0x66, 0x69,
+        ],
+        // VenPcAnsi()
+        &[
+            0x03, 0x0A, 0x14, 0x00, 0x53, 0x47, 0xC1, 0xE0, 0xBE, 0xF9, 0xD2, 0x11, 0x9A, 0x0C,
+            0x00, 0x90, 0x27, 0x3F, 0xC1, 0x4D,
+        ],
+        // CDROM(0x1,0x2B4,0x1800)
+        &[
+            0x04, 0x02, 0x18, 0x00, 0x01, 0x00, 0x00, 0x00, 0xB4, 0x02, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ],
+        // VenMedia(5568e427-68fc-4f3d-ac74-ca555231cc68,0100) (linux initrd with payload)
+        &[
+            0x04, 0x03, 0x16, 0x00, 0x27, 0xE4, 0x68, 0x55, 0xFC, 0x68, 0x3D, 0x4F, 0xAC, 0x74,
+            0xCA, 0x55, 0x52, 0x31, 0xCC, 0x68, 0x01, 0x00,
+        ],
+        // Fv(7cb8bdc9-f8eb-4f34-aaea-3ee4af6516a1)
+        &[
+            0x04, 0x07, 0x14, 0x00, 0xC9, 0xBD, 0xB8, 0x7C, 0xEB, 0xF8, 0x34, 0x4F, 0xAA, 0xEA,
+            0x3E, 0xE4, 0xAF, 0x65, 0x16, 0xA1,
+        ],
+        // FvFile(462caa21-7614-4503-836e-8ab6f4662331)
+        &[
+            0x04, 0x06, 0x14, 0x00, 0x21, 0xAA, 0x2C, 0x46, 0x14, 0x76, 0x03, 0x45, 0x83, 0x6E,
+            0x8A, 0xB6, 0xF4, 0x66, 0x23, 0x31,
+        ],
+        // Offset(0x1000,0x1FFF)
+        &[
+            0x04, 0x08, 0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0xFF, 0x1F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ],
+        // VirtualDisk(0x10000000,0x1FFFFFFF,0)
+        &[
+            0x04, 0x09, 0x26, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF,
+            0xFF, 0x1F, 0x00, 0x00, 0x00, 0x00, 0x5A, 0x53, 0xAB, 0x77, 0xFC, 0x45, 0x4B, 0x62,
+            0x55, 0x60, 0xF7, 0xB2, 0x81, 0xD1, 0xF9, 0x6E, 0x00, 0x00,
+        ],
+        // \EFI\BOOT\BOOTX64.EFI
+        &[
+            0x04, 0x04, 0x30, 0x00, 0x5C, 0x00, 0x45, 0x00, 0x46, 0x00, 0x49, 0x00, 0x5C, 0x00,
+            0x42, 0x00, 0x4F, 0x00, 0x4F, 0x00, 0x54, 0x00, 0x5C, 0x00, 0x42, 0x00, 0x4F, 0x00,
+            0x4F, 0x00, 0x54, 0x00, 0x58, 0x00, 0x36, 0x00, 0x34, 0x00, 0x2E, 0x00, 0x45, 0x00,
+            0x46, 0x00, 0x49, 0x00, 0x00, 0x00,
+        ],
+        // end of instance / end of entire device path
+        &[0x7F, 0x01, 0x04, 0x00],
+        &[0x7F, 0xFF, 0x04, 0x00],
+        // BBS(0x80,IBA GE Slot 0100,0x0) padded with an extra null byte, kept as-is
+        &[
+            0x05, 0x01, 0x1A, 0x00, 0x80, 0x00, 0x00, 0x00, 0x49, 0x42, 0x41, 0x20, 0x47, 0x45,
+            0x20, 0x53, 0x6C, 0x6F, 0x74, 0x20, 0x30, 0x31, 0x30, 0x30, 0x00, 0x00,
+        ],
+    ];
 
     #[test]
-    fn efi_hard_drive_type_parse() {
-        assert_eq!(EFIHardDriveType::parse(0x01), EFIHardDriveType::Mbr);
-        assert_eq!(EFIHardDriveType::parse(0x02), EFIHardDriveType::Gpt);
-        assert_eq!(EFIHardDriveType::parse(0x03), EFIHardDriveType::Unknown);
-        assert_eq!(EFIHardDriveType::parse(0xFF), EFIHardDriveType::Unknown);
+    fn nodes_roundtrip() {
+        for node in NODES {
+            let mut buf = ParseCursor::new(node);
+            let parsed = DevicePath::parse(&mut buf).unwrap();
+            assert!(buf.is_empty(), "{:?} did not consume its data", parsed);
+            assert_eq!(
+                &parsed.to_bytes().unwrap(),
+                node,
+                "{:?} did not roundtrip",
+                parsed
+            );
+        }
     }
 
     #[test]
-    fn efi_hard_drive_type_dump() {
-        assert_eq!(EFIHardDriveType::Mbr.as_u8(), 0x01);
-        assert_eq!(EFIHardDriveType::Gpt.as_u8(), 0x02);
+    fn nodes_are_known() {
+        // every node in the corpus except the padded BBS one is modeled
+        for node in &NODES[..NODES.len() - 1] {
+            let parsed = DevicePath::parse(&mut ParseCursor::new(node)).unwrap();
+            assert!(
+                !matches!(parsed, DevicePath::Unknown { .. }),
+                "{:02X?} parsed as unknown",
+                node
+            );
+        }
     }
 
     #[test]
-    fn efi_hard_drive_type_print() {
-        assert_eq!(format!("{}", EFIHardDriveType::Mbr), "MBR");
-        assert_eq!(format!("{}", EFIHardDriveType::Gpt), "GPT");
-        assert_eq!(format!("{}", EFIHardDriveType::Unknown), "Unknown");
+    fn parse_fields() {
+        assert_eq!(
+            DevicePath::parse(&mut ParseCursor::new(NODES[1])).unwrap(),
+            DevicePath::Pci(Pci {
+                function: 0,
+                device: 0x1D
+            })
+        );
+        assert_eq!(
+            DevicePath::parse(&mut ParseCursor::new(NODES[10])).unwrap(),
+            DevicePath::MacAddr(MacAddr::from_ethernet([0x52, 0x54, 0x00, 0x12, 0x34, 0x56]))
+        );
+        assert_eq!(
+            DevicePath::parse(&mut ParseCursor::new(NODES[11])).unwrap(),
+            DevicePath::Ipv4(Ipv4 {
+                local_address: Ipv4Addr::UNSPECIFIED,
+                remote_address: Ipv4Addr::UNSPECIFIED,
+                local_port: 0,
+                remote_port: 0,
+                protocol: 0,
+                static_address: false,
+                gateway_address: Ipv4Addr::UNSPECIFIED,
+                subnet_mask: Ipv4Addr::UNSPECIFIED,
+            })
+        );
+        assert_eq!(
+            DevicePath::parse(&mut ParseCursor::new(NODES[18])).unwrap(),
+            DevicePath::FirmwareFile(FirmwareFile {
+                name: Uuid::from_str("462caa21-7614-4503-836e-8ab6f4662331").unwrap()
+            })
+        );
     }
 
     #[test]
-    #[should_panic]
-    fn efi_hard_drive_type_dump_invalid() {
-        EFIHardDriveType::Unknown.as_u8();
+    fn unexpected_layout_is_unknown() {
+        // PCI node with a trailing byte: keep it as-is
+        let data = [0x01, 0x01, 0x07, 0x00, 0x00, 0x1D, 0xAA];
+        let parsed = DevicePath::parse(&mut ParseCursor::new(&data)).unwrap();
+        assert_eq!(
+            parsed,
+            DevicePath::Unknown {
+                r#type: 0x01,
+                subtype: 0x01,
+                data: vec![0x00, 0x1D, 0xAA]
+            }
+        );
+        assert_eq!(parsed.to_bytes().unwrap(), data);
     }
 
     #[test]
-    fn print_hard_drive() {
-        assert_eq!(
-            "HD(1,GPT,90364bbd-1000-47fc-8c05-8707e01b4593)",
-            format!(
-                "{}",
-                EFIHardDrive {
-                    partition_number: 1,
-                    partition_start: 2,
-                    partition_size: 3,
-                    partition_sig: Uuid::from_str("90364bbd-1000-47fc-8c05-8707e01b4593").unwrap(),
-                    format: 5,
-                    sig_type: EFIHardDriveType::Gpt,
-                }
-            )
-        );
+    fn hard_drive_unknown_signature() {
+        let mut data = vec![0x04, 0x01, 0x2A, 0x00];
+        data.extend_from_slice(&[0x00; 20]);
+        data.extend_from_slice(&[0xAA; 16]);
+        data.push(0x02); // format
+        data.push(0x03); // unknown signature type
+        let parsed = DevicePath::parse(&mut ParseCursor::new(&data)).unwrap();
+        assert!(matches!(
+            parsed,
+            DevicePath::HardDrive(EFIHardDrive {
+                partition_sig: PartitionSignature::Unknown { sig_type: 0x03, .. },
+                ..
+            })
+        ));
+        assert_eq!(parsed.to_bytes().unwrap(), data);
     }
 
     #[test]
-    fn to_from_bytes() {
-        let drive = EFIHardDrive {
-            partition_number: 1,
-            partition_start: 2,
-            partition_size: 3,
-            partition_sig: Uuid::from_str("90364bbd-1000-47fc-8c05-8707e01b4593").unwrap(),
-            format: 5,
-            sig_type: EFIHardDriveType::Gpt,
-        };
-        let bytes = drive.to_bytes_raw();
-        let mut x = bytes.as_slice();
-        let test_parse = EFIHardDrive::parse(&mut x).unwrap();
-        assert_eq!(drive, test_parse)
+    fn vendor_to_bytes() {
+        let node = DevicePath::HardwareVendor(VendorDevicePath {
+            guid: Uuid::from_str("e0c14753-f9be-11d2-9a0c-0090273fc14d").unwrap(),
+            data: vec![0x01, 0x02],
+        });
+        let bytes = node.to_bytes().unwrap();
+        assert_eq!(&bytes[..4], &[0x01, 0x04, 0x16, 0x00]);
+        assert_eq!(
+            DevicePath::parse(&mut ParseCursor::new(&bytes)).unwrap(),
+            node
+        );
     }
 }
@@ -0,0 +1,92 @@
+//! ACPI device path nodes (type 0x02)
+
+use crate::push::PushVecU8;
+
+use super::ParseCursor;
+
+#[derive(Debug, PartialEq, Clone)]
+// See spec, 10.3.3
+pub struct Acpi {
+    pub hid: u32,
+    pub uid: u32,
+}
+
+impl Acpi {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<Acpi> {
+        Ok(Acpi {
+            hid: buf.read_u32("Acpi.hid")?,
+            uid: buf.read_u32("Acpi.uid")?,
+        })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+        bytes.push_u32(self.hid);
+        bytes.push_u32(self.uid);
+
+        bytes
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+// See spec, 10.3.3, expanded ACPI device path
+pub struct ExpandedAcpi {
+    pub hid: u32,
+    pub uid: u32,
+    pub cid: u32,
+    pub hid_str: String,
+    pub uid_str: String,
+    pub cid_str: String,
+}
+
+impl ExpandedAcpi {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<ExpandedAcpi> {
+        Ok(ExpandedAcpi {
+            hid: buf.read_u32("ExpandedAcpi.hid")?,
+            uid: buf.read_u32("ExpandedAcpi.uid")?,
+            cid: buf.read_u32("ExpandedAcpi.cid")?,
+            hid_str: buf.read_nt_ascii_string("ExpandedAcpi.hid_str")?,
+            uid_str: buf.read_nt_ascii_string("ExpandedAcpi.uid_str")?,
+            cid_str: buf.read_nt_ascii_string("ExpandedAcpi.cid_str")?,
+        })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+        bytes.push_u32(self.hid);
+        bytes.push_u32(self.uid);
+        bytes.push_u32(self.cid);
+        for string in [&self.hid_str, &self.uid_str, &self.cid_str] {
+            bytes.extend_from_slice(string.as_bytes());
+            bytes.push_u8(0x00);
+        }
+
+        bytes
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+// See spec, 10.3.3.1
+pub struct AcpiAdr {
+    pub adr: Vec<u32>,
+}
+
+impl AcpiAdr {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<AcpiAdr> {
+        let mut adr = vec![buf.read_u32("AcpiAdr.adr")?];
+        while !buf.is_empty() {
+            adr.push(buf.read_u32("AcpiAdr.adr")?);
+        }
+
+        Ok(AcpiAdr { adr })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+        for adr in &self.adr {
+            bytes.push_u32(*adr);
+        }
+
+        bytes
+    }
+}
@@ -0,0 +1,33 @@
+//! BIOS Boot Specification device path nodes (type 0x05)
+
+use crate::push::PushVecU8;
+
+use super::ParseCursor;
+
+#[derive(Debug, PartialEq, Clone)]
+/// Legacy (CSM) boot device. See spec, 10.3.7
+pub struct Bbs {
+    pub device_type: u16,
+    pub status_flag: u16,
+    pub description: String,
+}
+
+impl Bbs {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<Bbs> {
+        Ok(Bbs {
+            device_type: buf.read_u16("Bbs.device_type")?,
+            status_flag: buf.read_u16("Bbs.status_flag")?,
+            description: buf.read_nt_ascii_string("Bbs.description")?,
+        })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+        bytes.push_u16(self.device_type);
+        bytes.push_u16(self.status_flag);
+        bytes.extend_from_slice(self.description.as_bytes());
+        bytes.push_u8(0x00);
+
+        bytes
+    }
+}
@@ -0,0 +1,754 @@
+//! Parsing of the device path text representation, the reverse of [`super::text`]
+
+use std::{
+    convert::{TryFrom, TryInto},
+    net::{Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+use uuid::Uuid;
+
+use crate::Error;
+
+use super::{
+    text::{
+        PC_ANSI_GUID, PERSISTENT_VIRTUAL_CD_GUID, PERSISTENT_VIRTUAL_DISK_GUID, PNP_EISA_ID,
+        VIRTUAL_CD_GUID, VIRTUAL_DISK_GUID, VT_100_GUID, VT_100_PLUS_GUID, VT_UTF8_GUID,
+    },
+    Acpi, AcpiAdr, Bbs, CdRom, Controller, DevicePath, EFIHardDrive, ExpandedAcpi, FilePath,
+    FirmwareFile, FirmwareVolume, Ipv4, Ipv6, MacAddr, MemoryMapped, Nvme, ParseCursor,
+    PartitionSignature, Pci, RamDisk, RelativeOffsetRange, Sata, Scsi, Uri, Usb, VendorDevicePath,
+};
+
+fn text_error(text: &str, reason: impl Into<String>) -> Error {
+    Error::DevicePathTextError {
+        text: text.to_owned(),
+        reason: reason.into(),
+    }
+}
+
+/// arguments of a node, such as `0x1D,0x0` in `Pci(0x1D,0x0)`
+struct Args<'a> {
+    node: &'a str,
+    args: std::vec::IntoIter<&'a str>,
+}
+
+impl<'a> Args<'a> {
+    fn new(node: &'a str, args: &'a str) -> Args<'a> {
+        let args: Vec<&str> = if args.is_empty() {
+            vec![]
+        } else {
+            args.split(',').map(str::trim).collect()
+        };
+
+        Args {
+            node,
+            args: args.into_iter(),
+        }
+    }
+
+    fn error(&self, reason: impl Into<String>) -> Error {
+        text_error(self.node, reason)
+    }
+
+    fn next(&mut self) -> crate::Result<&'a str> {
+        let node = self.node;
+        self.args
+            .next()
+            .ok_or_else(|| text_error(node, "missing argument"))
+    }
+
+    /// parse a number, written in hexadecimal if it starts with `0x`, in decimal otherwise
+    fn int<T: TryFrom<u64>>(&mut self) -> crate::Result<T> {
+        let arg = self.next()?;
+        let value = match arg.strip_prefix("0x").or_else(|| arg.strip_prefix("0X")) {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => arg.parse::<u64>(),
+        }
+        .map_err(|_| self.error(format!("invalid number '{arg}'")))?;
+
+        value
+            .try_into()
+            .map_err(|_| self.error(format!("number '{arg}' is too large")))
+    }
+
+    /// like [`Args::int`], but gives a default value if the argument is missing
+    fn int_or<T: TryFrom<u64>>(&mut self, default: T) -> crate::Result<T> {
+        if self.args.as_slice().is_empty() {
+            Ok(default)
+        } else {
+            self.int()
+        }
+    }
+
+    fn guid(&mut self) -> crate::Result<Uuid> {
+        let arg = self.next()?;
+        Uuid::from_str(arg).map_err(|_| self.error(format!("invalid GUID '{arg}'")))
+    }
+
+    fn hex(&mut self) -> crate::Result<Vec<u8>> {
+        let arg = self.next()?;
+        parse_hex_bytes(arg).ok_or_else(|| self.error(format!("invalid hex string '{arg}'")))
+    }
+
+    /// parse an EISA id, either as `PNP0A03` or as a number
+    fn eisa_id(&mut self) -> crate::Result<u32> {
+        let arg = *self
+            .args
+            .as_slice()
+            .first()
+            .ok_or_else(|| self.error("missing argument"))?;
+        let bytes = arg.as_bytes();
+        if bytes.len() == 7 && bytes[..3].iter().all(|c| (b'@'..=b'Z').contains(c)) {
+            if let Ok(num) = u32::from_str_radix(&arg[3..], 16) {
+                self.next()?;
+                let letter = |c: u8| u32::from(c - b'@');
+                return Ok((num << 16)
+                    | (letter(bytes[0]) << 10)
+                    | (letter(bytes[1]) << 5)
+                    | letter(bytes[2]));
+            }
+        }
+
+        self.int()
+    }
+
+    fn ipv4(&mut self) -> crate::Result<Ipv4Addr> {
+        let arg = self.next()?;
+        Ipv4Addr::from_str(arg).map_err(|_| self.error(format!("invalid IPv4 address '{arg}'")))
+    }
+
+    fn ipv6(&mut self) -> crate::Result<Ipv6Addr> {
+        let arg = self.next()?;
+        Ipv6Addr::from_str(arg).map_err(|_| self.error(format!("invalid IPv6 address '{arg}'")))
+    }
+
+    fn protocol(&mut self) -> crate::Result<u16> {
+        match self.args.as_slice().first() {
+            Some(&"TCP") => self.next().map(|_| 6),
+            Some(&"UDP") => self.next().map(|_| 17),
+            _ => self.int(),
+        }
+    }
+
+    fn vendor(&mut self) -> crate::Result<VendorDevicePath> {
+        let guid = self.guid()?;
+        let data = if self.args.as_slice().is_empty() {
+            vec![]
+        } else {
+            self.hex()?
+        };
+
+        Ok(VendorDevicePath { guid, data })
+    }
+}
+
+fn parse_hex_bytes(hex: &str) -> Option<Vec<u8>> {
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            if pair.len() != 2 || !pair.iter().all(u8::is_ascii_hexdigit) {
+                return None;
+            }
+            u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()
+        })
+        .collect()
+}
+
+fn pnp_acpi(num: u32, args: &mut Args) -> crate::Result<DevicePath> {
+    Ok(DevicePath::Acpi(Acpi {
+        hid: (num << 16) | PNP_EISA_ID,
+        uid: args.int()?,
+    }))
+}
+
+fn messaging_vendor(guid: Uuid) -> DevicePath {
+    DevicePath::MessagingVendor(VendorDevicePath { guid, data: vec![] })
+}
+
+fn ram_disk(disk_type: Option<Uuid>, args: &mut Args) -> crate::Result<DevicePath> {
+    let starting_address = args.int()?;
+    let ending_address = args.int()?;
+    let instance = args.int()?;
+    let disk_type = match disk_type {
+        Some(disk_type) => disk_type,
+        None => args.guid()?,
+    };
+
+    Ok(DevicePath::RamDisk(RamDisk {
+        starting_address,
+        ending_address,
+        disk_type,
+        instance,
+    }))
+}
+
+fn hard_drive(args: &mut Args) -> crate::Result<DevicePath> {
+    let partition_number = args.int()?;
+    let partition_sig = match args.args.as_slice().first() {
+        Some(&"GPT") => {
+            args.next()?;
+            PartitionSignature::Gpt(args.guid()?)
+        }
+        Some(&"MBR") => {
+            args.next()?;
+            PartitionSignature::Mbr(args.int()?)
+        }
+        // other signature types are given as a number, followed by an ignored signature
+        _ => {
+            let sig_type = args.int()?;
+            args.next()?;
+            PartitionSignature::parse(sig_type, [0; 16])
+        }
+    };
+    let format = match partition_sig {
+        PartitionSignature::Mbr(_) => 0x01,
+        _ => 0x02,
+    };
+
+    Ok(DevicePath::HardDrive(EFIHardDrive {
+        partition_number,
+        partition_start: args.int_or(0)?,
+        partition_size: args.int_or(0)?,
+        partition_sig,
+        format,
+    }))
+}
+
+fn expanded_acpi(args: &mut Args) -> crate::Result<DevicePath> {
+    Ok(DevicePath::ExpandedAcpi(ExpandedAcpi {
+        hid: args.eisa_id()?,
+        cid: args.eisa_id()?,
+        uid: args.int()?,
+        hid_str: args.next()?.to_owned(),
+        cid_str: args.next()?.to_owned(),
+        uid_str: args.next()?.to_owned(),
+    }))
+}
+
+fn expanded_acpi_short(args: &mut Args) -> crate::Result<DevicePath> {
+    let hid = args.eisa_id()?;
+    let cid = if args.args.as_slice().first() == Some(&"0") {
+        args.next().map(|_| 0)?
+    } else {
+        args.eisa_id()?
+    };
+
+    Ok(DevicePath::ExpandedAcpi(ExpandedAcpi {
+        hid,
+        uid: 0,
+        cid,
+        hid_str: String::new(),
+        uid_str: args.next()?.to_owned(),
+        cid_str: String::new(),
+    }))
+}
+
+fn mac(args: &mut Args) -> crate::Result<DevicePath> {
+    let mac = args.hex()?;
+    if mac.len() > 32 {
+        return Err(args.error("MAC address is too long"));
+    }
+    let mut address = [0u8; 32];
+    address[..mac.len()].copy_from_slice(&mac);
+
+    Ok(DevicePath::MacAddr(MacAddr {
+        address,
+        if_type: args.int_or(0)?,
+    }))
+}
+
+fn ipv4(args: &mut Args) -> crate::Result<DevicePath> {
+    let remote_address = args.ipv4()?;
+    let mut ip = Ipv4 {
+        local_address: Ipv4Addr::UNSPECIFIED,
+        remote_address,
+        local_port: 0,
+        remote_port: 0,
+        protocol: 0,
+        static_address: false,
+        gateway_address: Ipv4Addr::UNSPECIFIED,
+        subnet_mask: Ipv4Addr::UNSPECIFIED,
+    };
+
+    // the display-only form only has the remote address
+    if !args.args.as_slice().is_empty() {
+        ip.protocol = args.protocol()?;
+        ip.static_address = match args.next()? {
+            "Static" => true,
+            "DHCP" => false,
+            origin => return Err(args.error(format!("invalid address origin '{origin}'"))),
+        };
+        ip.local_address = args.ipv4()?;
+        if !args.args.as_slice().is_empty() {
+            ip.gateway_address = args.ipv4()?;
+            ip.subnet_mask = args.ipv4()?;
+        }
+    }
+
+    Ok(DevicePath::Ipv4(ip))
+}
+
+fn ipv6(args: &mut Args) -> crate::Result<DevicePath> {
+    let remote_address = args.ipv6()?;
+    let mut ip = Ipv6 {
+        local_address: Ipv6Addr::UNSPECIFIED,
+        remote_address,
+        local_port: 0,
+        remote_port: 0,
+        protocol: 0,
+        address_origin: 0,
+        prefix_length: 0,
+        gateway_address: Ipv6Addr::UNSPECIFIED,
+    };
+
+    // the display-only form only has the remote address
+    if !args.args.as_slice().is_empty() {
+        ip.protocol = args.protocol()?;
+        ip.address_origin = match args.next()? {
+            "Static" => 0x00,
+            "StatelessAutoConfigure" => 0x01,
+            "StatefulAutoConfigure" => 0x02,
+            origin => return Err(args.error(format!("invalid address origin '{origin}'"))),
+        };
+        ip.local_address = args.ipv6()?;
+        if !args.args.as_slice().is_empty() {
+            ip.prefix_length = args.int()?;
+            ip.gateway_address = args.ipv6()?;
+        }
+    }
+
+    Ok(DevicePath::Ipv6(ip))
+}
+
+fn nvme(args: &mut Args) -> crate::Result<DevicePath> {
+    let namespace_id = args.int()?;
+    let eui64_text = args.next()?;
+    let mut eui64: Vec<u8> = parse_hex_bytes(&eui64_text.replace('-', ""))
+        .filter(|eui64| eui64.len() == 8)
+        .ok_or_else(|| args.error(format!("invalid EUI-64 '{eui64_text}'")))?;
+    // the text representation holds the bytes in reverse order
+    eui64.reverse();
+
+    Ok(DevicePath::Nvme(Nvme {
+        namespace_id,
+        eui64: eui64.try_into().expect("length was checked"),
+    }))
+}
+
+/// parse the arguments of a BBS node. The description is free text, kept as-is: it is everything
+/// between the device type and the status flag. In the display-only form, which has no status
+/// flag, a description ending with a comma and a number is read as a status flag
+fn bbs(text: &str, args: &str) -> crate::Result<DevicePath> {
+    let (device_type, rest) = args
+        .split_once(',')
+        .ok_or_else(|| text_error(text, "missing argument"))?;
+    let device_type = match device_type.trim() {
+        "Floppy" => 0x01,
+        "HD" => 0x02,
+        "CDROM" => 0x03,
+        "PCMCIA" => 0x04,
+        "USB" => 0x05,
+        "Network" => 0x06,
+        _ => Args::new(text, device_type).int()?,
+    };
+    let (description, status_flag) = match rest.rsplit_once(',') {
+        Some((description, flag)) => match Args::new(text, flag).int() {
+            Ok(status_flag) => (description, status_flag),
+            Err(_) => (rest, 0),
+        },
+        None => (rest, 0),
+    };
+    if !description.is_ascii() {
+        return Err(text_error(
+            text,
+            format!("invalid description '{description}'"),
+        ));
+    }
+
+    Ok(DevicePath::Bbs(Bbs {
+        device_type,
+        description: description.to_owned(),
+        status_flag,
+    }))
+}
+
+/// parse a node with a generic representation, such as `HardwarePath(1,001d)`
+fn unknown(r#type: u8, args: &mut Args) -> crate::Result<DevicePath> {
+    let subtype = args.int()?;
+    let data = if args.args.as_slice().is_empty() {
+        vec![]
+    } else {
+        args.hex()?
+    };
+    let node = DevicePath::Unknown {
+        r#type,
+        subtype,
+        data,
+    };
+
+    // go through the binary representation, in case this is a node we know about
+    DevicePath::parse(&mut ParseCursor::new(&node.to_bytes()?))
+}
+
+impl FromStr for DevicePath {
+    type Err = Error;
+
+    /// parse a single device path node from its text representation. Anything that is not in the
+    /// `Name(arguments)` form is considered to be a file path
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (name, args) = match text.find('(') {
+            Some(start)
+                if text.ends_with(')')
+                    && text[..start].chars().all(|c| c.is_ascii_alphanumeric()) =>
+            {
+                (&text[..start], &text[start + 1..text.len() - 1])
+            }
+            _ => {
+                return Ok(DevicePath::FilePath(FilePath {
+                    path: text.to_owned(),
+                }))
+            }
+        };
+
+        // free text arguments may contain commas, so they are taken as-is
+        if name == "BBS" {
+            return bbs(text, args);
+        }
+        if name == "Uri" {
+            return Ok(DevicePath::Uri(Uri {
+                uri: args.to_owned(),
+            }));
+        }
+        if name == "File" {
+            return Ok(DevicePath::FilePath(FilePath {
+                path: args.to_owned(),
+            }));
+        }
+
+        let args = &mut Args::new(text, args);
+        let node = match name {
+            // hardware device paths
+            "Pci" => {
+                let device = args.int()?;
+                DevicePath::Pci(Pci {
+                    function: args.int()?,
+                    device,
+                })
+            }
+            "MemoryMapped" => DevicePath::MemoryMapped(MemoryMapped {
+                memory_type: args.int()?,
+                start_address: args.int()?,
+                end_address: args.int()?,
+            }),
+            "VenHw" => DevicePath::HardwareVendor(args.vendor()?),
+            "Ctrl" => DevicePath::Controller(Controller {
+                number: args.int()?,
+            }),
+            // ACPI device paths
+            "PciRoot" => pnp_acpi(0x0A03, args)?,
+            "PcieRoot" => pnp_acpi(0x0A08, args)?,
+            "Floppy" => pnp_acpi(0x0604, args)?,
+            "Keyboard" => pnp_acpi(0x0301, args)?,
+            "Serial" => pnp_acpi(0x0501, args)?,
+            "ParallelPort" => pnp_acpi(0x0401, args)?,
+            "Acpi" => DevicePath::Acpi(Acpi {
+                hid: args.eisa_id()?,
+                uid: args.int()?,
+            }),
+            "AcpiEx" => expanded_acpi(args)?,
+            "AcpiExp" => expanded_acpi_short(args)?,
+            "AcpiAdr" => {
+                let mut adr = vec![args.int()?];
+                while !args.args.as_slice().is_empty() {
+                    adr.push(args.int()?);
+                }
+                DevicePath::AcpiAdr(AcpiAdr { adr })
+            }
+            // messaging device paths
+            "USB" => DevicePath::Usb(Usb {
+                parent_port: args.int()?,
+                interface: args.int()?,
+            }),
+            "Sata" => DevicePath::Sata(Sata {
+                hba_port: args.int()?,
+                port_multiplier_port: args.int()?,
+                lun: args.int()?,
+            }),
+            "NVMe" => nvme(args)?,
+            "Scsi" => DevicePath::Scsi(Scsi {
+                target: args.int()?,
+                lun: args.int()?,
+            }),
+            "MAC" => mac(args)?,
+            "IPv4" => ipv4(args)?,
+            "IPv6" => ipv6(args)?,
+            "VenMsg" => DevicePath::MessagingVendor(args.vendor()?),
+            "VenPcAnsi" => messaging_vendor(PC_ANSI_GUID),
+            "VenVt100" => messaging_vendor(VT_100_GUID),
+            "VenVt100Plus" => messaging_vendor(VT_100_PLUS_GUID),
+            "VenUtf8" => messaging_vendor(VT_UTF8_GUID),
+            // media device paths
+            "HD" => hard_drive(args)?,
+            "CDROM" => DevicePath::CdRom(CdRom {
+                boot_entry: args.int()?,
+                partition_start: args.int_or(0)?,
+                partition_size: args.int_or(0)?,
+            }),
+            "VenMedia" => DevicePath::MediaVendor(args.vendor()?),
+            "FvFile" => DevicePath::FirmwareFile(FirmwareFile { name: args.guid()? }),
+            "Fv" => DevicePath::FirmwareVolume(FirmwareVolume { name: args.guid()? }),
+            "Offset" => DevicePath::RelativeOffsetRange(RelativeOffsetRange {
+                reserved: 0,
+                starting_offset: args.int()?,
+                ending_offset: args.int()?,
+            }),
+            "VirtualDisk" => ram_disk(Some(VIRTUAL_DISK_GUID), args)?,
+            "VirtualCD" => ram_disk(Some(VIRTUAL_CD_GUID), args)?,
+            "PersistentVirtualDisk" => ram_disk(Some(PERSISTENT_VIRTUAL_DISK_GUID), args)?,
+            "PersistentVirtualCD" => ram_disk(Some(PERSISTENT_VIRTUAL_CD_GUID), args)?,
+            "RamDisk" => ram_disk(None, args)?,
+            // generic representations
+            "HardwarePath" => unknown(0x01, args)?,
+            "AcpiPath" => unknown(0x02, args)?,
+            "Msg" => unknown(0x03, args)?,
+            "MediaPath" => unknown(0x04, args)?,
+            "BbsPath" => unknown(0x05, args)?,
+            "Path" => {
+                let r#type = args.int()?;
+                unknown(r#type, args)?
+            }
+            _ => return Err(text_error(text, format!("unknown node type '{name}'"))),
+        };
+
+        args.args.as_slice().first().map_or(Ok(()), |arg| {
+            Err(text_error(text, format!("unexpected argument '{arg}'")))
+        })?;
+
+        Ok(node)
+    }
+}
+
+/// nodes whose arguments are free text, that may hold parentheses and separators
+const FREE_TEXT_NODES: [&str; 3] = ["File(", "Uri(", "BBS("];
+
+/// length of the node at the start of `text`, which is followed by a separator or the end of
+/// the text
+fn node_len(text: &str) -> crate::Result<usize> {
+    let is_end = |rest: &str| rest.is_empty() || rest.starts_with(['/', ',']);
+
+    // free text ends at the first closing parenthesis followed by a separator
+    if let Some(prefix) = FREE_TEXT_NODES
+        .iter()
+        .find(|prefix| text.starts_with(**prefix))
+    {
+        return text[prefix.len()..]
+            .match_indices(')')
+            .map(|(index, _)| prefix.len() + index + 1)
+            .find(|&end| is_end(&text[end..]))
+            .ok_or_else(|| text_error(text, "unbalanced parentheses"));
+    }
+
+    let mut depth: usize = 0;
+    for (index, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| text_error(text, "unbalanced parentheses"))?
+            }
+            '/' | ',' if depth == 0 => return Ok(index),
+            _ => {}
+        }
+    }
+
+    if depth != 0 {
+        return Err(text_error(text, "unbalanced parentheses"));
+    }
+
+    Ok(text.len())
+}
+
+/// parse the text representation of a device path into a list of nodes.
+///
+/// Nodes are separated by `/`, and instances by `,`. The returned list ends with
+/// [`DevicePath::EndEntire`], and instances are separated by [`DevicePath::EndInstance`].
+///
+/// The arguments of `File(...)`, `Uri(...)` and `BBS(...)` nodes are taken as-is, up to the first
+/// `)` followed by a separator or the end of the text. File paths written without `File(...)`, as
+/// edk2 shows them, end at the first `/` or `,` that is not between parentheses, which is why
+/// paths holding those characters or parentheses are shown in the `File(...)` form. Free text
+/// holding a `)` followed by a separator cannot be parsed back.
+pub fn device_path_from_text(text: &str) -> crate::Result<Vec<DevicePath>> {
+    let mut nodes = vec![];
+    let mut rest = text;
+
+    loop {
+        let len = node_len(rest)?;
+        nodes.push(rest[..len].parse()?);
+        match rest[len..].chars().next() {
+            None => break,
+            Some(',') => nodes.push(DevicePath::EndInstance),
+            Some(_) => {}
+        }
+        rest = &rest[len + 1..];
+    }
+    nodes.push(DevicePath::EndEntire);
+
+    Ok(nodes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        device_path_from_text, Bbs, DevicePath, FilePath, ParseCursor, PartitionSignature, Pci, Uri,
+    };
+
+    #[test]
+    fn parse_node() {
+        assert_eq!(
+            "Pci(0x1D,0x2)".parse::<DevicePath>().unwrap(),
+            DevicePath::Pci(Pci {
+                function: 2,
+                device: 0x1D
+            })
+        );
+    }
+
+    #[test]
+    fn parse_hard_drive_signatures() {
+        for (text, signature) in [
+            (
+                "HD(1,MBR,0xBE1AFDFA,0x3F,0x64000)",
+                PartitionSignature::Mbr(0xBE1AFDFA),
+            ),
+            ("HD(1,0,0,0x3F,0x64000)", PartitionSignature::None),
+            (
+                "HD(1,3,0,0x3F,0x64000)",
+                PartitionSignature::Unknown {
+                    sig_type: 3,
+                    signature: [0; 16],
+                },
+            ),
+        ] {
+            let node = text.parse::<DevicePath>().unwrap();
+            match &node {
+                DevicePath::HardDrive(hard_drive) => {
+                    assert_eq!(hard_drive.partition_sig, signature)
+                }
+                node => panic!("{:?} is not a hard drive", node),
+            }
+            assert_eq!(node.to_string(), text);
+        }
+    }
+
+    #[test]
+    fn parse_invalid_nodes() {
+        for text in [
+            "Pci(0x1D)",
+            "Pci(0x1D,0x2,0x3)",
+            "Pci(0x1D,0x200)",
+            "Pci(zz,0x0)",
+            "HD(1,GPT,not-a-guid,0x800,0x1000)",
+            "Unicorn(0x1)",
+        ] {
+            assert!(text.parse::<DevicePath>().is_err(), "{} was accepted", text);
+        }
+    }
+
+    #[test]
+    fn parse_unbalanced() {
+        assert!(device_path_from_text("PciRoot(0x0)/Pci(0x1D,0x0").is_err());
+        assert!(device_path_from_text("PciRoot(0x0))/Pci(0x1D,0x0)").is_err());
+    }
+
+    #[test]
+    fn free_text_roundtrip() {
+        for (text, node) in [
+            (
+                "File(\\EFI\\a(b.efi)",
+                DevicePath::FilePath(FilePath {
+                    path: "\\EFI\\a(b.efi".to_owned(),
+                }),
+            ),
+            (
+                "File(\\EFI\\a)b,c/d.efi)",
+                DevicePath::FilePath(FilePath {
+                    path: "\\EFI\\a)b,c/d.efi".to_owned(),
+                }),
+            ),
+            (
+                " \\EFI\\spaces.efi ",
+                DevicePath::FilePath(FilePath {
+                    path: " \\EFI\\spaces.efi ".to_owned(),
+                }),
+            ),
+            (
+                "Uri(http://host/a(b)c)",
+                DevicePath::Uri(Uri {
+                    uri: "http://host/a(b)c".to_owned(),
+                }),
+            ),
+            (
+                "BBS(HD, Disk (1) a, 2 ,0x0)",
+                DevicePath::Bbs(Bbs {
+                    device_type: 0x02,
+                    status_flag: 0,
+                    description: " Disk (1) a, 2 ".to_owned(),
+                }),
+            ),
+        ] {
+            let nodes =
+                device_path_from_text(&format!("PciRoot(0x0)/{text}/Pci(0x1,0x0)")).unwrap();
+            assert_eq!(nodes[1], node, "{}", text);
+            assert_eq!(nodes.len(), 4, "{}", text);
+
+            let bytes = node.to_bytes().unwrap();
+            let parsed = DevicePath::parse(&mut ParseCursor::new(&bytes)).unwrap();
+            assert_eq!(parsed.to_string(), text, "{} did not round-trip", text);
+        }
+    }
+
+    #[test]
+    fn free_text_limits() {
+        // a bare file path ends at the first separator
+        assert_eq!(
+            device_path_from_text("\\EFI\\a,b.efi").unwrap(),
+            vec![
+                DevicePath::FilePath(FilePath {
+                    path: "\\EFI\\a".to_owned()
+                }),
+                DevicePath::EndInstance,
+                DevicePath::FilePath(FilePath {
+                    path: "b.efi".to_owned()
+                }),
+                DevicePath::EndEntire,
+            ]
+        );
+        // in the display-only form of BBS nodes, a trailing number is the status flag
+        assert_eq!(
+            "BBS(HD,Disk,1)".parse::<DevicePath>().unwrap(),
+            DevicePath::Bbs(Bbs {
+                device_type: 0x02,
+                status_flag: 1,
+                description: "Disk".to_owned(),
+            })
+        );
+        assert!(device_path_from_text("File(\\EFI\\a.efi").is_err());
+        // free text cannot hold a closing parenthesis followed by a separator
+        assert!(device_path_from_text("File(\\EFI\\a)/b.efi)").is_err());
+    }
+
+    #[test]
+    fn generic_known_node() {
+        // a generic representation of a PCI node gives back a PCI node
+        assert_eq!(
+            "HardwarePath(1,001d)".parse::<DevicePath>().unwrap(),
+            DevicePath::Pci(Pci {
+                function: 0,
+                device: 0x1D
+            })
+        );
+    }
+}
@@ -0,0 +1,73 @@
+//! Hardware device path nodes (type 0x01)
+
+use crate::push::PushVecU8;
+
+use super::ParseCursor;
+
+#[derive(Debug, PartialEq, Clone)]
+// See spec, 10.3.2.1
+pub struct Pci {
+    pub function: u8,
+    pub device: u8,
+}
+
+impl Pci {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<Pci> {
+        Ok(Pci {
+            function: buf.read_u8("Pci.function")?,
+            device: buf.read_u8("Pci.device")?,
+        })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        vec![self.function, self.device]
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+// See spec, 10.3.2.3
+pub struct MemoryMapped {
+    pub memory_type: u32,
+    pub start_address: u64,
+    pub end_address: u64,
+}
+
+impl MemoryMapped {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<MemoryMapped> {
+        Ok(MemoryMapped {
+            memory_type: buf.read_u32("MemoryMapped.memory_type")?,
+            start_address: buf.read_u64("MemoryMapped.start_address")?,
+            end_address: buf.read_u64("MemoryMapped.end_address")?,
+        })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+        bytes.push_u32(self.memory_type);
+        bytes.push_u64(self.start_address);
+        bytes.push_u64(self.end_address);
+
+        bytes
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+// See spec, 10.3.2.5
+pub struct Controller {
+    pub number: u32,
+}
+
+impl Controller {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<Controller> {
+        Ok(Controller {
+            number: buf.read_u32("Controller.number")?,
+        })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+        bytes.push_u32(self.number);
+
+        bytes
+    }
+}
@@ -0,0 +1,482 @@
+//! Media device path nodes (type 0x04)
+
+use std::fmt::Display;
+
+use uuid::Uuid;
+
+use crate::push::PushVecU8;
+
+use super::{consts, encap_as_device_path, ParseCursor};
+
+/// Signature of the disk holding a partition, as stored in a hard drive device path node
+#[derive(Debug, PartialEq, Clone)]
+pub enum PartitionSignature {
+    /// No disk signature
+    None,
+    /// 32-bit signature of a MBR disk, stored at offset 0x1B8 of the MBR
+    Mbr(u32),
+    /// GUID of a GPT partition
+    Gpt(Uuid),
+    /// Signature type we do not know about. Kept as-is
+    Unknown { sig_type: u8, signature: [u8; 16] },
+}
+
+impl PartitionSignature {
+    pub fn parse(sig_type: u8, signature: [u8; 16]) -> PartitionSignature {
+        match sig_type {
+            0x00 => Self::None,
+            0x01 => Self::Mbr(u32::from_le_bytes([
+                signature[0],
+                signature[1],
+                signature[2],
+                signature[3],
+            ])),
+            0x02 => Self::Gpt(Uuid::from_bytes_le(signature)),
+            sig_type => Self::Unknown {
+                sig_type,
+                signature,
+            },
+        }
+    }
+
+    /// get the disk signature from the first sector of a MBR disk.
+    /// Returns None if the sector is not a valid MBR
+    pub fn from_mbr(sector: &[u8]) -> Option<PartitionSignature> {
+        if sector.len() < 512 || sector[510..512] != [0x55, 0xAA] {
+            return None;
+        }
+
+        Some(Self::Mbr(u32::from_le_bytes([
+            sector[0x1B8],
+            sector[0x1B9],
+            sector[0x1BA],
+            sector[0x1BB],
+        ])))
+    }
+
+    /// get the signature type, as stored in the node
+    pub fn sig_type(&self) -> u8 {
+        match self {
+            PartitionSignature::None => 0x00,
+            PartitionSignature::Mbr(_) => 0x01,
+            PartitionSignature::Gpt(_) => 0x02,
+            PartitionSignature::Unknown { sig_type, .. } => *sig_type,
+        }
+    }
+
+    /// get the 16 bytes of the signature, as stored in the node
+    pub fn signature(&self) -> [u8; 16] {
+        match self {
+            PartitionSignature::None => [0; 16],
+            PartitionSignature::Mbr(signature) => {
+                let mut bytes = [0; 16];
+                bytes[..4].copy_from_slice(&signature.to_le_bytes());
+                bytes
+            }
+            PartitionSignature::Gpt(guid) => guid.to_bytes_le(),
+            PartitionSignature::Unknown { signature, .. } => *signature,
+        }
+    }
+}
+
+impl Display for PartitionSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PartitionSignature::None => f.write_str("None"),
+            PartitionSignature::Mbr(signature) => write!(f, "MBR,0x{signature:08X}"),
+            PartitionSignature::Gpt(guid) => write!(f, "GPT,{guid}"),
+            PartitionSignature::Unknown { sig_type, .. } => write!(f, "Unknown({sig_type})"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+// See spec, 10.3.6.1
+pub struct EFIHardDrive {
+    pub partition_number: u32,
+    pub partition_start: u64,
+    pub partition_size: u64,
+    pub partition_sig: PartitionSignature,
+    /// partition format: 0x01 for a MBR partition table, 0x02 for GPT
+    pub format: u8,
+}
+
+impl EFIHardDrive {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<EFIHardDrive> {
+        let partition_number = buf.read_u32("EFIHardDrive.partition_number")?;
+        let partition_start = buf.read_u64("EFIHardDrive.partition_start")?;
+        let partition_size = buf.read_u64("EFIHardDrive.partition_size")?;
+        let signature = buf.read_array("EFIHardDrive.partition_sig")?;
+        let format = buf.read_u8("EFIHardDrive.format")?;
+        let sig_type = buf.read_u8("EFIHardDrive.sig_type")?;
+
+        Ok(EFIHardDrive {
+            partition_number,
+            partition_start,
+            partition_size,
+            partition_sig: PartitionSignature::parse(sig_type, signature),
+            format,
+        })
+    }
+
+    /// get bytes representation for a EFIHardDrive, without encapsulating them in a DevicePath structure
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+        bytes.push_u32(self.partition_number);
+        bytes.push_u64(self.partition_start);
+        bytes.push_u64(self.partition_size);
+        bytes.extend_from_slice(&self.partition_sig.signature());
+        bytes.push_u8(self.format);
+        bytes.push_u8(self.partition_sig.sig_type());
+
+        bytes
+    }
+
+    /// get bytes representation for a EFIHardDrive, as a DevicePath (EFI_DEVICE_PATH_PROTOCOL) structure
+    pub fn to_bytes_encap(&self) -> crate::Result<Vec<u8>> {
+        encap_as_device_path(
+            consts::DEVICE_PATH_TYPE::MEDIA_DEVICE_PATH,
+            consts::MEDIA_DEVICE_PATH_SUBTYPE::HARD_DRIVE,
+            self.to_bytes_raw(),
+        )
+    }
+}
+
+impl Display for EFIHardDrive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HD({},{})", self.partition_number, self.partition_sig)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+// See spec, 10.3.5.2
+pub struct CdRom {
+    pub boot_entry: u32,
+    pub partition_start: u64,
+    pub partition_size: u64,
+}
+
+impl CdRom {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<CdRom> {
+        Ok(CdRom {
+            boot_entry: buf.read_u32("CdRom.boot_entry")?,
+            partition_start: buf.read_u64("CdRom.partition_start")?,
+            partition_size: buf.read_u64("CdRom.partition_size")?,
+        })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+        bytes.push_u32(self.boot_entry);
+        bytes.push_u64(self.partition_start);
+        bytes.push_u64(self.partition_size);
+
+        bytes
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct FilePath {
+    /// the UEFI standard seem to use UCS-2 strings (a subset of UTF-16), so Rust UTF8 strings should be able to represent them
+    pub path: String,
+}
+
+impl FilePath {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<FilePath> {
+        Ok(FilePath {
+            path: buf.read_nt_utf16_string("FilePath.path")?,
+        })
+    }
+
+    /// get bytes representation for a FilePath, without encapsulating them in a DevicePath structure
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        let utf16_bytes = self.path.encode_utf16();
+        let mut utf8_bytes: Vec<u8> = utf16_bytes
+            .into_iter()
+            .flat_map(|var| var.to_le_bytes())
+            .collect();
+
+        // write null termination
+        utf8_bytes.push_u16(0x0000);
+
+        utf8_bytes
+    }
+
+    /// get bytes representation for a FilePath, as a DevicePath (EFI_DEVICE_PATH_PROTOCOL) structure
+    pub fn to_bytes_encap(&self) -> crate::Result<Vec<u8>> {
+        encap_as_device_path(
+            consts::DEVICE_PATH_TYPE::MEDIA_DEVICE_PATH,
+            consts::MEDIA_DEVICE_PATH_SUBTYPE::FILE_PATH,
+            self.to_bytes_raw(),
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+/// PIWG firmware file. See spec, 10.3.5.6
+pub struct FirmwareFile {
+    pub name: Uuid,
+}
+
+impl FirmwareFile {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<FirmwareFile> {
+        Ok(FirmwareFile {
+            name: buf.read_guid("FirmwareFile.name")?,
+        })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        self.name.to_bytes_le().to_vec()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+/// PIWG firmware volume. See spec, 10.3.5.7
+pub struct FirmwareVolume {
+    pub name: Uuid,
+}
+
+impl FirmwareVolume {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<FirmwareVolume> {
+        Ok(FirmwareVolume {
+            name: buf.read_guid("FirmwareVolume.name")?,
+        })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        self.name.to_bytes_le().to_vec()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+// See spec, 10.3.5.8
+pub struct RelativeOffsetRange {
+    pub reserved: u32,
+    pub starting_offset: u64,
+    pub ending_offset: u64,
+}
+
+impl RelativeOffsetRange {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<RelativeOffsetRange> {
+        Ok(RelativeOffsetRange {
+            reserved: buf.read_u32("RelativeOffsetRange.reserved")?,
+            starting_offset: buf.read_u64("RelativeOffsetRange.starting_offset")?,
+            ending_offset: buf.read_u64("RelativeOffsetRange.ending_offset")?,
+        })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+        bytes.push_u32(self.reserved);
+        bytes.push_u64(self.starting_offset);
+        bytes.push_u64(self.ending_offset);
+
+        bytes
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+// See spec, 10.3.5.9
+pub struct RamDisk {
+    pub starting_address: u64,
+    pub ending_address: u64,
+    pub disk_type: Uuid,
+    pub instance: u16,
+}
+
+impl RamDisk {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<RamDisk> {
+        Ok(RamDisk {
+            starting_address: buf.read_u64("RamDisk.starting_address")?,
+            ending_address: buf.read_u64("RamDisk.ending_address")?,
+            disk_type: buf.read_guid("RamDisk.disk_type")?,
+            instance: buf.read_u16("RamDisk.instance")?,
+        })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+        bytes.push_u64(self.starting_address);
+        bytes.push_u64(self.ending_address);
+        bytes.push_guid(&self.disk_type);
+        bytes.push_u16(self.instance);
+
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use uuid::Uuid;
+
+    use crate::{Error, ParseError, ParseErrorKind};
+
+    use super::{EFIHardDrive, ParseCursor, PartitionSignature};
+
+    #[test]
+    fn partition_signature_parse() {
+        assert_eq!(
+            PartitionSignature::parse(0x00, [0; 16]),
+            PartitionSignature::None
+        );
+        let mut signature = [0; 16];
+        signature[..4].copy_from_slice(&[0xFA, 0xFD, 0x1A, 0xBE]);
+        assert_eq!(
+            PartitionSignature::parse(0x01, signature),
+            PartitionSignature::Mbr(0xBE1AFDFA)
+        );
+        assert_eq!(
+            PartitionSignature::parse(
+                0x02,
+                Uuid::from_str("90364bbd-1000-47fc-8c05-8707e01b4593")
+                    .unwrap()
+                    .to_bytes_le()
+            ),
+            PartitionSignature::Gpt(
+                Uuid::from_str("90364bbd-1000-47fc-8c05-8707e01b4593").unwrap()
+            )
+        );
+        assert_eq!(
+            PartitionSignature::parse(0x03, [0xAA; 16]),
+            PartitionSignature::Unknown {
+                sig_type: 0x03,
+                signature: [0xAA; 16]
+            }
+        );
+    }
+
+    #[test]
+    fn partition_signature_from_mbr() {
+        let mut sector = [0; 512];
+        sector[0x1B8..0x1BC].copy_from_slice(&[0xFA, 0xFD, 0x1A, 0xBE]);
+        assert_eq!(PartitionSignature::from_mbr(&sector), None);
+
+        sector[510..].copy_from_slice(&[0x55, 0xAA]);
+        assert_eq!(
+            PartitionSignature::from_mbr(&sector),
+            Some(PartitionSignature::Mbr(0xBE1AFDFA))
+        );
+        assert_eq!(PartitionSignature::from_mbr(&sector[..511]), None);
+    }
+
+    #[test]
+    fn partition_signature_dump() {
+        assert_eq!(PartitionSignature::None.sig_type(), 0x00);
+        assert_eq!(PartitionSignature::None.signature(), [0; 16]);
+        assert_eq!(PartitionSignature::Mbr(0xBE1AFDFA).sig_type(), 0x01);
+        assert_eq!(
+            PartitionSignature::Mbr(0xBE1AFDFA).signature(),
+            [0xFA, 0xFD, 0x1A, 0xBE, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(PartitionSignature::Gpt(Uuid::nil()).sig_type(), 0x02);
+
+        // unknown signature types are written back as they were read
+        let unknown = PartitionSignature::Unknown {
+            sig_type: 0xFF,
+            signature: [0xAA; 16],
+        };
+        assert_eq!(unknown.sig_type(), 0xFF);
+        assert_eq!(unknown.signature(), [0xAA; 16]);
+    }
+
+    #[test]
+    fn partition_signature_print() {
+        assert_eq!(format!("{}", PartitionSignature::None), "None");
+        assert_eq!(
+            format!("{}", PartitionSignature::Mbr(0xBE1AFDFA)),
+            "MBR,0xBE1AFDFA"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                PartitionSignature::Unknown {
+                    sig_type: 3,
+                    signature: [0; 16]
+                }
+            ),
+            "Unknown(3)"
+        );
+    }
+
+    #[test]
+    fn print_hard_drive() {
+        assert_eq!(
+            "HD(1,GPT,90364bbd-1000-47fc-8c05-8707e01b4593)",
+            format!(
+                "{}",
+                EFIHardDrive {
+                    partition_number: 1,
+                    partition_start: 2,
+                    partition_size: 3,
+                    partition_sig: PartitionSignature::Gpt(
+                        Uuid::from_str("90364bbd-1000-47fc-8c05-8707e01b4593").unwrap()
+                    ),
+                    format: 5,
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn to_from_bytes() {
+        let drive = EFIHardDrive {
+            partition_number: 1,
+            partition_start: 2,
+            partition_size: 3,
+            partition_sig: PartitionSignature::Gpt(
+                Uuid::from_str("90364bbd-1000-47fc-8c05-8707e01b4593").unwrap(),
+            ),
+            format: 5,
+        };
+        let bytes = drive.to_bytes_raw();
+        let mut x = ParseCursor::new(&bytes);
+        let test_parse = EFIHardDrive::parse(&mut x).unwrap();
+        assert_eq!(drive, test_parse)
+    }
+
+    #[test]
+    fn to_from_bytes_mbr() {
+        let drive = EFIHardDrive {
+            partition_number: 1,
+            partition_start: 0x3F,
+            partition_size: 0x64000,
+            partition_sig: PartitionSignature::Mbr(0xBE1AFDFA),
+            format: 1,
+        };
+        let bytes = drive.to_bytes_raw();
+        assert_eq!(&bytes[20..24], &[0xFA, 0xFD, 0x1A, 0xBE]);
+        assert_eq!(&bytes[36..], &[0x01, 0x01]);
+        let mut x = ParseCursor::new(&bytes);
+        assert_eq!(EFIHardDrive::parse(&mut x).unwrap(), drive)
+    }
+
+    #[test]
+    fn truncated_hard_drive() {
+        let bytes = EFIHardDrive {
+            partition_number: 1,
+            partition_start: 2,
+            partition_size: 3,
+            partition_sig: PartitionSignature::Mbr(4),
+            format: 1,
+        }
+        .to_bytes_raw();
+
+        match EFIHardDrive::parse(&mut ParseCursor::new(&bytes[..14])) {
+            Err(Error::ParseError(err)) => assert_eq!(
+                err,
+                ParseError {
+                    variable: None,
+                    offset: 12,
+                    field: "EFIHardDrive.partition_size",
+                    kind: ParseErrorKind::Truncated {
+                        expected: 8,
+                        available: 2
+                    }
+                }
+            ),
+            res => panic!("unexpected result {:?}", res),
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! Messaging device path nodes (type 0x03)
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::{push::PushVecU8, ParseError};
+
+use super::ParseCursor;
+
+#[derive(Debug, PartialEq, Clone)]
+// See spec, 10.3.4.2
+pub struct Scsi {
+    pub target: u16,
+    pub lun: u16,
+}
+
+impl Scsi {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<Scsi> {
+        Ok(Scsi {
+            target: buf.read_u16("Scsi.target")?,
+            lun: buf.read_u16("Scsi.lun")?,
+        })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+        bytes.push_u16(self.target);
+        bytes.push_u16(self.lun);
+
+        bytes
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+// See spec, 10.3.4.5
+pub struct Usb {
+    pub parent_port: u8,
+    pub interface: u8,
+}
+
+impl Usb {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<Usb> {
+        Ok(Usb {
+            parent_port: buf.read_u8("Usb.parent_port")?,
+            interface: buf.read_u8("Usb.interface")?,
+        })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        vec![self.parent_port, self.interface]
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+// See spec, 10.3.4.6
+pub struct Sata {
+    pub hba_port: u16,
+    pub port_multiplier_port: u16,
+    pub lun: u16,
+}
+
+impl Sata {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<Sata> {
+        Ok(Sata {
+            hba_port: buf.read_u16("Sata.hba_port")?,
+            port_multiplier_port: buf.read_u16("Sata.port_multiplier_port")?,
+            lun: buf.read_u16("Sata.lun")?,
+        })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+        bytes.push_u16(self.hba_port);
+        bytes.push_u16(self.port_multiplier_port);
+        bytes.push_u16(self.lun);
+
+        bytes
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+// See spec, 10.3.4.11
+pub struct MacAddr {
+    /// MAC address, padded with zeroes
+    pub address: [u8; 32],
+    /// network interface type, see RFC 3232
+    pub if_type: u8,
+}
+
+impl MacAddr {
+    /// build a MAC address node for an ethernet interface
+    pub fn from_ethernet(mac: [u8; 6]) -> MacAddr {
+        let mut address = [0u8; 32];
+        address[..6].copy_from_slice(&mac);
+
+        MacAddr {
+            address,
+            if_type: 0x01,
+        }
+    }
+
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<MacAddr> {
+        Ok(MacAddr {
+            address: buf.read_array("MacAddr.address")?,
+            if_type: buf.read_u8("MacAddr.if_type")?,
+        })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        let mut bytes = self.address.to_vec();
+        bytes.push_u8(self.if_type);
+
+        bytes
+    }
+}
+
+fn read_ipv4(buf: &mut ParseCursor, field: &'static str) -> crate::Result<Ipv4Addr> {
+    Ok(Ipv4Addr::from(buf.read_array::<4>(field)?))
+}
+
+fn read_ipv6(buf: &mut ParseCursor, field: &'static str) -> crate::Result<Ipv6Addr> {
+    Ok(Ipv6Addr::from(buf.read_array::<16>(field)?))
+}
+
+#[derive(Debug, PartialEq, Clone)]
+// See spec, 10.3.4.12
+pub struct Ipv4 {
+    pub local_address: Ipv4Addr,
+    pub remote_address: Ipv4Addr,
+    pub local_port: u16,
+    pub remote_port: u16,
+    pub protocol: u16,
+    /// false if the address was assigned through DHCP
+    pub static_address: bool,
+    pub gateway_address: Ipv4Addr,
+    pub subnet_mask: Ipv4Addr,
+}
+
+impl Ipv4 {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<Ipv4> {
+        Ok(Ipv4 {
+            local_address: read_ipv4(buf, "Ipv4.local_address")?,
+            remote_address: read_ipv4(buf, "Ipv4.remote_address")?,
+            local_port: buf.read_u16("Ipv4.local_port")?,
+            remote_port: buf.read_u16("Ipv4.remote_port")?,
+            protocol: buf.read_u16("Ipv4.protocol")?,
+            static_address: match buf.read_u8("Ipv4.static_address")? {
+                0x00 => false,
+                0x01 => true,
+                value => {
+                    return Err(ParseError::invalid(
+                        buf.offset() - 1,
+                        "Ipv4.static_address",
+                        format!("expected 0 or 1, got {value}"),
+                    )
+                    .into())
+                }
+            },
+            gateway_address: read_ipv4(buf, "Ipv4.gateway_address")?,
+            subnet_mask: read_ipv4(buf, "Ipv4.subnet_mask")?,
+        })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+        bytes.extend_from_slice(&self.local_address.octets());
+        bytes.extend_from_slice(&self.remote_address.octets());
+        bytes.push_u16(self.local_port);
+        bytes.push_u16(self.remote_port);
+        bytes.push_u16(self.protocol);
+        bytes.push_u8(self.static_address as u8);
+        bytes.extend_from_slice(&self.gateway_address.octets());
+        bytes.extend_from_slice(&self.subnet_mask.octets());
+
+        bytes
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+// See spec, 10.3.4.13
+pub struct Ipv6 {
+    pub local_address: Ipv6Addr,
+    pub remote_address: Ipv6Addr,
+    pub local_port: u16,
+    pub remote_port: u16,
+    pub protocol: u16,
+    /// 0x00 for manual configuration, 0x01 for stateless and 0x02 for stateful auto-configuration
+    pub address_origin: u8,
+    pub prefix_length: u8,
+    pub gateway_address: Ipv6Addr,
+}
+
+impl Ipv6 {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<Ipv6> {
+        Ok(Ipv6 {
+            local_address: read_ipv6(buf, "Ipv6.local_address")?,
+            remote_address: read_ipv6(buf, "Ipv6.remote_address")?,
+            local_port: buf.read_u16("Ipv6.local_port")?,
+            remote_port: buf.read_u16("Ipv6.remote_port")?,
+            protocol: buf.read_u16("Ipv6.protocol")?,
+            address_origin: buf.read_u8("Ipv6.address_origin")?,
+            prefix_length: buf.read_u8("Ipv6.prefix_length")?,
+            gateway_address: read_ipv6(buf, "Ipv6.gateway_address")?,
+        })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+        bytes.extend_from_slice(&self.local_address.octets());
+        bytes.extend_from_slice(&self.remote_address.octets());
+        bytes.push_u16(self.local_port);
+        bytes.push_u16(self.remote_port);
+        bytes.push_u16(self.protocol);
+        bytes.push_u8(self.address_origin);
+        bytes.push_u8(self.prefix_length);
+        bytes.extend_from_slice(&self.gateway_address.octets());
+
+        bytes
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+// See spec, 10.3.4.23
+pub struct Nvme {
+    pub namespace_id: u32,
+    /// IEEE Extended Unique Identifier, as stored in the node
+    pub eui64: [u8; 8],
+}
+
+impl Nvme {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<Nvme> {
+        Ok(Nvme {
+            namespace_id: buf.read_u32("Nvme.namespace_id")?,
+            eui64: buf.read_array("Nvme.eui64")?,
+        })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+        bytes.push_u32(self.namespace_id);
+        bytes.extend_from_slice(&self.eui64);
+
+        bytes
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+// See spec, 10.3.4.24
+pub struct Uri {
+    pub uri: String,
+}
+
+impl Uri {
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<Uri> {
+        let offset = buf.offset();
+        let uri = std::str::from_utf8(buf.read_rest())
+            .map_err(|_| ParseError::invalid(offset, "Uri.uri", "invalid UTF-8 string"))?
+            .to_owned();
+
+        Ok(Uri { uri })
+    }
+
+    pub(super) fn to_bytes_raw(&self) -> Vec<u8> {
+        self.uri.as_bytes().to_vec()
+    }
+}
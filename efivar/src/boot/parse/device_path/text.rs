@@ -0,0 +1,575 @@
+//! Text representation of device paths, following the "Device Path Text Representation"
+//! chapter of the UEFI specification (what edk2's DevicePathToText produces)
+
+use std::fmt::{Display, Write};
+
+use uuid::{uuid, Uuid};
+
+use super::{
+    consts, Acpi, Bbs, DevicePath, EFIHardDrive, ExpandedAcpi, Ipv4, Ipv6, MacAddr,
+    PartitionSignature, RamDisk, VendorDevicePath,
+};
+
+pub(super) const PC_ANSI_GUID: Uuid = uuid!("e0c14753-f9be-11d2-9a0c-0090273fc14d");
+pub(super) const VT_100_GUID: Uuid = uuid!("dfa66065-b419-11d3-9a2d-0090273fc14d");
+pub(super) const VT_100_PLUS_GUID: Uuid = uuid!("7baec70b-57e0-4c76-8e87-2f9e28088343");
+pub(super) const VT_UTF8_GUID: Uuid = uuid!("ad15a0d6-8bec-4acf-a073-d01de77e2d88");
+
+pub(super) const VIRTUAL_DISK_GUID: Uuid = uuid!("77ab535a-45fc-624b-5560-f7b281d1f96e");
+pub(super) const VIRTUAL_CD_GUID: Uuid = uuid!("3d5abd30-4175-87ce-6d64-d2ade523c4bb");
+pub(super) const PERSISTENT_VIRTUAL_DISK_GUID: Uuid = uuid!("5cea02c9-4d07-69d3-269f-4496fbe096f9");
+pub(super) const PERSISTENT_VIRTUAL_CD_GUID: Uuid = uuid!("08018188-42cd-bb48-100f-5387d53ded3d");
+
+/// compressed EISA id of PNP devices (`PNP` vendor prefix)
+pub(super) const PNP_EISA_ID: u32 = 0x41D0;
+
+/// get the product number of an EISA id
+fn eisa_id_to_num(id: u32) -> u32 {
+    id >> 16
+}
+
+/// get the text representation of an EISA id, such as `PNP0A03`
+fn eisa_id_to_text(id: u32) -> String {
+    let letter = |shift: u32| (((id >> shift) & 0x1F) as u8 + b'A' - 1) as char;
+    format!(
+        "{}{}{}{:04X}",
+        letter(10),
+        letter(5),
+        letter(0),
+        eisa_id_to_num(id)
+    )
+}
+
+fn hex_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn vendor_to_text(out: &mut String, name: &str, vendor: &VendorDevicePath) -> std::fmt::Result {
+    write!(out, "{name}({}", vendor.guid)?;
+    if !vendor.data.is_empty() {
+        write!(out, ",{}", hex_bytes(&vendor.data))?;
+    }
+    out.write_char(')')
+}
+
+fn acpi_to_text(out: &mut String, acpi: &Acpi) -> std::fmt::Result {
+    if acpi.hid & 0xFFFF == PNP_EISA_ID {
+        let name = match eisa_id_to_num(acpi.hid) {
+            0x0A03 => Some("PciRoot"),
+            0x0A08 => Some("PcieRoot"),
+            0x0604 => Some("Floppy"),
+            0x0301 => Some("Keyboard"),
+            0x0501 => Some("Serial"),
+            0x0401 => Some("ParallelPort"),
+            _ => None,
+        };
+        match name {
+            Some(name) => write!(out, "{name}(0x{:X})", acpi.uid),
+            None => write!(
+                out,
+                "Acpi(PNP{:04X},0x{:X})",
+                eisa_id_to_num(acpi.hid),
+                acpi.uid
+            ),
+        }
+    } else {
+        write!(out, "Acpi(0x{:08X},0x{:X})", acpi.hid, acpi.uid)
+    }
+}
+
+fn expanded_acpi_to_text(
+    out: &mut String,
+    acpi: &ExpandedAcpi,
+    display_only: bool,
+) -> std::fmt::Result {
+    let uid_text = || {
+        if acpi.uid == 0 && !acpi.uid_str.is_empty() {
+            acpi.uid_str.clone()
+        } else {
+            format!("0x{:X}", acpi.uid)
+        }
+    };
+
+    if display_only {
+        let hid = eisa_id_to_num(acpi.hid);
+        let cid = eisa_id_to_num(acpi.cid);
+        if hid == 0x0A03 || (cid == 0x0A03 && hid != 0x0A08) {
+            return write!(out, "PciRoot({})", uid_text());
+        }
+        if hid == 0x0A08 || cid == 0x0A08 {
+            return write!(out, "PcieRoot({})", uid_text());
+        }
+    }
+
+    let hid_text = eisa_id_to_text(acpi.hid);
+    let cid_text = eisa_id_to_text(acpi.cid);
+
+    if acpi.hid_str.is_empty() && acpi.cid_str.is_empty() && !acpi.uid_str.is_empty() {
+        if acpi.cid == 0 {
+            write!(out, "AcpiExp({hid_text},0,{})", acpi.uid_str)
+        } else {
+            write!(out, "AcpiExp({hid_text},{cid_text},{})", acpi.uid_str)
+        }
+    } else if display_only {
+        let or_text = |string: &String, text: String| {
+            if string.is_empty() {
+                text
+            } else {
+                string.clone()
+            }
+        };
+        write!(
+            out,
+            "AcpiEx({},{},{})",
+            or_text(&acpi.hid_str, hid_text),
+            or_text(&acpi.cid_str, cid_text),
+            or_text(&acpi.uid_str, format!("0x{:X}", acpi.uid))
+        )
+    } else {
+        write!(
+            out,
+            "AcpiEx({hid_text},{cid_text},0x{:X},{},{},{})",
+            acpi.uid, acpi.hid_str, acpi.cid_str, acpi.uid_str
+        )
+    }
+}
+
+fn mac_to_text(out: &mut String, mac: &MacAddr) -> std::fmt::Result {
+    // ethernet addresses are 6 bytes long, keep the whole buffer for anything else
+    let size = match mac.if_type {
+        0x00 | 0x01 => 6,
+        _ => mac.address.len(),
+    };
+    write!(
+        out,
+        "MAC({},0x{:X})",
+        hex_bytes(&mac.address[..size]),
+        mac.if_type
+    )
+}
+
+fn protocol_to_text(protocol: u16) -> String {
+    match protocol {
+        6 => "TCP".to_owned(),
+        17 => "UDP".to_owned(),
+        _ => format!("0x{protocol:X}"),
+    }
+}
+
+fn ipv4_to_text(out: &mut String, ip: &Ipv4, display_only: bool) -> std::fmt::Result {
+    write!(out, "IPv4({}", ip.remote_address)?;
+    if !display_only {
+        write!(
+            out,
+            ",{},{},{},{},{}",
+            protocol_to_text(ip.protocol),
+            if ip.static_address { "Static" } else { "DHCP" },
+            ip.local_address,
+            ip.gateway_address,
+            ip.subnet_mask
+        )?;
+    }
+    out.write_char(')')
+}
+
+fn ipv6_to_text(out: &mut String, ip: &Ipv6, display_only: bool) -> std::fmt::Result {
+    write!(out, "IPv6({}", ip.remote_address)?;
+    if !display_only {
+        write!(
+            out,
+            ",{},{},{},0x{:X},{}",
+            protocol_to_text(ip.protocol),
+            match ip.address_origin {
+                0x00 => "Static",
+                0x01 => "StatelessAutoConfigure",
+                _ => "StatefulAutoConfigure",
+            },
+            ip.local_address,
+            ip.prefix_length,
+            ip.gateway_address
+        )?;
+    }
+    out.write_char(')')
+}
+
+fn hard_drive_to_text(
+    out: &mut String,
+    hard_drive: &EFIHardDrive,
+    display_only: bool,
+) -> std::fmt::Result {
+    match &hard_drive.partition_sig {
+        PartitionSignature::Mbr(signature) => write!(
+            out,
+            "HD({},MBR,0x{:08X}",
+            hard_drive.partition_number, signature
+        )?,
+        PartitionSignature::Gpt(guid) => {
+            write!(out, "HD({},GPT,{}", hard_drive.partition_number, guid)?
+        }
+        // like edk2, other signature types are printed as a number, without the signature
+        signature => write!(
+            out,
+            "HD({},{},0",
+            hard_drive.partition_number,
+            signature.sig_type()
+        )?,
+    }
+    if !display_only {
+        write!(
+            out,
+            ",0x{:X},0x{:X}",
+            hard_drive.partition_start, hard_drive.partition_size
+        )?;
+    }
+    out.write_char(')')
+}
+
+fn ram_disk_to_text(out: &mut String, ram_disk: &RamDisk) -> std::fmt::Result {
+    let name = match ram_disk.disk_type {
+        VIRTUAL_DISK_GUID => "VirtualDisk",
+        VIRTUAL_CD_GUID => "VirtualCD",
+        PERSISTENT_VIRTUAL_DISK_GUID => "PersistentVirtualDisk",
+        PERSISTENT_VIRTUAL_CD_GUID => "PersistentVirtualCD",
+        _ => {
+            return write!(
+                out,
+                "RamDisk(0x{:X},0x{:X},{},{})",
+                ram_disk.starting_address,
+                ram_disk.ending_address,
+                ram_disk.instance,
+                ram_disk.disk_type
+            )
+        }
+    };
+    write!(
+        out,
+        "{name}(0x{:X},0x{:X},{})",
+        ram_disk.starting_address, ram_disk.ending_address, ram_disk.instance
+    )
+}
+
+fn bbs_to_text(out: &mut String, bbs: &Bbs, display_only: bool) -> std::fmt::Result {
+    match bbs.device_type {
+        0x01 => out.write_str("BBS(Floppy")?,
+        0x02 => out.write_str("BBS(HD")?,
+        0x03 => out.write_str("BBS(CDROM")?,
+        0x04 => out.write_str("BBS(PCMCIA")?,
+        0x05 => out.write_str("BBS(USB")?,
+        0x06 => out.write_str("BBS(Network")?,
+        device_type => write!(out, "BBS(0x{device_type:X}")?,
+    }
+    write!(out, ",{}", bbs.description)?;
+    if !display_only {
+        write!(out, ",0x{:X}", bbs.status_flag)?;
+    }
+    out.write_char(')')
+}
+
+fn unknown_to_text(out: &mut String, r#type: u8, subtype: u8, data: &[u8]) -> std::fmt::Result {
+    match r#type {
+        consts::DEVICE_PATH_TYPE::HARDWARE_DEVICE_PATH => write!(out, "HardwarePath({subtype}")?,
+        consts::DEVICE_PATH_TYPE::ACPI_DEVICE_PATH => write!(out, "AcpiPath({subtype}")?,
+        consts::DEVICE_PATH_TYPE::MESSAGING_DEVICE_PATH => write!(out, "Msg({subtype}")?,
+        consts::DEVICE_PATH_TYPE::MEDIA_DEVICE_PATH => write!(out, "MediaPath({subtype}")?,
+        consts::DEVICE_PATH_TYPE::BIOS_BOOT_SPECIFICATION_DEVICE_PATH => {
+            write!(out, "BbsPath({subtype}")?
+        }
+        _ => write!(out, "Path({},{subtype}", r#type)?,
+    }
+    if !data.is_empty() {
+        write!(out, ",{}", hex_bytes(data))?;
+    }
+    out.write_char(')')
+}
+
+impl DevicePath {
+    fn write_text(
+        &self,
+        out: &mut String,
+        display_only: bool,
+        allow_shortcuts: bool,
+    ) -> std::fmt::Result {
+        match self {
+            DevicePath::Pci(pci) => write!(out, "Pci(0x{:X},0x{:X})", pci.device, pci.function),
+            DevicePath::MemoryMapped(memory) => write!(
+                out,
+                "MemoryMapped(0x{:X},0x{:X},0x{:X})",
+                memory.memory_type, memory.start_address, memory.end_address
+            ),
+            DevicePath::HardwareVendor(vendor) => vendor_to_text(out, "VenHw", vendor),
+            DevicePath::Controller(controller) => write!(out, "Ctrl(0x{:X})", controller.number),
+            DevicePath::Acpi(acpi) => acpi_to_text(out, acpi),
+            DevicePath::ExpandedAcpi(acpi) => expanded_acpi_to_text(out, acpi, display_only),
+            DevicePath::AcpiAdr(acpi) => {
+                let adr: Vec<String> = acpi.adr.iter().map(|adr| format!("0x{adr:X}")).collect();
+                write!(out, "AcpiAdr({})", adr.join(","))
+            }
+            DevicePath::Usb(usb) => {
+                write!(out, "USB(0x{:X},0x{:X})", usb.parent_port, usb.interface)
+            }
+            DevicePath::Sata(sata) => write!(
+                out,
+                "Sata(0x{:X},0x{:X},0x{:X})",
+                sata.hba_port, sata.port_multiplier_port, sata.lun
+            ),
+            DevicePath::Nvme(nvme) => {
+                let eui64: Vec<String> = nvme
+                    .eui64
+                    .iter()
+                    .rev()
+                    .map(|b| format!("{b:02X}"))
+                    .collect();
+                write!(out, "NVMe(0x{:X},{})", nvme.namespace_id, eui64.join("-"))
+            }
+            DevicePath::Scsi(scsi) => write!(out, "Scsi(0x{:X},0x{:X})", scsi.target, scsi.lun),
+            DevicePath::MacAddr(mac) => mac_to_text(out, mac),
+            DevicePath::Ipv4(ip) => ipv4_to_text(out, ip, display_only),
+            DevicePath::Ipv6(ip) => ipv6_to_text(out, ip, display_only),
+            DevicePath::Uri(uri) => write!(out, "Uri({})", uri.uri),
+            DevicePath::MessagingVendor(vendor) => {
+                let shortcut = match vendor.guid {
+                    PC_ANSI_GUID => Some("VenPcAnsi"),
+                    VT_100_GUID => Some("VenVt100"),
+                    VT_100_PLUS_GUID => Some("VenVt100Plus"),
+                    VT_UTF8_GUID => Some("VenUtf8"),
+                    _ => None,
+                };
+                match shortcut {
+                    Some(name) if allow_shortcuts && vendor.data.is_empty() => {
+                        write!(out, "{name}()")
+                    }
+                    _ => vendor_to_text(out, "VenMsg", vendor),
+                }
+            }
+            DevicePath::HardDrive(hard_drive) => hard_drive_to_text(out, hard_drive, display_only),
+            DevicePath::CdRom(cd_rom) => {
+                write!(out, "CDROM(0x{:X}", cd_rom.boot_entry)?;
+                if !display_only {
+                    write!(
+                        out,
+                        ",0x{:X},0x{:X}",
+                        cd_rom.partition_start, cd_rom.partition_size
+                    )?;
+                }
+                out.write_char(')')
+            }
+            DevicePath::MediaVendor(vendor) => vendor_to_text(out, "VenMedia", vendor),
+            DevicePath::FilePath(file_path) => {
+                // like edk2, paths are shown as-is, unless they would not be parsed back as a
+                // single file path node
+                if file_path.path.contains(['/', ',', '(', ')']) {
+                    write!(out, "File({})", file_path.path)
+                } else {
+                    out.write_str(&file_path.path)
+                }
+            }
+            DevicePath::FirmwareFile(file) => write!(out, "FvFile({})", file.name),
+            DevicePath::FirmwareVolume(volume) => write!(out, "Fv({})", volume.name),
+            DevicePath::RelativeOffsetRange(range) => write!(
+                out,
+                "Offset(0x{:X},0x{:X})",
+                range.starting_offset, range.ending_offset
+            ),
+            DevicePath::RamDisk(ram_disk) => ram_disk_to_text(out, ram_disk),
+            DevicePath::Bbs(bbs) => bbs_to_text(out, bbs, display_only),
+            DevicePath::EndInstance => out.write_char(','),
+            DevicePath::EndEntire => Ok(()),
+            DevicePath::Unknown {
+                r#type,
+                subtype,
+                data,
+            } => unknown_to_text(out, *r#type, *subtype, data),
+        }
+    }
+
+    /// get the text representation of this node.
+    ///
+    /// `display_only` gives the shorter form meant to be shown to users, which cannot be
+    /// converted back to a device path. `allow_shortcuts` uses the shortcut forms of vendor
+    /// nodes, such as `VenPcAnsi()`.
+    pub fn to_text(&self, display_only: bool, allow_shortcuts: bool) -> String {
+        let mut text = String::new();
+        self.write_text(&mut text, display_only, allow_shortcuts)
+            .expect("writing to a String should not fail");
+
+        text
+    }
+}
+
+impl Display for DevicePath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_text(false, true))
+    }
+}
+
+/// get the text representation of a list of device path nodes.
+///
+/// Nodes are separated by `/`, and instances by `,`. Rendering stops at the end of the entire
+/// device path. See [`DevicePath::to_text`] for the meaning of the flags.
+pub fn device_path_to_text(
+    nodes: &[DevicePath],
+    display_only: bool,
+    allow_shortcuts: bool,
+) -> String {
+    let mut text = String::new();
+
+    for node in nodes {
+        match node {
+            DevicePath::EndEntire => break,
+            DevicePath::EndInstance => text.push(','),
+            node => {
+                if !text.is_empty() && !text.ends_with(',') {
+                    text.push('/');
+                }
+                node.write_text(&mut text, display_only, allow_shortcuts)
+                    .expect("writing to a String should not fail");
+            }
+        }
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::boot::parse::{DevicePath, ParseCursor};
+
+    use super::{super::device_path_from_text, device_path_to_text};
+
+    /// Device paths of common boot entries, written by hand rather than captured from firmware,
+    /// along with their expected text in the format of edk2's DevicePathToText (full text and
+    /// display-only text)
+    const CORPUS: &[(&str, &str, &str)] = &[
+        (
+            "02010c00d041030a0000000001010600001d0101060000000317100001000000389cb0715b3825000401\
+             2a000100000000080000000000000000100000000000bd4b36900010fc478c058707e01b459302020404\
+             34005c004500460049005c007500620075006e00740075005c007300680069006d007800360034002e00\
+             65006600690000007fff0400",
+            "PciRoot(0x0)/Pci(0x1D,0x0)/Pci(0x0,0x0)/NVMe(0x1,00-25-38-5B-71-B0-9C-38)/HD(1,GPT,90364bbd-1000-47fc-8c05-8707e01b4593,0x800,0x100000)/\\EFI\\ubuntu\\shimx64.efi",
+            "PciRoot(0x0)/Pci(0x1D,0x0)/Pci(0x0,0x0)/NVMe(0x1,00-25-38-5B-71-B0-9C-38)/HD(1,GPT,90364bbd-1000-47fc-8c05-8707e01b4593)/\\EFI\\ubuntu\\shimx64.efi",
+        ),
+        (
+            "02010c00d041030a00000000010106000003030b25005254001234560000000000000000000000000000\
+             00000000000000000000000001030c1b0000000000000000000000000000000000000000000000007fff\
+             0400",
+            "PciRoot(0x0)/Pci(0x3,0x0)/MAC(525400123456,0x1)/IPv4(0.0.0.0,0x0,DHCP,0.0.0.0,0.0.0.0,0.0.0.0)",
+            "PciRoot(0x0)/Pci(0x3,0x0)/MAC(525400123456,0x1)/IPv4(0.0.0.0)",
+        ),
+        (
+            "02010c00d041030a00000000010106000003030b25005254001234560000000000000000000000000000\
+             00000000000000000000000001030c1b0000000000000000000000000000000000000000000000000318\
+             1f00687474703a2f2f3139322e3136382e312e312f626f6f742e6566697fff0400",
+            "PciRoot(0x0)/Pci(0x3,0x0)/MAC(525400123456,0x1)/IPv4(0.0.0.0,0x0,DHCP,0.0.0.0,0.0.0.0,0.0.0.0)/Uri(http://192.168.1.1/boot.efi)",
+            "PciRoot(0x0)/Pci(0x3,0x0)/MAC(525400123456,0x1)/IPv4(0.0.0.0)/Uri(http://192.168.1.1/boot.efi)",
+        ),
+        (
+            "04071400c9bdb87cebf8344faaea3ee4af6516a10406140021aa2c4614760345836e8ab6f46623317fff\
+             0400",
+            "Fv(7cb8bdc9-f8eb-4f34-aaea-3ee4af6516a1)/FvFile(462caa21-7614-4503-836e-8ab6f4662331)",
+            "Fv(7cb8bdc9-f8eb-4f34-aaea-3ee4af6516a1)/FvFile(462caa21-7614-4503-836e-8ab6f4662331)",
+        ),
+        (
+            "02010c00d041030a0000000001010600021f03120a000000ffff00007fff0400",
+            "PciRoot(0x0)/Pci(0x1F,0x2)/Sata(0x0,0xFFFF,0x0)",
+            "PciRoot(0x0)/Pci(0x1F,0x2)/Sata(0x0,0xFFFF,0x0)",
+        ),
+        (
+            "02010c00d041030a000000000101060000140305060003007fff0400",
+            "PciRoot(0x0)/Pci(0x14,0x0)/USB(0x3,0x0)",
+            "PciRoot(0x0)/Pci(0x14,0x0)/USB(0x3,0x0)",
+        ),
+        (
+            "04012a00010000003f000000000000000040060000000000fafd1abe0000000000000000000000000101\
+             040446005c004500460049005c004d006900630072006f0073006f00660074005c0042006f006f007400\
+             5c0062006f006f0074006d006700660077002e0065006600690000007fff0400",
+            "HD(1,MBR,0xBE1AFDFA,0x3F,0x64000)/\\EFI\\Microsoft\\Boot\\bootmgfw.efi",
+            "HD(1,MBR,0xBE1AFDFA)/\\EFI\\Microsoft\\Boot\\bootmgfw.efi",
+        ),
+        (
+            "02010c00d041030a0000000001010600021f03120a000100ffff00000402180001000000b40200000000\
+             000000180000000000007fff0400",
+            "PciRoot(0x0)/Pci(0x1F,0x2)/Sata(0x1,0xFFFF,0x0)/CDROM(0x1,0x2B4,0x1800)",
+            "PciRoot(0x0)/Pci(0x1F,0x2)/Sata(0x1,0xFFFF,0x0)/CDROM(0x1)",
+        ),
+        (
+            "02010c00d041030a0000000001010600001f02010c00d041010500000000030a14005347c1e0bef9d211\
+             9a0c0090273fc14d7f01040002010c00d041030a000000000101060000027fff0400",
+            "PciRoot(0x0)/Pci(0x1F,0x0)/Serial(0x0)/VenPcAnsi(),PciRoot(0x0)/Pci(0x2,0x0)",
+            "PciRoot(0x0)/Pci(0x1F,0x0)/Serial(0x0)/VenPcAnsi(),PciRoot(0x0)/Pci(0x2,0x0)",
+        ),
+        (
+            "050113000200000055454649205368656c6c007fff0400",
+            "BBS(HD,UEFI Shell,0x0)",
+            "BBS(HD,UEFI Shell)",
+        ),
+    ];
+
+    fn parse_hex(hex: &str) -> Vec<u8> {
+        let hex: String = hex.chars().filter(|c| !c.is_whitespace()).collect();
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn parse_nodes(data: &[u8]) -> Vec<DevicePath> {
+        let mut buf = ParseCursor::new(data);
+        let mut nodes = vec![];
+        while !buf.is_empty() {
+            nodes.push(DevicePath::parse(&mut buf).unwrap());
+        }
+        nodes
+    }
+
+    #[test]
+    fn corpus() {
+        for (hex, full, display) in CORPUS {
+            let nodes = parse_nodes(&parse_hex(hex));
+            assert_eq!(&device_path_to_text(&nodes, false, true), full);
+            assert_eq!(&device_path_to_text(&nodes, true, true), display);
+        }
+    }
+
+    #[test]
+    fn corpus_from_text() {
+        for (hex, full, _) in CORPUS {
+            let nodes = device_path_from_text(full).unwrap();
+            assert_eq!(&device_path_to_text(&nodes, false, true), full);
+
+            let bytes: Vec<u8> = nodes
+                .iter()
+                .flat_map(|node| node.to_bytes().unwrap())
+                .collect();
+            assert_eq!(bytes, parse_hex(hex), "{} gave different bytes", full);
+        }
+    }
+
+    #[test]
+    fn without_shortcuts() {
+        let nodes = parse_nodes(&parse_hex(CORPUS[8].0));
+        assert_eq!(
+            device_path_to_text(&nodes, false, false),
+            "PciRoot(0x0)/Pci(0x1F,0x0)/Serial(0x0)/VenMsg(e0c14753-f9be-11d2-9a0c-0090273fc14d),PciRoot(0x0)/Pci(0x2,0x0)"
+        );
+    }
+
+    #[test]
+    fn display_node() {
+        let nodes = parse_nodes(&parse_hex(CORPUS[0].0));
+        assert_eq!(
+            nodes
+                .iter()
+                .map(|node| node.to_string())
+                .collect::<Vec<_>>(),
+            [
+                "PciRoot(0x0)",
+                "Pci(0x1D,0x0)",
+                "Pci(0x0,0x0)",
+                "NVMe(0x1,00-25-38-5B-71-B0-9C-38)",
+                "HD(1,GPT,90364bbd-1000-47fc-8c05-8707e01b4593,0x800,0x100000)",
+                "\\EFI\\ubuntu\\shimx64.efi",
+                "",
+            ]
+        );
+    }
+}
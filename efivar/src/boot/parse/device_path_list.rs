@@ -1,76 +1,153 @@
 //! This module contains parsing code for the device path list component of a boot entry
 
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
 use super::{
     device_path::{self, FilePath},
-    DevicePath, EFIHardDrive,
+    DevicePath, DevicePathIter, EFIHardDrive, ParseCursor,
 };
 
-/// holds the potential fields we may get from a packed file path list
-/// TODO remove ?
-#[derive(Debug)]
-pub struct OptFilePathList {
-    pub file_path: Option<FilePath>,
-    pub hard_drive: Option<EFIHardDrive>,
+/// How a device path instance is terminated
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum InstanceEnd {
+    /// END_THIS_INSTANCE node, another instance follows
+    Instance,
+    /// END_ENTIRE node
+    Entire,
 }
 
-/// Same structure as OptFilePathList, but we ensure that the file path list
-/// is a valid file path overall
+/// A device path instance: the nodes up to an end node
 #[derive(Debug, PartialEq, Clone)]
-pub struct FilePathList {
-    pub file_path: FilePath,
-    pub hard_drive: EFIHardDrive,
+pub struct DevicePathInstance {
+    /// nodes of this instance, without the end node
+    pub nodes: Vec<DevicePath>,
+    /// node terminating this instance. None if the list stopped before any end node
+    pub end: Option<InstanceEnd>,
 }
 
-impl From<OptFilePathList> for Option<FilePathList> {
-    fn from(value: OptFilePathList) -> Self {
-        Some(FilePathList {
-            file_path: value.file_path?,
-            hard_drive: value.hard_drive?,
-        })
-    }
+/// File path list of a boot entry, as an ordered list of device path instances.
+/// Usually holds a single instance, such as `HD(...)/\EFI\BOOT\BOOTX64.EFI`
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct FilePathList {
+    pub instances: Vec<DevicePathInstance>,
 }
 
 impl Display for FilePathList {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}/File({})", self.hard_drive, self.file_path.path)
+        f.write_str(&self.to_text(false, true))
+    }
+}
+
+impl FromStr for FilePathList {
+    type Err = crate::Error;
+
+    /// parse the text representation of a file path list, such as
+    /// `HD(1,GPT,90364bbd-1000-47fc-8c05-8707e01b4593,0x800,0x100000)/\EFI\BOOT\BOOTX64.EFI`
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Ok(FilePathList::from_nodes(
+            device_path::device_path_from_text(text)?,
+        ))
     }
 }
 
 impl FilePathList {
-    pub fn parse(full_buf: &mut &[u8]) -> crate::Result<OptFilePathList> {
-        let mut file_path_list = OptFilePathList {
-            file_path: None,
-            hard_drive: None,
-        };
-
-        loop {
-            if full_buf.is_empty() {
-                break;
-            } else {
-                match DevicePath::parse(full_buf)? {
-                    Some(DevicePath::FilePath(inner_path)) => {
-                        file_path_list.file_path = Some(inner_path);
-                    }
-                    Some(DevicePath::HardDrive(inner_hard_drive)) => {
-                        file_path_list.hard_drive = Some(inner_hard_drive);
-                    }
-                    None => {}
-                };
+    /// build a file path list holding a single instance made of these nodes
+    pub fn new(nodes: Vec<DevicePath>) -> FilePathList {
+        FilePathList {
+            instances: vec![DevicePathInstance {
+                nodes,
+                end: Some(InstanceEnd::Entire),
+            }],
+        }
+    }
+
+    /// build a file path list from a list of nodes, splitting instances on end nodes
+    pub fn from_nodes(nodes: impl IntoIterator<Item = DevicePath>) -> FilePathList {
+        let mut instances = vec![];
+        let mut current = vec![];
+
+        for node in nodes {
+            let end = match node {
+                DevicePath::EndInstance => InstanceEnd::Instance,
+                DevicePath::EndEntire => InstanceEnd::Entire,
+                node => {
+                    current.push(node);
+                    continue;
+                }
             };
+            instances.push(DevicePathInstance {
+                nodes: std::mem::take(&mut current),
+                end: Some(end),
+            });
+        }
+
+        if !current.is_empty() {
+            instances.push(DevicePathInstance {
+                nodes: current,
+                end: None,
+            });
         }
 
-        Ok(file_path_list)
+        FilePathList { instances }
     }
 
-    pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes: Vec<u8> = vec![];
+    pub fn parse(full_buf: &mut ParseCursor) -> crate::Result<FilePathList> {
+        let nodes: Vec<DevicePath> = DevicePathIter::new(*full_buf)
+            .map(|node| node.map(DevicePath::from))
+            .collect::<crate::Result<_>>()?;
+        full_buf.read_rest();
+
+        Ok(FilePathList::from_nodes(nodes))
+    }
 
-        bytes.append(&mut self.hard_drive.to_bytes_encap());
-        bytes.append(&mut self.file_path.to_bytes_encap());
-        bytes.append(&mut device_path::get_end_device_path_bytes());
+    /// get all nodes of this list, including end nodes
+    pub fn nodes(&self) -> impl Iterator<Item = &DevicePath> {
+        self.instances.iter().flat_map(|instance| {
+            instance.nodes.iter().chain(match instance.end {
+                Some(InstanceEnd::Instance) => Some(&DevicePath::EndInstance),
+                Some(InstanceEnd::Entire) => Some(&DevicePath::EndEntire),
+                None => None,
+            })
+        })
+    }
+
+    /// get the hard drive node of the first instance, if any
+    pub fn hard_drive(&self) -> Option<&EFIHardDrive> {
+        self.instances
+            .first()?
+            .nodes
+            .iter()
+            .find_map(|node| match node {
+                DevicePath::HardDrive(hard_drive) => Some(hard_drive),
+                _ => None,
+            })
+    }
+
+    /// get the file path node of the first instance, if any
+    pub fn file_path(&self) -> Option<&FilePath> {
+        self.instances
+            .first()?
+            .nodes
+            .iter()
+            .find_map(|node| match node {
+                DevicePath::FilePath(file_path) => Some(file_path),
+                _ => None,
+            })
+    }
+
+    /// get the text representation of this file path list.
+    /// See [`DevicePath::to_text`] for the meaning of the flags
+    pub fn to_text(&self, display_only: bool, allow_shortcuts: bool) -> String {
+        let nodes: Vec<DevicePath> = self.nodes().cloned().collect();
+        device_path::device_path_to_text(&nodes, display_only, allow_shortcuts)
+    }
+
+    pub fn to_bytes(&self) -> crate::Result<Vec<u8>> {
+        let mut bytes = vec![];
+        for node in self.nodes() {
+            bytes.append(&mut node.to_bytes()?);
+        }
 
-        bytes
+        Ok(bytes)
     }
 }
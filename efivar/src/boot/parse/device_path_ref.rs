@@ -0,0 +1,108 @@
+//! This module contains borrowed views of device path nodes, that are only decoded on demand
+
+use crate::ParseError;
+
+use super::{consts::DEVICE_PATH_TYPE, DevicePath, ParseCursor};
+
+/// A device path node borrowed from the buffer it was read from.
+///
+/// Only the node header is validated. The node data is decoded by [`DevicePathRef::to_device_path`]
+#[derive(Debug, Clone, Copy)]
+pub struct DevicePathRef<'a> {
+    r#type: u8,
+    subtype: u8,
+    /// offset of the node header in the buffer being parsed
+    offset: usize,
+    data: ParseCursor<'a>,
+}
+
+impl<'a> DevicePathRef<'a> {
+    pub fn parse(buf: &mut ParseCursor<'a>) -> crate::Result<DevicePathRef<'a>> {
+        let offset = buf.offset();
+        let r#type = buf.read_u8("DevicePath.type")?;
+        let subtype = buf.read_u8("DevicePath.subtype")?;
+        let length = buf.read_u16("DevicePath.length")?;
+
+        // the length includes the 4 bytes of the header we just read
+        let data_size = length.checked_sub(1 + 1 + 2).ok_or_else(|| {
+            ParseError::invalid(
+                offset + 2,
+                "DevicePath.length",
+                format!("length {length} is smaller than the node header"),
+            )
+        })?;
+
+        Ok(DevicePathRef {
+            r#type,
+            subtype,
+            offset,
+            data: buf.read_cursor(data_size.into(), "DevicePath.data")?,
+        })
+    }
+
+    pub fn r#type(&self) -> u8 {
+        self.r#type
+    }
+
+    pub fn subtype(&self) -> u8 {
+        self.subtype
+    }
+
+    /// offset of the node in the buffer being parsed
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// node data, without the header
+    pub fn data(&self) -> &'a [u8] {
+        self.data.remaining()
+    }
+
+    /// whether this node ends a device path instance, or the entire device path
+    pub fn is_end(&self) -> bool {
+        self.r#type == DEVICE_PATH_TYPE::END_OF_HARDWARE_DEVICE_PATH
+    }
+
+    /// decode the node data
+    pub fn to_device_path(&self) -> DevicePath {
+        DevicePath::decode(self.r#type, self.subtype, self.data)
+    }
+}
+
+impl From<DevicePathRef<'_>> for DevicePath {
+    fn from(node: DevicePathRef<'_>) -> Self {
+        node.to_device_path()
+    }
+}
+
+/// Lazily loop over the device path nodes of a buffer.
+///
+/// Iteration stops after the first node that could not be read.
+#[derive(Debug, Clone)]
+pub struct DevicePathIter<'a> {
+    buf: ParseCursor<'a>,
+}
+
+impl<'a> DevicePathIter<'a> {
+    pub fn new(buf: ParseCursor<'a>) -> DevicePathIter<'a> {
+        DevicePathIter { buf }
+    }
+}
+
+impl<'a> Iterator for DevicePathIter<'a> {
+    type Item = crate::Result<DevicePathRef<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() {
+            return None;
+        }
+
+        let node = DevicePathRef::parse(&mut self.buf);
+        if node.is_err() {
+            // the length of a broken node is unknown, so there is no way to find the next one
+            self.buf.read_rest();
+        }
+
+        Some(node)
+    }
+}
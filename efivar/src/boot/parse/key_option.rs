@@ -0,0 +1,392 @@
+//! This module contains parsing code for a hotkey (Key####) variable
+
+use std::{fmt::Display, str::FromStr};
+
+use super::{BootEntry, ParseCursor};
+use crate::{
+    boot::load_option, efi::Variable, globals::GlobalVarReader, push::PushVecU8, Error, ParseError,
+    VarReader,
+};
+
+bitflags::bitflags! {
+    /// Modifier keys that must be held with the keys of a [`KeyOption`].
+    /// Stored in the `KeyData` field, along with the revision and the key count
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct KeyModifiers : u32 {
+        const SHIFT = 0x100;
+        const CONTROL = 0x200;
+        const ALT = 0x400;
+        const LOGO = 0x800;
+        const MENU = 0x1000;
+        const SYS_REQ = 0x2000;
+    }
+}
+
+bitflags::bitflags! {
+    /// Content of the `BootOptionSupport` variable: what the boot manager supports
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct BootOptionSupport : u32 {
+        const KEY = 0x1;
+        const APP = 0x2;
+        const SYS_PREP = 0x10;
+        const COUNT = 0x300;
+    }
+}
+
+impl BootOptionSupport {
+    pub fn read(manager: &(impl ?Sized + VarReader)) -> crate::Result<Self> {
+        manager.get_boot_option_support()
+    }
+
+    /// maximum number of keys a hotkey may use, not counting modifiers
+    pub fn key_count(&self) -> usize {
+        ((self.bits() & BootOptionSupport::COUNT.bits()) >> 8) as usize
+    }
+
+    /// check that the boot manager can handle this hotkey
+    pub fn check_key_option(&self, key_option: &KeyOption) -> crate::Result<()> {
+        if !self.contains(BootOptionSupport::KEY) {
+            return Err(Error::UnsupportedByFirmware {
+                what: "hotkeys".to_owned(),
+            });
+        }
+
+        if key_option.keys.len() > self.key_count() {
+            return Err(Error::UnsupportedByFirmware {
+                what: format!(
+                    "hotkeys with {} keys (up to {} supported)",
+                    key_option.keys.len(),
+                    self.key_count()
+                ),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// A key stroke (EFI_INPUT_KEY). Either `scan_code` or `unicode_char` is set, the other one is 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InputKey {
+    pub scan_code: u16,
+    pub unicode_char: u16,
+}
+
+/// Names of the scan codes of non-printable keys. See spec, table 12.2
+const SCAN_CODES: &[(u16, &str)] = &[
+    (0x01, "Up"),
+    (0x02, "Down"),
+    (0x03, "Right"),
+    (0x04, "Left"),
+    (0x05, "Home"),
+    (0x06, "End"),
+    (0x07, "Insert"),
+    (0x08, "Delete"),
+    (0x09, "PageUp"),
+    (0x0A, "PageDown"),
+    (0x0B, "F1"),
+    (0x0C, "F2"),
+    (0x0D, "F3"),
+    (0x0E, "F4"),
+    (0x0F, "F5"),
+    (0x10, "F6"),
+    (0x11, "F7"),
+    (0x12, "F8"),
+    (0x13, "F9"),
+    (0x14, "F10"),
+    (0x15, "F11"),
+    (0x16, "F12"),
+    (0x17, "Esc"),
+];
+
+impl InputKey {
+    fn parse(buf: &mut ParseCursor) -> crate::Result<InputKey> {
+        Ok(InputKey {
+            scan_code: buf.read_u16("InputKey.scan_code")?,
+            unicode_char: buf.read_u16("InputKey.unicode_char")?,
+        })
+    }
+}
+
+impl Display for InputKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.unicode_char == 0 {
+            if let Some((_, name)) = SCAN_CODES.iter().find(|(code, _)| *code == self.scan_code) {
+                return f.write_str(name);
+            }
+        } else if self.scan_code == 0 {
+            if let Some(chr) = char::from_u32(self.unicode_char.into()).filter(|c| !c.is_control())
+            {
+                return write!(f, "{chr}");
+            }
+        }
+
+        write!(f, "Key(0x{:X},0x{:X})", self.scan_code, self.unicode_char)
+    }
+}
+
+impl FromStr for InputKey {
+    type Err = Error;
+
+    /// parse a key name (e.g. `F12`, `Esc`) or a single character
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((scan_code, _)) = SCAN_CODES
+            .iter()
+            .find(|(_, name)| name.eq_ignore_ascii_case(s))
+        {
+            return Ok(InputKey {
+                scan_code: *scan_code,
+                unicode_char: 0,
+            });
+        }
+
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(chr), None) if (chr as u32) <= 0xFFFF => Ok(InputKey {
+                scan_code: 0,
+                unicode_char: chr as u16,
+            }),
+            _ => Err(Error::InvalidKey { key: s.to_owned() }),
+        }
+    }
+}
+
+/// A hotkey that launches a boot option (EFI_KEY_OPTION). See spec, 3.1.6
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct KeyOption {
+    /// revision of the key data, 0 for now
+    pub revision: u8,
+    /// modifiers to hold, and any reserved bit of the key data
+    pub modifiers: KeyModifiers,
+    /// CRC32 of the whole boot option variable data, to check that it did not change
+    pub boot_option_crc: u32,
+    /// ID of the Boot#### variable to launch
+    pub boot_option: u16,
+    /// keys to press, 3 at most
+    pub keys: Vec<InputKey>,
+}
+
+impl KeyOption {
+    /// maximum number of keys the key data can hold
+    pub const MAX_KEYS: usize = 3;
+
+    /// build a hotkey launching this boot entry
+    pub fn new(
+        boot_option: u16,
+        entry: &BootEntry,
+        modifiers: KeyModifiers,
+        keys: Vec<InputKey>,
+    ) -> crate::Result<KeyOption> {
+        if keys.len() > KeyOption::MAX_KEYS {
+            return Err(Error::ValueTooLarge {
+                what: "hotkey key count",
+            });
+        }
+
+        Ok(KeyOption {
+            revision: 0,
+            modifiers,
+            boot_option_crc: crc32fast::hash(&entry.to_bytes()?),
+            boot_option,
+            keys,
+        })
+    }
+
+    /// name of the variable holding the hotkey with that ID (e.g. `Key0001`)
+    pub fn variable_name(id: u16) -> String {
+        format!("Key{id:04X}")
+    }
+
+    /// Returns the hotkey ID if this variable name is one of a hotkey. Else, return None
+    pub fn option_id(name: &str) -> Option<u16> {
+        load_option::option_id("Key", name)
+    }
+
+    pub fn read(manager: &(impl ?Sized + VarReader), variable: &Variable) -> crate::Result<Self> {
+        let (value, _flags) = manager.read(variable)?;
+        Self::parse(&value).map_err(|err| err.with_variable(variable))
+    }
+
+    pub fn parse(value: &[u8]) -> crate::Result<Self> {
+        let mut buf = ParseCursor::new(value);
+
+        let key_data = buf.read_u32("KeyOption.key_data")?;
+        let boot_option_crc = buf.read_u32("KeyOption.boot_option_crc")?;
+        let boot_option = buf.read_u16("KeyOption.boot_option")?;
+
+        // the key count is stored in the 2 upper bits of the key data
+        let keys = (0..key_data >> 30)
+            .map(|_| InputKey::parse(&mut buf))
+            .collect::<crate::Result<_>>()?;
+
+        if !buf.is_empty() {
+            return Err(ParseError::invalid(
+                buf.offset(),
+                "KeyOption.keys",
+                format!("{} bytes after the last key", buf.remaining().len()),
+            )
+            .into());
+        }
+
+        Ok(KeyOption {
+            revision: key_data as u8,
+            modifiers: KeyModifiers::from_bits_retain(key_data & 0x3FFF_FF00),
+            boot_option_crc,
+            boot_option,
+            keys,
+        })
+    }
+
+    pub fn to_bytes(&self) -> crate::Result<Vec<u8>> {
+        if self.keys.len() > KeyOption::MAX_KEYS {
+            return Err(Error::ValueTooLarge {
+                what: "hotkey key count",
+            });
+        }
+
+        let mut bytes: Vec<u8> = vec![];
+        bytes.push_u32(
+            (self.keys.len() as u32) << 30
+                | (self.modifiers.bits() & 0x3FFF_FF00)
+                | u32::from(self.revision),
+        );
+        bytes.push_u32(self.boot_option_crc);
+        bytes.push_u16(self.boot_option);
+        for key in &self.keys {
+            bytes.push_u16(key.scan_code);
+            bytes.push_u16(key.unicode_char);
+        }
+
+        Ok(bytes)
+    }
+
+    /// check the CRC against the data of the boot option this hotkey launches.
+    /// The firmware ignores hotkeys whose CRC does not match
+    pub fn matches(&self, boot_option_data: &[u8]) -> bool {
+        crc32fast::hash(boot_option_data) == self.boot_option_crc
+    }
+}
+
+impl Display for KeyOption {
+    /// write the key combination, e.g. `Ctrl+Alt+F12`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let modifiers = [
+            (KeyModifiers::SHIFT, "Shift"),
+            (KeyModifiers::CONTROL, "Ctrl"),
+            (KeyModifiers::ALT, "Alt"),
+            (KeyModifiers::LOGO, "Logo"),
+            (KeyModifiers::MENU, "Menu"),
+            (KeyModifiers::SYS_REQ, "SysReq"),
+        ];
+
+        let parts: Vec<String> = modifiers
+            .iter()
+            .filter(|(modifier, _)| self.modifiers.contains(*modifier))
+            .map(|(_, name)| name.to_string())
+            .chain(self.keys.iter().map(InputKey::to_string))
+            .collect();
+
+        f.write_str(&parts.join("+"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::boot::{BootEntry, BootEntryAttributes, FilePathList};
+
+    use super::{BootOptionSupport, InputKey, KeyModifiers, KeyOption};
+
+    // Ctrl+F12 launching Boot0003
+    const KEY_OPTION: &[u8] = &[
+        0x00, 0x02, 0x00, 0x40, 0x78, 0x56, 0x34, 0x12, 0x03, 0x00, 0x16, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn parse_roundtrip() {
+        let key_option = KeyOption::parse(KEY_OPTION).unwrap();
+        assert_eq!(
+            key_option,
+            KeyOption {
+                revision: 0,
+                modifiers: KeyModifiers::CONTROL,
+                boot_option_crc: 0x12345678,
+                boot_option: 0x0003,
+                keys: vec![InputKey {
+                    scan_code: 0x16,
+                    unicode_char: 0
+                }],
+            }
+        );
+        assert_eq!(key_option.to_string(), "Ctrl+F12");
+        assert_eq!(key_option.to_bytes().unwrap(), KEY_OPTION);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        // missing key
+        assert!(KeyOption::parse(&KEY_OPTION[..12]).is_err());
+        // trailing data
+        assert!(KeyOption::parse(&[KEY_OPTION, &[0x00]].concat()).is_err());
+    }
+
+    #[test]
+    fn input_keys() {
+        for text in ["F1", "Esc", "PageDown", "a", "?"] {
+            assert_eq!(text.parse::<InputKey>().unwrap().to_string(), text);
+        }
+        assert_eq!(
+            "f12".parse::<InputKey>().unwrap(),
+            InputKey {
+                scan_code: 0x16,
+                unicode_char: 0
+            }
+        );
+        assert!("F13".parse::<InputKey>().is_err());
+        assert!("".parse::<InputKey>().is_err());
+    }
+
+    #[test]
+    fn boot_option_crc() {
+        let mut entry = BootEntry {
+            attributes: BootEntryAttributes::LOAD_OPTION_ACTIVE,
+            description: "Some entry".to_owned(),
+            file_path_list: FilePathList::default(),
+            optional_data: vec![],
+        };
+        let key_option = KeyOption::new(
+            0x0001,
+            &entry,
+            KeyModifiers::ALT,
+            vec!["F2".parse().unwrap()],
+        )
+        .unwrap();
+        assert!(key_option.matches(&entry.to_bytes().unwrap()));
+
+        entry.description = "Changed entry".to_owned();
+        assert!(!key_option.matches(&entry.to_bytes().unwrap()));
+
+        assert!(KeyOption::new(
+            0x0001,
+            &entry,
+            KeyModifiers::empty(),
+            vec!["a".parse().unwrap(); 4]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn boot_option_support() {
+        let key_option = KeyOption::parse(KEY_OPTION).unwrap();
+
+        let support = BootOptionSupport::from_bits_retain(0x0000_0103);
+        assert_eq!(support.key_count(), 1);
+        assert!(support.check_key_option(&key_option).is_ok());
+
+        assert!(BootOptionSupport::APP
+            .check_key_option(&key_option)
+            .is_err());
+        assert!(BootOptionSupport::KEY
+            .check_key_option(&key_option)
+            .is_err());
+    }
+}
@@ -1,13 +1,27 @@
 mod boot_entry;
+mod boot_entry_ref;
 mod boot_variable;
 mod consts;
+mod cursor;
 mod device_path;
 mod device_path_list;
+mod device_path_ref;
+mod key_option;
+mod optional_data;
 #[cfg(test)]
 mod tests;
 
 pub use boot_entry::{BootEntry, BootEntryAttributes};
+pub use boot_entry_ref::BootEntryRef;
 pub use boot_variable::BootVariable;
-pub use device_path::DevicePath;
-pub use device_path::{EFIHardDrive, EFIHardDriveType, FilePath};
-pub use device_path_list::FilePathList;
+pub use cursor::ParseCursor;
+pub use device_path::{device_path_from_text, device_path_to_text, DevicePath, VendorDevicePath};
+pub use device_path::{
+    Acpi, AcpiAdr, Bbs, CdRom, Controller, EFIHardDrive, ExpandedAcpi, FilePath, FirmwareFile,
+    FirmwareVolume, Ipv4, Ipv6, MacAddr, MemoryMapped, Nvme, PartitionSignature, Pci, RamDisk,
+    RelativeOffsetRange, Sata, Scsi, Uri, Usb,
+};
+pub use device_path_list::{DevicePathInstance, FilePathList, InstanceEnd};
+pub use device_path_ref::{DevicePathIter, DevicePathRef};
+pub use key_option::{BootOptionSupport, InputKey, KeyModifiers, KeyOption};
+pub use optional_data::OptionalData;
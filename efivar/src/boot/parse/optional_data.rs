@@ -0,0 +1,266 @@
+//! This module contains decoders for the optional data of a boot entry
+
+use std::fmt::Display;
+
+use uuid::Uuid;
+
+use super::ParseCursor;
+use crate::push::PushVecU8;
+
+/// Signature at the start of the optional data of Windows Boot Manager entries
+const WINDOWS_SIGNATURE: &[u8; 8] = b"WINDOWS\0";
+
+/// Known layouts of the optional data of a boot entry.
+///
+/// Decoding only keeps a typed value if it gives back the exact same bytes, anything else is
+/// [`OptionalData::Binary`]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum OptionalData {
+    None,
+    /// UTF-16 arguments, as read by the Linux EFI stub, shim or systemd-boot
+    CommandLine {
+        args: String,
+        /// whether the arguments end with a null character
+        null_terminated: bool,
+    },
+    /// Windows Boot Manager data, pointing to the BCD object to load
+    WindowsBootManager {
+        bcd_object: Uuid,
+        /// data after the BCD object string, kept as-is (including alignment padding)
+        extra: Vec<u8>,
+    },
+    Binary(Vec<u8>),
+}
+
+impl OptionalData {
+    /// null-terminated command line, as written by efibootmgr or kernel-install
+    pub fn command_line(args: &str) -> OptionalData {
+        OptionalData::CommandLine {
+            args: args.to_owned(),
+            null_terminated: true,
+        }
+    }
+
+    /// Windows Boot Manager data loading that BCD object
+    /// (e.g. `9dea862c-5cdd-4e70-acc1-f32b344d4795` for `{bootmgr}`)
+    pub fn windows_boot_manager(bcd_object: Uuid) -> OptionalData {
+        OptionalData::WindowsBootManager {
+            bcd_object,
+            extra: vec![],
+        }
+    }
+
+    pub fn parse(data: &[u8]) -> OptionalData {
+        if data.is_empty() {
+            return OptionalData::None;
+        }
+
+        let decoded = Self::parse_windows(data).or_else(|| Self::parse_command_line(data));
+
+        match decoded {
+            Some(decoded) if decoded.to_bytes() == data => decoded,
+            _ => OptionalData::Binary(data.to_vec()),
+        }
+    }
+
+    fn parse_windows(data: &[u8]) -> Option<OptionalData> {
+        let mut buf = ParseCursor::new(data);
+        if &buf.read_array::<8>("WindowsOptionalData.signature").ok()? != WINDOWS_SIGNATURE {
+            return None;
+        }
+        let _version = buf.read_u32("WindowsOptionalData.version").ok()?;
+        let _length = buf.read_u32("WindowsOptionalData.length").ok()?;
+        let _data_offset = buf.read_u32("WindowsOptionalData.data_offset").ok()?;
+
+        let bcd_object = buf
+            .read_nt_utf16_string("WindowsOptionalData.bcd_object")
+            .ok()?;
+        let bcd_object = bcd_object
+            .strip_prefix("BCDOBJECT={")?
+            .strip_suffix('}')?
+            .parse()
+            .ok()?;
+
+        Some(OptionalData::WindowsBootManager {
+            bcd_object,
+            extra: buf.read_rest().to_vec(),
+        })
+    }
+
+    fn parse_command_line(data: &[u8]) -> Option<OptionalData> {
+        if !data.len().is_multiple_of(2) {
+            return None;
+        }
+
+        let chars: Vec<u16> = data
+            .as_chunks::<2>()
+            .0
+            .iter()
+            .map(|&chr| u16::from_le_bytes(chr))
+            .collect();
+        let (chars, null_terminated) = match chars.split_last() {
+            Some((0, chars)) => (chars, true),
+            _ => (&chars[..], false),
+        };
+
+        let args = String::from_utf16(chars).ok()?;
+        // binary data may decode as UTF-16 by chance, but rarely as printable text
+        if args.is_empty() || args.chars().any(char::is_control) {
+            return None;
+        }
+
+        Some(OptionalData::CommandLine {
+            args,
+            null_terminated,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            OptionalData::None => vec![],
+            OptionalData::CommandLine {
+                args,
+                null_terminated,
+            } => {
+                let mut bytes: Vec<u8> = args.encode_utf16().flat_map(u16::to_le_bytes).collect();
+                if *null_terminated {
+                    bytes.push_u16(0x0000);
+                }
+                bytes
+            }
+            OptionalData::WindowsBootManager { bcd_object, extra } => {
+                let mut string: Vec<u8> = format!("BCDOBJECT={{{bcd_object}}}")
+                    .encode_utf16()
+                    .chain([0x0000])
+                    .flat_map(u16::to_le_bytes)
+                    .collect();
+
+                let mut bytes = WINDOWS_SIGNATURE.to_vec();
+                // version
+                bytes.push_u32(1);
+                // length of the whole structure
+                bytes.push_u32((20 + string.len() + extra.len()) as u32);
+                // offset of the data following the string, which Windows aligns on 8 bytes
+                bytes.push_u32(if extra.is_empty() {
+                    20 + string.len() as u32
+                } else {
+                    (20 + string.len() as u32 + 7) & !7
+                });
+                bytes.append(&mut string);
+                bytes.extend_from_slice(extra);
+                bytes
+            }
+            OptionalData::Binary(data) => data.clone(),
+        }
+    }
+}
+
+impl Display for OptionalData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptionalData::None => f.write_str("None"),
+            OptionalData::CommandLine { args, .. } => write!(f, "Arguments \"{args}\""),
+            OptionalData::WindowsBootManager { bcd_object, .. } => {
+                write!(f, "Windows Boot Manager, BCD object {{{bcd_object}}}")
+            }
+            OptionalData::Binary(data) => f.write_str(
+                &data
+                    .iter()
+                    .map(|b| format!("{b:02x}"))
+                    .collect::<Vec<String>>()
+                    .join(" "),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use uuid::Uuid;
+
+    use super::OptionalData;
+
+    /// Optional data in the layout Windows writes for its Boot Manager entry, written by hand
+    const WINDOWS: &[u8] = &[
+        0x57, 0x49, 0x4E, 0x44, 0x4F, 0x57, 0x53, 0x00, 0x01, 0x00, 0x00, 0x00, 0x88, 0x00, 0x00,
+        0x00, 0x78, 0x00, 0x00, 0x00, 0x42, 0x00, 0x43, 0x00, 0x44, 0x00, 0x4F, 0x00, 0x42, 0x00,
+        0x4A, 0x00, 0x45, 0x00, 0x43, 0x00, 0x54, 0x00, 0x3D, 0x00, 0x7B, 0x00, 0x39, 0x00, 0x64,
+        0x00, 0x65, 0x00, 0x61, 0x00, 0x38, 0x00, 0x36, 0x00, 0x32, 0x00, 0x63, 0x00, 0x2D, 0x00,
+        0x35, 0x00, 0x63, 0x00, 0x64, 0x00, 0x64, 0x00, 0x2D, 0x00, 0x34, 0x00, 0x65, 0x00, 0x37,
+        0x00, 0x30, 0x00, 0x2D, 0x00, 0x61, 0x00, 0x63, 0x00, 0x63, 0x00, 0x31, 0x00, 0x2D, 0x00,
+        0x66, 0x00, 0x33, 0x00, 0x32, 0x00, 0x62, 0x00, 0x33, 0x00, 0x34, 0x00, 0x34, 0x00, 0x64,
+        0x00, 0x34, 0x00, 0x37, 0x00, 0x39, 0x00, 0x35, 0x00, 0x7D, 0x00, 0x00, 0x00, 0x61, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x7F, 0xFF, 0x04,
+        0x00,
+    ];
+
+    #[test]
+    fn parse_windows() {
+        let data = OptionalData::parse(WINDOWS);
+        match &data {
+            OptionalData::WindowsBootManager { bcd_object, extra } => {
+                assert_eq!(
+                    *bcd_object,
+                    Uuid::from_str("9dea862c-5cdd-4e70-acc1-f32b344d4795").unwrap()
+                );
+                assert_eq!(extra.len(), 18);
+            }
+            data => panic!("unexpected decoding {:?}", data),
+        }
+        assert_eq!(data.to_bytes(), WINDOWS);
+        assert_eq!(
+            data.to_string(),
+            "Windows Boot Manager, BCD object {9dea862c-5cdd-4e70-acc1-f32b344d4795}"
+        );
+    }
+
+    #[test]
+    fn parse_command_line() {
+        let data = OptionalData::command_line("initrd=\\initramfs.img root=/dev/sda2");
+        let bytes = data.to_bytes();
+        assert_eq!(&bytes[..4], &[0x69, 0x00, 0x6E, 0x00]);
+        assert_eq!(&bytes[bytes.len() - 2..], &[0x00, 0x00]);
+        assert_eq!(OptionalData::parse(&bytes), data);
+
+        // without terminator
+        let bytes = &bytes[..bytes.len() - 2];
+        assert_eq!(
+            OptionalData::parse(bytes),
+            OptionalData::CommandLine {
+                args: "initrd=\\initramfs.img root=/dev/sda2".to_owned(),
+                null_terminated: false
+            }
+        );
+    }
+
+    #[test]
+    fn parse_binary() {
+        assert_eq!(OptionalData::parse(&[]), OptionalData::None);
+        for data in [
+            &[0x01, 0x02, 0x03][..],
+            &[0x01, 0x00, 0x00, 0x00],
+            &[0x00, 0x00],
+            // truncated windows data
+            &WINDOWS[..40],
+        ] {
+            assert_eq!(
+                OptionalData::parse(data),
+                OptionalData::Binary(data.to_vec())
+            );
+        }
+        assert_eq!(OptionalData::Binary(vec![0x01, 0xAB]).to_string(), "01 ab");
+    }
+
+    #[test]
+    fn build_windows() {
+        let bcd_object = Uuid::from_str("9dea862c-5cdd-4e70-acc1-f32b344d4795").unwrap();
+        let data = OptionalData::windows_boot_manager(bcd_object);
+        assert_eq!(OptionalData::parse(&data.to_bytes()), data);
+        let bytes = data.to_bytes();
+        assert_eq!(bytes.len(), 118);
+        assert_eq!(&bytes[..12], &WINDOWS[..12]);
+        assert_eq!(&bytes[20..], &WINDOWS[20..118]);
+    }
+}
@@ -2,7 +2,10 @@ use std::str::FromStr;
 
 use uuid::Uuid;
 
-use crate::boot::{BootEntry, BootEntryAttributes, EFIHardDrive, FilePath, FilePathList};
+use crate::boot::{
+    BootEntry, BootEntryAttributes, DevicePath, EFIHardDrive, FilePath, FilePathList,
+    PartitionSignature,
+};
 
 #[test]
 fn dump() {
@@ -11,19 +14,20 @@ fn dump() {
     let entry = BootEntry {
         attributes: BootEntryAttributes::LOAD_OPTION_ACTIVE,
         description: "GRUB".to_owned(),
-        file_path_list: Some(FilePathList {
-            file_path: FilePath {
-                path: "\\EFI\\arch\\grubx64.efi".into(),
-            },
-            hard_drive: EFIHardDrive {
+        file_path_list: FilePathList::new(vec![
+            DevicePath::HardDrive(EFIHardDrive {
                 partition_number: 1,
                 partition_start: 10240,
                 partition_size: 991232,
-                partition_sig: Uuid::from_str("f586c90a-35a3-a8f2-4943-3ef0837cdce3").unwrap(),
+                partition_sig: PartitionSignature::Gpt(
+                    Uuid::from_str("f586c90a-35a3-a8f2-4943-3ef0837cdce3").unwrap(),
+                ),
                 format: 2,
-                sig_type: crate::boot::EFIHardDriveType::Gpt,
-            },
-        }),
+            }),
+            DevicePath::FilePath(FilePath {
+                path: "\\EFI\\arch\\grubx64.efi".into(),
+            }),
+        ]),
         optional_data: vec![],
     };
 
@@ -39,5 +43,20 @@ fn dump() {
         0x00, 0x7F, 0xFF, 0x04, 0x00,
     ];
 
-    assert_eq!(entry.to_bytes(), data);
+    assert_eq!(entry.to_bytes().unwrap(), data);
+}
+
+#[test]
+fn dump_too_large() {
+    //! A node larger than what its u16 length can hold cannot be written
+    let entry = BootEntry {
+        attributes: BootEntryAttributes::LOAD_OPTION_ACTIVE,
+        description: "Large".to_owned(),
+        file_path_list: FilePathList::new(vec![DevicePath::FilePath(FilePath {
+            path: "a".repeat(0x8000),
+        })]),
+        optional_data: vec![],
+    };
+
+    assert!(entry.to_bytes().is_err());
 }
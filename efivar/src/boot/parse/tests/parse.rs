@@ -1,8 +1,15 @@
-use std::str::FromStr;
+use std::{convert::TryFrom, str::FromStr};
 
 use uuid::Uuid;
 
-use crate::boot::{BootEntry, BootEntryAttributes, EFIHardDrive, FilePath, FilePathList};
+use crate::{
+    boot::{
+        BootEntry, BootEntryAttributes, BootEntryRef, DevicePath, EFIHardDrive, FilePath,
+        FilePathList, PartitionSignature,
+    },
+    efi::Variable,
+    Error, ParseErrorKind,
+};
 
 #[test]
 fn parse() {
@@ -23,20 +30,261 @@ fn parse() {
         BootEntry {
             attributes: BootEntryAttributes::LOAD_OPTION_ACTIVE,
             description: "GRUB".to_owned(),
-            file_path_list: Some(FilePathList {
-                file_path: FilePath {
-                    path: "\\EFI\\arch\\grubx64.efi".into()
-                },
-                hard_drive: EFIHardDrive {
+            file_path_list: FilePathList::new(vec![
+                DevicePath::HardDrive(EFIHardDrive {
                     partition_number: 1,
                     partition_start: 10240,
                     partition_size: 991232,
-                    partition_sig: Uuid::from_str("837cdce3-3ef0-4943-a8f2-35a3f586c90a").unwrap(),
+                    partition_sig: PartitionSignature::Gpt(
+                        Uuid::from_str("837cdce3-3ef0-4943-a8f2-35a3f586c90a").unwrap()
+                    ),
                     format: 2,
-                    sig_type: crate::boot::EFIHardDriveType::Gpt,
-                }
-            }),
+                }),
+                DevicePath::FilePath(FilePath {
+                    path: "\\EFI\\arch\\grubx64.efi".into(),
+                }),
+            ]),
             optional_data: vec![]
         }
     );
 }
+
+#[test]
+fn parse_file_path_list_text() {
+    //! Parse the text representation of the file path list of the entry above
+    let text =
+        "HD(1,GPT,837cdce3-3ef0-4943-a8f2-35a3f586c90a,0x2800,0xF2000)/\\EFI\\arch\\grubx64.efi";
+    let file_path_list = FilePathList::from_str(text).unwrap();
+    assert_eq!(
+        file_path_list,
+        FilePathList::new(vec![
+            DevicePath::HardDrive(EFIHardDrive {
+                partition_number: 1,
+                partition_start: 10240,
+                partition_size: 991232,
+                partition_sig: PartitionSignature::Gpt(
+                    Uuid::from_str("837cdce3-3ef0-4943-a8f2-35a3f586c90a").unwrap()
+                ),
+                format: 2,
+            }),
+            DevicePath::FilePath(FilePath {
+                path: "\\EFI\\arch\\grubx64.efi".into(),
+            }),
+        ])
+    );
+    assert_eq!(file_path_list.to_string(), text);
+
+    // the File() form used by efibootmgr is accepted too
+    assert_eq!(
+        FilePathList::from_str(
+            "HD(1,GPT,837cdce3-3ef0-4943-a8f2-35a3f586c90a,0x2800,0xF2000)/File(\\EFI\\arch\\grubx64.efi)"
+        )
+        .unwrap(),
+        file_path_list
+    );
+
+    // any device path can be stored
+    let usb = "PciRoot(0x0)/Pci(0x14,0x0)/USB(0x3,0x0)";
+    assert_eq!(FilePathList::from_str(usb).unwrap().to_string(), usb);
+}
+
+/// Boot entries whose file path list is not a plain HD + file path
+const ENTRIES: &[&[u8]] = &[
+    // PXE entry with an unknown attribute bit and optional data
+    &[
+        0x01, 0x00, 0x00, 0x40, 0x56, 0x00, 0x55, 0x00, 0x45, 0x00, 0x46, 0x00, 0x49, 0x00, 0x20,
+        0x00, 0x50, 0x00, 0x58, 0x00, 0x45, 0x00, 0x76, 0x00, 0x34, 0x00, 0x20, 0x00, 0x28, 0x00,
+        0x4D, 0x00, 0x41, 0x00, 0x43, 0x00, 0x3A, 0x00, 0x35, 0x00, 0x32, 0x00, 0x35, 0x00, 0x34,
+        0x00, 0x30, 0x00, 0x30, 0x00, 0x31, 0x00, 0x32, 0x00, 0x33, 0x00, 0x34, 0x00, 0x35, 0x00,
+        0x36, 0x00, 0x29, 0x00, 0x00, 0x00, 0x02, 0x01, 0x0C, 0x00, 0xD0, 0x41, 0x03, 0x0A, 0x00,
+        0x00, 0x00, 0x00, 0x01, 0x01, 0x06, 0x00, 0x00, 0x03, 0x03, 0x0B, 0x25, 0x00, 0x52, 0x54,
+        0x00, 0x12, 0x34, 0x56, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x03, 0x0C, 0x1B, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x7F, 0xFF,
+        0x04, 0x00, 0x4E, 0xAC, 0x08, 0x81, 0x11, 0x9F, 0x59, 0x4D, 0x85, 0x0E, 0xE2, 0x1A, 0x52,
+        0x2C, 0x59, 0xB2,
+    ],
+    // two instances
+    &[
+        0x01, 0x00, 0x00, 0x00, 0x38, 0x00, 0x43, 0x00, 0x6F, 0x00, 0x6E, 0x00, 0x73, 0x00, 0x6F,
+        0x00, 0x6C, 0x00, 0x65, 0x00, 0x00, 0x00, 0x02, 0x01, 0x0C, 0x00, 0xD0, 0x41, 0x03, 0x0A,
+        0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x06, 0x00, 0x00, 0x1F, 0x02, 0x01, 0x0C, 0x00, 0xD0,
+        0x41, 0x01, 0x05, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x01, 0x04, 0x00, 0x02, 0x01, 0x0C, 0x00,
+        0xD0, 0x41, 0x03, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x06, 0x00, 0x00, 0x02, 0x7F,
+        0xFF, 0x04, 0x00,
+    ],
+    // no end node
+    &[
+        0x01, 0x00, 0x00, 0x00, 0x30, 0x00, 0x4E, 0x00, 0x6F, 0x00, 0x20, 0x00, 0x65, 0x00, 0x6E,
+        0x00, 0x64, 0x00, 0x00, 0x00, 0x04, 0x04, 0x30, 0x00, 0x5C, 0x00, 0x45, 0x00, 0x46, 0x00,
+        0x49, 0x00, 0x5C, 0x00, 0x42, 0x00, 0x4F, 0x00, 0x4F, 0x00, 0x54, 0x00, 0x5C, 0x00, 0x42,
+        0x00, 0x4F, 0x00, 0x4F, 0x00, 0x54, 0x00, 0x58, 0x00, 0x36, 0x00, 0x34, 0x00, 0x2E, 0x00,
+        0x45, 0x00, 0x46, 0x00, 0x49, 0x00, 0x00, 0x00,
+    ],
+    // node after the end of the device path
+    &[
+        0x01, 0x00, 0x00, 0x00, 0x36, 0x00, 0x41, 0x00, 0x66, 0x00, 0x74, 0x00, 0x65, 0x00, 0x72,
+        0x00, 0x20, 0x00, 0x65, 0x00, 0x6E, 0x00, 0x64, 0x00, 0x00, 0x00, 0x04, 0x07, 0x14, 0x00,
+        0xC9, 0xBD, 0xB8, 0x7C, 0xEB, 0xF8, 0x34, 0x4F, 0xAA, 0xEA, 0x3E, 0xE4, 0xAF, 0x65, 0x16,
+        0xA1, 0x04, 0x06, 0x14, 0x00, 0x21, 0xAA, 0x2C, 0x46, 0x14, 0x76, 0x03, 0x45, 0x83, 0x6E,
+        0x8A, 0xB6, 0xF4, 0x66, 0x23, 0x31, 0x7F, 0xFF, 0x04, 0x00, 0x04, 0x04, 0x0A, 0x00, 0x5C,
+        0x00, 0x61, 0x00, 0x00, 0x00,
+    ],
+    // empty file path list
+    &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x45, 0x00, 0x6D, 0x00, 0x70, 0x00, 0x74, 0x00, 0x79,
+        0x00, 0x00, 0x00,
+    ],
+];
+
+#[test]
+fn parse_dump_roundtrip() {
+    //! Parsing an entry and dumping it back must give the exact same bytes
+    for data in ENTRIES {
+        let entry = BootEntry::parse(data.to_vec()).unwrap();
+        assert_eq!(
+            &entry.to_bytes().unwrap(),
+            data,
+            "{:?} did not roundtrip",
+            entry
+        );
+    }
+}
+
+#[test]
+fn parse_borrowed() {
+    //! Borrowed entries decode to the same values as owned entries
+    for data in ENTRIES {
+        let entry_ref = BootEntryRef::parse(data).unwrap();
+        let entry = BootEntry::parse(data.to_vec()).unwrap();
+
+        assert_eq!(entry_ref.attributes(), entry.attributes);
+        assert_eq!(entry_ref.description().unwrap(), entry.description);
+        assert!(entry_ref
+            .description_utf16()
+            .eq(entry.description.encode_utf16()));
+        assert_eq!(entry_ref.optional_data(), &entry.optional_data[..]);
+        assert_eq!(
+            entry_ref
+                .device_paths()
+                .map(|node| node.unwrap().to_device_path())
+                .collect::<Vec<_>>(),
+            entry.file_path_list.nodes().cloned().collect::<Vec<_>>()
+        );
+        assert_eq!(BootEntry::try_from(entry_ref).unwrap(), entry);
+    }
+}
+
+#[test]
+fn parse_borrowed_lazily() {
+    //! Broken nodes are only reported when reached
+    let data = [
+        0x01, 0x00, 0x00, 0x00, 0x08, 0x00, 0x41, 0x00, 0x00, 0x00, 0x7F, 0x01, 0x04, 0x00, 0x7F,
+        0xFF, 0x08, 0x00,
+    ];
+    assert!(BootEntry::parse(data.to_vec()).is_err());
+
+    let entry_ref = BootEntryRef::parse(&data).unwrap();
+    assert_eq!(entry_ref.description().unwrap(), "A");
+
+    let mut nodes = entry_ref.device_paths();
+    let node = nodes.next().unwrap().unwrap();
+    assert!(node.is_end());
+    assert_eq!(node.offset(), 10);
+    assert_eq!(node.to_device_path(), DevicePath::EndInstance);
+    match nodes.next() {
+        Some(Err(Error::ParseError(err))) => {
+            assert_eq!(err.offset, 18);
+            assert_eq!(err.field, "DevicePath.data");
+        }
+        res => panic!("unexpected result {:?}", res),
+    }
+    assert!(nodes.next().is_none());
+}
+
+#[test]
+fn parse_instances() {
+    let entry = BootEntry::parse(ENTRIES[1].to_vec()).unwrap();
+    assert_eq!(entry.file_path_list.instances.len(), 2);
+    assert_eq!(
+        entry.file_path_list.to_string(),
+        "PciRoot(0x0)/Pci(0x1F,0x0)/Serial(0x0),PciRoot(0x0)/Pci(0x2,0x0)"
+    );
+
+    let entry = BootEntry::parse(ENTRIES[2].to_vec()).unwrap();
+    assert_eq!(entry.file_path_list.instances[0].end, None);
+    assert_eq!(
+        entry.file_path_list.file_path().unwrap().path,
+        "\\EFI\\BOOT\\BOOTX64.EFI"
+    );
+    assert_eq!(entry.file_path_list.hard_drive(), None);
+
+    let entry = BootEntry::parse(ENTRIES[4].to_vec()).unwrap();
+    assert_eq!(entry.file_path_list, FilePathList::default());
+}
+
+#[test]
+fn parse_malformed() {
+    //! Malformed entries are errors, never panics
+    for data in [
+        // truncated header
+        &[0x01, 0x00, 0x00][..],
+        // description without NUL terminator
+        &[0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x41, 0x00],
+        // file path list longer than the entry
+        &[
+            0x01, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x7F, 0xFF, 0x04, 0x00,
+        ],
+        // node length below the size of the node header
+        &[
+            0x01, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x7F, 0xFF, 0x00, 0x00,
+        ],
+        &[
+            0x01, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x7F, 0xFF, 0x03, 0x00,
+        ],
+        // node longer than the file path list
+        &[
+            0x01, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x7F, 0xFF, 0x08, 0x00,
+        ],
+    ] {
+        assert!(
+            BootEntry::parse(data.to_vec()).is_err(),
+            "{:02X?} was accepted",
+            data
+        );
+    }
+}
+
+#[test]
+fn parse_error_context() {
+    // the file path list claims 0x10 bytes, but only 4 are left after the description
+    let data = [
+        0x01, 0x00, 0x00, 0x00, 0x10, 0x00, 0x41, 0x00, 0x00, 0x00, 0x7F, 0xFF, 0x04, 0x00,
+    ];
+
+    let err = BootEntry::parse(data.to_vec())
+        .unwrap_err()
+        .with_variable(&Variable::new("Boot0001"));
+    match &err {
+        Error::ParseError(parse_error) => {
+            assert_eq!(parse_error.offset, 10);
+            assert_eq!(parse_error.field, "BootEntry.file_path_list");
+            assert_eq!(
+                parse_error.kind,
+                ParseErrorKind::Truncated {
+                    expected: 0x10,
+                    available: 4
+                }
+            );
+        }
+        err => panic!("unexpected error {:?}", err),
+    }
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "failed to parse variable '{}' at offset 0xA (BootEntry.file_path_list): expected 16 bytes, only 4 available",
+            Variable::new("Boot0001")
+        )
+    );
+}
@@ -1,33 +1,305 @@
 //! This module contains functions to read boot entries. Actual boot entry parsing is done in [`crate::boot::parse`]
 
-use byteorder::{LittleEndian, ReadBytesExt};
+use uuid::Uuid;
 
-use crate::{boot::BootVarFormat, efi::Variable, Error, VarReader};
+use crate::{
+    boot::{BootVarFormat, ParseCursor},
+    efi::Variable,
+    Error, ParseError, VarReader,
+};
 
-use super::boot_entry_iter::BootEntriesIterator;
+use super::{boot_entry_iter::BootEntriesIterator, LoadOptionKind};
 
 pub trait BootVarReader {
     fn get_boot_order(&self) -> crate::Result<Vec<u16>>;
     fn get_boot_entries<'a>(&'a self) -> crate::Result<BootEntriesIterator<'a>>;
+    /// get the IDs stored in the order variable of that kind of load option (e.g. `DriverOrder`)
+    fn get_load_option_order(&self, kind: LoadOptionKind) -> crate::Result<Vec<u16>>;
+    /// get the vendor GUIDs stored in `OsRecoveryOrder`, whose namespaces hold the
+    /// `OsRecovery####` options
+    fn get_os_recovery_order(&self) -> crate::Result<Vec<Uuid>>;
+    /// loop over the load options of that kind, in their order.
+    ///
+    /// `PlatformRecovery####` options have no order variable, and are listed in increasing ID
+    /// order from `PlatformRecovery0000` until one is missing. `OsRecovery####` options are looked
+    /// up the same way in each namespace of `OsRecoveryOrder`
+    fn get_load_options<'a>(
+        &'a self,
+        kind: LoadOptionKind,
+    ) -> crate::Result<BootEntriesIterator<'a>>;
 }
 
 impl<T: VarReader> BootVarReader for T {
     fn get_boot_order(&self) -> crate::Result<Vec<u16>> {
-        let (data, _) = self.read(&Variable::new("BootOrder"))?;
+        self.get_load_option_order(LoadOptionKind::Boot)
+    }
+
+    fn get_boot_entries<'a>(&'a self) -> crate::Result<BootEntriesIterator<'a>> {
+        self.get_load_options(LoadOptionKind::Boot)
+    }
+
+    fn get_load_option_order(&self, kind: LoadOptionKind) -> crate::Result<Vec<u16>> {
+        let variable = kind
+            .order_variable()
+            .ok_or(Error::NoLoadOptionOrder { kind })?;
+        let (data, _) = self.read(&variable)?;
 
-        assert!(data.len() % 2 == 0); // ids are u16 values, so it must be an even number of u8
+        // ids are u16 values, so it must be an even number of u8
+        if data.len() % 2 != 0 {
+            return Err(Error::from(ParseError::truncated(
+                data.len() - 1,
+                "LoadOptionOrder.id",
+                2,
+                1,
+            ))
+            .with_variable(&variable));
+        }
 
-        let mut ids = vec![0u16; data.len() / 2];
-        data.as_slice()
-            .read_u16_into::<LittleEndian>(&mut ids)
-            .map_err(Error::UnknownIoError)?;
+        let ids: Vec<u16> = data
+            .as_chunks::<2>()
+            .0
+            .iter()
+            .map(|&id| u16::from_le_bytes(id))
+            .collect();
 
         let ids_formatted: Vec<String> = ids.iter().map(|id| id.boot_id_format()).collect();
-        log::debug!("Queried BootOrder: [{}]", ids_formatted.join(", "));
+        log::debug!(
+            "Queried {}: [{}]",
+            variable.name(),
+            ids_formatted.join(", ")
+        );
         Ok(ids)
     }
 
-    fn get_boot_entries<'a>(&'a self) -> crate::Result<BootEntriesIterator<'a>> {
-        BootEntriesIterator::new(self)
+    fn get_os_recovery_order(&self) -> crate::Result<Vec<Uuid>> {
+        let variable = Variable::new("OsRecoveryOrder");
+        let (data, _) = self.read(&variable)?;
+
+        let mut buf = ParseCursor::new(&data);
+        let mut vendors = vec![];
+        while !buf.is_empty() {
+            vendors.push(
+                buf.read_guid("OsRecoveryOrder.vendor")
+                    .map_err(|err| err.with_variable(&variable))?,
+            );
+        }
+
+        Ok(vendors)
+    }
+
+    fn get_load_options<'a>(
+        &'a self,
+        kind: LoadOptionKind,
+    ) -> crate::Result<BootEntriesIterator<'a>> {
+        let options = match kind {
+            LoadOptionKind::PlatformRecovery => {
+                let mut options = vec![];
+                for id in 0..=u16::MAX {
+                    let variable = kind.variable(id);
+                    if !self.exists(&variable)? {
+                        break;
+                    }
+                    options.push((id, variable));
+                }
+                options
+            }
+            LoadOptionKind::OsRecovery => {
+                let mut options = vec![];
+                for vendor in self.get_os_recovery_order()? {
+                    for id in 0..=u16::MAX {
+                        let variable = kind.vendor_variable(id, vendor);
+                        if !self.exists(&variable)? {
+                            break;
+                        }
+                        options.push((id, variable));
+                    }
+                }
+                options
+            }
+            _ => self
+                .get_load_option_order(kind)?
+                .into_iter()
+                .map(|id| (id, kind.variable(id)))
+                .collect(),
+        };
+
+        Ok(BootEntriesIterator::new(self, options))
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "store")]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::{
+        boot::{
+            BootEntry, BootEntryAttributes, BootVarWriter, DevicePath, FilePath, FilePathList,
+            LoadOptionKind,
+        },
+        efi::{Variable, VariableFlags},
+        push::PushVecU8,
+        store::MemoryStore,
+        VarWriter,
+    };
+
+    use super::BootVarReader;
+
+    fn entry(description: &str) -> BootEntry {
+        BootEntry {
+            attributes: BootEntryAttributes::LOAD_OPTION_ACTIVE,
+            description: description.to_owned(),
+            file_path_list: FilePathList::new(vec![DevicePath::FilePath(FilePath {
+                path: "\\EFI\\test.efi".into(),
+            })]),
+            optional_data: vec![],
+        }
+    }
+
+    fn descriptions(store: &MemoryStore, kind: LoadOptionKind) -> Vec<(String, Variable)> {
+        store
+            .get_load_options(kind)
+            .unwrap()
+            .map(|(entry, variable)| (entry.unwrap().entry.description, variable))
+            .collect()
+    }
+
+    #[test]
+    fn odd_boot_order() {
+        let mut store = MemoryStore::new();
+        store
+            .write(
+                &Variable::new("BootOrder"),
+                VariableFlags::empty(),
+                &[0x01, 0x00, 0x02],
+            )
+            .unwrap();
+
+        assert!(store.get_boot_order().is_err());
+    }
+
+    #[test]
+    fn load_option_order() {
+        let mut store = MemoryStore::new();
+        store
+            .set_load_option_order(LoadOptionKind::Driver, vec![0x0002, 0x1000])
+            .unwrap();
+
+        assert_eq!(
+            store.get_load_option_order(LoadOptionKind::Driver).unwrap(),
+            vec![0x0002, 0x1000]
+        );
+        assert!(store.get_boot_order().is_err());
+        assert!(store
+            .get_load_option_order(LoadOptionKind::PlatformRecovery)
+            .is_err());
+    }
+
+    #[test]
+    fn load_options_in_order() {
+        let mut store = MemoryStore::new();
+        for (id, description) in [(0x0001, "first"), (0x0002, "second"), (0x0003, "third")] {
+            store
+                .create_load_option(LoadOptionKind::Driver, id, entry(description))
+                .unwrap();
+        }
+        store
+            .set_load_option_order(LoadOptionKind::Driver, vec![0x0002, 0x0003, 0x0001])
+            .unwrap();
+
+        assert_eq!(
+            descriptions(&store, LoadOptionKind::Driver)
+                .into_iter()
+                .map(|(description, _)| description)
+                .collect::<Vec<_>>(),
+            vec!["second", "third", "first"]
+        );
+    }
+
+    #[test]
+    fn platform_recovery_options() {
+        let mut store = MemoryStore::new();
+        let kind = LoadOptionKind::PlatformRecovery;
+        for (id, description) in [
+            (0x0000, "first"),
+            (0x0001, "second"),
+            // not reached after the missing PlatformRecovery0002
+            (0x0003, "fourth"),
+        ] {
+            store
+                .create_load_option(kind, id, entry(description))
+                .unwrap();
+        }
+
+        assert_eq!(
+            descriptions(&store, kind),
+            vec![
+                ("first".to_owned(), Variable::new("PlatformRecovery0000")),
+                ("second".to_owned(), Variable::new("PlatformRecovery0001")),
+            ]
+        );
+    }
+
+    #[test]
+    fn os_recovery_options() {
+        let first = Uuid::from_u128(0x1111);
+        let second = Uuid::from_u128(0x2222);
+
+        let mut store = MemoryStore::new();
+        let mut order = vec![];
+        order.push_guid(&second);
+        order.push_guid(&first);
+        store
+            .write(
+                &Variable::new("OsRecoveryOrder"),
+                VariableFlags::default(),
+                &order,
+            )
+            .unwrap();
+
+        let kind = LoadOptionKind::OsRecovery;
+        for (id, vendor, description) in [
+            (0x0000, first, "first 0"),
+            (0x0001, first, "first 1"),
+            // not reached after the missing OsRecovery0002
+            (0x0003, first, "first 3"),
+            (0x0000, second, "second 0"),
+        ] {
+            store
+                .write(
+                    &kind.vendor_variable(id, vendor),
+                    VariableFlags::default(),
+                    &entry(description).to_bytes().unwrap(),
+                )
+                .unwrap();
+        }
+
+        assert_eq!(store.get_os_recovery_order().unwrap(), vec![second, first]);
+        assert_eq!(
+            descriptions(&store, kind),
+            vec![
+                (
+                    "second 0".to_owned(),
+                    Variable::new_with_vendor("OsRecovery0000", second)
+                ),
+                (
+                    "first 0".to_owned(),
+                    Variable::new_with_vendor("OsRecovery0000", first)
+                ),
+                (
+                    "first 1".to_owned(),
+                    Variable::new_with_vendor("OsRecovery0001", first)
+                ),
+            ]
+        );
+
+        store
+            .write(
+                &Variable::new("OsRecoveryOrder"),
+                VariableFlags::default(),
+                &[0; 15],
+            )
+            .unwrap();
+        assert!(store.get_os_recovery_order().is_err());
     }
 }
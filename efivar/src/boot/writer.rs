@@ -1,44 +1,58 @@
 //! This module contains functions to write boot entries
 
-use crate::{
-    boot::BootVarFormat,
-    efi::{Variable, VariableFlags},
-    VarWriter,
-};
+use crate::{efi::VariableFlags, Error, VarWriter};
 
-use super::BootEntry;
+use super::{BootEntry, LoadOptionKind};
 
 pub trait BootVarWriter {
     fn create_boot_entry(&mut self, id: u16, entry: BootEntry) -> crate::Result<()>;
     fn set_boot_order(&mut self, ids: Vec<u16>) -> crate::Result<()>;
+    fn create_load_option(
+        &mut self,
+        kind: LoadOptionKind,
+        id: u16,
+        entry: BootEntry,
+    ) -> crate::Result<()>;
+    fn set_load_option_order(&mut self, kind: LoadOptionKind, ids: Vec<u16>) -> crate::Result<()>;
 }
 
 impl<T: VarWriter> BootVarWriter for T {
     fn set_boot_order(&mut self, ids: Vec<u16>) -> crate::Result<()> {
+        self.set_load_option_order(LoadOptionKind::Boot, ids)
+    }
+
+    /// Creates an EFI variable for a boot entry.
+    /// You then need to add it to the boot order using [`BootVarWriter::set_boot_order`].
+    fn create_boot_entry(&mut self, id: u16, entry: BootEntry) -> crate::Result<()> {
+        self.create_load_option(LoadOptionKind::Boot, id, entry)
+    }
+
+    /// Overwrites the order variable of that kind of load option (e.g. `DriverOrder`)
+    fn set_load_option_order(&mut self, kind: LoadOptionKind, ids: Vec<u16>) -> crate::Result<()> {
+        let variable = kind
+            .order_variable()
+            .ok_or(Error::NoLoadOptionOrder { kind })?;
         let bytes: Vec<u8> = ids.iter().flat_map(|id| id.to_le_bytes()).collect();
 
-        self.write(
-            &Variable::new("BootOrder"),
-            VariableFlags::default(),
-            &bytes,
-        )?;
+        self.write(&variable, VariableFlags::default(), &bytes)?;
 
-        log::debug!("Set BootOrder to {ids:?}");
+        log::debug!("Set {} to {ids:?}", variable.name());
         Ok(())
     }
 
-    /// Creates an EFI variable for a boot entry.
-    /// You then need to add it to the boot order using [`BootVarWriter::set_boot_order`].
-    fn create_boot_entry(&mut self, id: u16, entry: BootEntry) -> crate::Result<()> {
-        let bytes = entry.to_bytes();
+    /// Creates an EFI variable for a load option of any kind.
+    /// You then need to add it to its order using [`BootVarWriter::set_load_option_order`].
+    fn create_load_option(
+        &mut self,
+        kind: LoadOptionKind,
+        id: u16,
+        entry: BootEntry,
+    ) -> crate::Result<()> {
+        let bytes = entry.to_bytes()?;
 
-        self.write(
-            &Variable::new(&id.boot_var_format()),
-            VariableFlags::default(),
-            &bytes,
-        )?;
+        self.write(&kind.variable(id), VariableFlags::default(), &bytes)?;
 
-        log::debug!("Created boot entry for ID {id}: {entry:?}");
+        log::debug!("Created {kind} entry for ID {id}: {entry:?}");
         Ok(())
     }
 }
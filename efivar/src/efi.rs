@@ -9,6 +9,9 @@ pub use variable::Variable;
 mod variable_vendor;
 pub use variable_vendor::VariableVendor;
 
+mod efi_time;
+pub use efi_time::EfiTime;
+
 lazy_static::lazy_static! {
     /// Vendor GUID of the EFI variables according to the specification
     pub static ref EFI_GUID: uuid::Uuid =
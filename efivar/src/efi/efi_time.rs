@@ -0,0 +1,151 @@
+//! This module contains the EFI_TIME structure, used by revocation times and authenticated
+//! variables
+
+use std::{
+    convert::TryFrom,
+    fmt::Display,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{boot::ParseCursor, push::PushVecU8};
+
+/// A point in time, as stored by the firmware (EFI_TIME). See spec, 8.3
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EfiTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub nanosecond: u32,
+    /// offset from UTC in minutes, or 0x07FF if unspecified
+    pub time_zone: i16,
+    pub daylight: u8,
+}
+
+impl EfiTime {
+    pub const SIZE: usize = 16;
+
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<EfiTime> {
+        let year = buf.read_u16("EfiTime.year")?;
+        let month = buf.read_u8("EfiTime.month")?;
+        let day = buf.read_u8("EfiTime.day")?;
+        let hour = buf.read_u8("EfiTime.hour")?;
+        let minute = buf.read_u8("EfiTime.minute")?;
+        let second = buf.read_u8("EfiTime.second")?;
+        let _pad1 = buf.read_u8("EfiTime.pad1")?;
+        let nanosecond = buf.read_u32("EfiTime.nanosecond")?;
+        let time_zone = buf.read_u16("EfiTime.time_zone")? as i16;
+        let daylight = buf.read_u8("EfiTime.daylight")?;
+        let _pad2 = buf.read_u8("EfiTime.pad2")?;
+
+        Ok(EfiTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            nanosecond,
+            time_zone,
+            daylight,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.push_u16(self.year);
+        bytes.push_u8(self.month);
+        bytes.push_u8(self.day);
+        bytes.push_u8(self.hour);
+        bytes.push_u8(self.minute);
+        bytes.push_u8(self.second);
+        bytes.push_u8(0);
+        bytes.push_u32(self.nanosecond);
+        bytes.push_u16(self.time_zone as u16);
+        bytes.push_u8(self.daylight);
+        bytes.push_u8(0);
+        bytes
+    }
+
+    /// the current UTC time, with a precision of one second
+    pub fn now() -> EfiTime {
+        EfiTime::from_unix_time(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default(),
+        )
+    }
+
+    /// the UTC time at `duration` after the Unix epoch, with a precision of one second
+    pub fn from_unix_time(duration: Duration) -> EfiTime {
+        let secs = duration.as_secs();
+        let (days, secs) = (secs / 86400, secs % 86400);
+
+        // civil date from days since the epoch, in eras of 400 years starting on March 1st
+        let z = days + 719468;
+        let era = z / 146097;
+        let day_of_era = z % 146097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+        let month = if month_index < 10 {
+            month_index + 3
+        } else {
+            month_index - 9
+        };
+        let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+        EfiTime {
+            year: u16::try_from(year).unwrap_or(u16::MAX),
+            month: month as u8,
+            day: day as u8,
+            hour: (secs / 3600) as u8,
+            minute: (secs / 60 % 60) as u8,
+            second: (secs % 60) as u8,
+            ..Default::default()
+        }
+    }
+
+    /// whether all fields are zero, which revocation times use to mean "always revoked"
+    pub fn is_zero(&self) -> bool {
+        *self == EfiTime::default()
+    }
+}
+
+impl Display for EfiTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::EfiTime;
+
+    #[test]
+    fn from_unix_time() {
+        assert_eq!(
+            EfiTime::from_unix_time(Duration::from_secs(1715949001)).to_string(),
+            "2024-05-17 12:30:01"
+        );
+        // leap day
+        assert_eq!(
+            EfiTime::from_unix_time(Duration::from_secs(951782400)).to_string(),
+            "2000-02-29 00:00:00"
+        );
+        assert_eq!(
+            EfiTime::from_unix_time(Duration::ZERO).to_string(),
+            "1970-01-01 00:00:00"
+        );
+    }
+}
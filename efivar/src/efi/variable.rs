@@ -3,7 +3,7 @@
 use std::fmt;
 use std::str::FromStr;
 
-use crate::Error;
+use crate::{boot::LoadOptionKind, Error};
 
 use super::variable_vendor::VariableVendor;
 
@@ -92,11 +92,13 @@ impl Variable {
 
     /// Returns the boot var ID (4 digits hex number) if this variable is a boot entry. Else, return None
     pub fn boot_var_id(&self) -> Option<u16> {
-        if self.name.len() == 8 && &self.name[0..4] == "Boot" {
-            u16::from_str_radix(&self.name[4..8], 16).ok()
-        } else {
-            None
-        }
+        self.load_option_id(LoadOptionKind::Boot)
+    }
+
+    /// Returns the option ID (4 digits hex number) if this variable is a load option of that kind
+    /// (e.g. `Driver0001`). Else, return None
+    pub fn load_option_id(&self, kind: LoadOptionKind) -> Option<u16> {
+        kind.option_id(&self.name)
     }
 }
 
@@ -180,4 +182,16 @@ mod tests {
         assert_eq!(Variable::new("Boot10000").boot_var_id(), None);
         assert_eq!(Variable::new("Boot100").boot_var_id(), None);
     }
+
+    #[test]
+    fn load_option_id() {
+        assert_eq!(
+            Variable::new("SysPrep00FF").load_option_id(LoadOptionKind::SysPrep),
+            Some(0x00FF)
+        );
+        assert_eq!(
+            Variable::new("SysPrep00FF").load_option_id(LoadOptionKind::Driver),
+            None
+        );
+    }
 }
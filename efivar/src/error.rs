@@ -1,6 +1,9 @@
 use std::io;
 
-use crate::efi::Variable;
+use crate::{
+    boot::LoadOptionKind,
+    efi::{Variable, VariableFlags},
+};
 
 /// Describes an error returned by EFI variable operations
 #[derive(Debug, thiserror::Error)]
@@ -27,10 +30,156 @@ pub enum Error {
     InvalidUTF8,
     #[error("failed to decode uuid: {}", error)]
     UuidError { error: uuid::Error },
-    #[error("failed to parse variable content (invalid content)")]
-    VarParseError,
+    #[error("{}", .0)]
+    ParseError(ParseError),
     #[error("failed to parse string: {}", 0)]
     StringParseError(crate::utils::StringParseError),
+    #[error("failed to parse device path '{}': {}", text, reason)]
+    DevicePathTextError { text: String, reason: String },
+    #[error("{} is too large", what)]
+    ValueTooLarge { what: &'static str },
+    #[error(
+        "variable store full: {} bytes needed, {} available",
+        needed,
+        available
+    )]
+    VariableStoreFull { needed: usize, available: usize },
+    #[error("invalid MAC address '{}'", mac)]
+    InvalidMacAddress { mac: String },
+    #[error("network interface {} not found", interface)]
+    NetworkInterfaceNotFound { interface: String },
+    #[error("{} load options have no order variable", kind)]
+    NoLoadOptionOrder { kind: LoadOptionKind },
+    #[error("invalid key '{}'", key)]
+    InvalidKey { key: String },
+    #[error("firmware does not support {}", what)]
+    UnsupportedByFirmware { what: String },
+    #[error(
+        "variable '{}' has attributes 0x{:X}, expected at least 0x{:X}",
+        var,
+        actual.bits(),
+        expected.bits()
+    )]
+    InvalidAttributes {
+        var: Variable,
+        expected: VariableFlags,
+        actual: VariableFlags,
+    },
+    #[error("invalid language code '{}'", lang)]
+    InvalidLanguage { lang: String },
+    #[error("invalid certificate: {}", reason)]
+    InvalidCertificate { reason: String },
+    #[error("invalid private key: {}", reason)]
+    InvalidPrivateKey { reason: String },
+    #[error("invalid signature: {}", reason)]
+    InvalidSignature { reason: String },
+    #[error("invalid password: {}", reason)]
+    InvalidPassword { reason: String },
+    #[error(
+        "signature of {} bytes does not match the signature size of its list ({} bytes)",
+        actual,
+        expected
+    )]
+    SignatureSizeMismatch { expected: usize, actual: usize },
+    #[error(
+        "invalid Secure Boot mode: SetupMode={}, AuditMode={}, DeployedMode={}",
+        *setup_mode as u8,
+        *audit_mode as u8,
+        *deployed_mode as u8
+    )]
+    InvalidSecureBootMode {
+        setup_mode: bool,
+        audit_mode: bool,
+        deployed_mode: bool,
+    },
+}
+
+/// Describes why the content of a variable could not be parsed, and where
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// variable being parsed, if known
+    pub variable: Option<Variable>,
+    /// offset of the field in the variable data
+    pub offset: usize,
+    /// structure and field being decoded, e.g. `EFIHardDrive.partition_start`
+    pub field: &'static str,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// the field needs more bytes than what is left
+    Truncated { expected: usize, available: usize },
+    /// the field holds a value that is not valid
+    Invalid { reason: String },
+}
+
+impl ParseError {
+    pub fn truncated(
+        offset: usize,
+        field: &'static str,
+        expected: usize,
+        available: usize,
+    ) -> ParseError {
+        ParseError {
+            variable: None,
+            offset,
+            field,
+            kind: ParseErrorKind::Truncated {
+                expected,
+                available,
+            },
+        }
+    }
+
+    pub fn invalid(offset: usize, field: &'static str, reason: impl Into<String>) -> ParseError {
+        ParseError {
+            variable: None,
+            offset,
+            field,
+            kind: ParseErrorKind::Invalid {
+                reason: reason.into(),
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to parse ")?;
+        if let Some(variable) = &self.variable {
+            write!(f, "variable '{variable}' ")?;
+        }
+        write!(f, "at offset 0x{:X} ({}): ", self.offset, self.field)?;
+        match &self.kind {
+            ParseErrorKind::Truncated {
+                expected,
+                available,
+            } => write!(f, "expected {expected} bytes, only {available} available"),
+            ParseErrorKind::Invalid { reason } => f.write_str(reason),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self {
+        Error::ParseError(error)
+    }
+}
+
+impl Error {
+    /// attach the variable being parsed to a parse error
+    pub(crate) fn with_variable(self, variable: &Variable) -> Error {
+        match self {
+            Error::ParseError(error) => Error::ParseError(ParseError {
+                variable: Some(variable.clone()),
+                ..error
+            }),
+            error => error,
+        }
+    }
 }
 
 #[cfg(not(target_os = "windows"))]
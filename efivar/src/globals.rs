@@ -0,0 +1,127 @@
+//! This module contains typed accessors for the variables of the EFI global namespace, defined in
+//! section 3.3 of the UEFI specification.
+//!
+//! Reading checks that a variable has the size and attributes the specification gives it, and
+//! writing uses these attributes. Missing variables are reported as [`crate::Error::VarNotFound`].
+
+mod os_indications;
+mod reader;
+mod writer;
+
+pub use os_indications::{OsIndications, OsIndicationsManager};
+pub use reader::GlobalVarReader;
+pub use writer::GlobalVarWriter;
+
+use crate::{
+    boot::ParseCursor,
+    efi::{Variable, VariableFlags},
+    Error, ParseError, VarReader, VarWriter,
+};
+
+const BS_RT: VariableFlags = VariableFlags::BOOTSERVICE_ACCESS.union(VariableFlags::RUNTIME_ACCESS);
+const NV_BS_RT: VariableFlags = VariableFlags::NON_VOLATILE.union(BS_RT);
+
+/// A variable of the EFI global namespace, along with the attributes the specification gives it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GlobalVariable {
+    pub name: &'static str,
+    pub attributes: VariableFlags,
+}
+
+impl GlobalVariable {
+    pub const TIMEOUT: GlobalVariable = GlobalVariable::new("Timeout", NV_BS_RT);
+    pub const BOOT_CURRENT: GlobalVariable = GlobalVariable::new("BootCurrent", BS_RT);
+    pub const BOOT_NEXT: GlobalVariable = GlobalVariable::new("BootNext", NV_BS_RT);
+    pub const BOOT_OPTION_SUPPORT: GlobalVariable = GlobalVariable::new("BootOptionSupport", BS_RT);
+    pub const LANG: GlobalVariable = GlobalVariable::new("Lang", NV_BS_RT);
+    pub const LANG_CODES: GlobalVariable = GlobalVariable::new("LangCodes", BS_RT);
+    pub const PLATFORM_LANG: GlobalVariable = GlobalVariable::new("PlatformLang", NV_BS_RT);
+    pub const PLATFORM_LANG_CODES: GlobalVariable = GlobalVariable::new("PlatformLangCodes", BS_RT);
+    pub const CON_IN: GlobalVariable = GlobalVariable::new("ConIn", NV_BS_RT);
+    pub const CON_OUT: GlobalVariable = GlobalVariable::new("ConOut", NV_BS_RT);
+    pub const ERR_OUT: GlobalVariable = GlobalVariable::new("ErrOut", NV_BS_RT);
+    pub const CON_IN_DEV: GlobalVariable = GlobalVariable::new("ConInDev", BS_RT);
+    pub const CON_OUT_DEV: GlobalVariable = GlobalVariable::new("ConOutDev", BS_RT);
+    pub const ERR_OUT_DEV: GlobalVariable = GlobalVariable::new("ErrOutDev", BS_RT);
+    pub const OS_INDICATIONS: GlobalVariable = GlobalVariable::new("OsIndications", NV_BS_RT);
+    pub const OS_INDICATIONS_SUPPORTED: GlobalVariable =
+        GlobalVariable::new("OsIndicationsSupported", BS_RT);
+    pub const HW_ERR_REC_SUPPORT: GlobalVariable = GlobalVariable::new("HwErrRecSupport", NV_BS_RT);
+    pub const SECURE_BOOT: GlobalVariable = GlobalVariable::new("SecureBoot", BS_RT);
+    pub const SETUP_MODE: GlobalVariable = GlobalVariable::new("SetupMode", BS_RT);
+    pub const AUDIT_MODE: GlobalVariable = GlobalVariable::new("AuditMode", BS_RT);
+    pub const DEPLOYED_MODE: GlobalVariable = GlobalVariable::new("DeployedMode", BS_RT);
+    pub const VENDOR_KEYS: GlobalVariable = GlobalVariable::new("VendorKeys", BS_RT);
+    pub const SIGNATURE_SUPPORT: GlobalVariable = GlobalVariable::new("SignatureSupport", BS_RT);
+
+    const fn new(name: &'static str, attributes: VariableFlags) -> GlobalVariable {
+        GlobalVariable { name, attributes }
+    }
+
+    pub fn variable(&self) -> Variable {
+        Variable::new(self.name)
+    }
+
+    /// read the raw value of the variable, checking that it has the expected attributes
+    pub fn read(&self, reader: &(impl ?Sized + VarReader)) -> crate::Result<Vec<u8>> {
+        let variable = self.variable();
+        let (data, attributes) = reader.read(&variable)?;
+
+        if !attributes.contains(self.attributes) {
+            return Err(Error::InvalidAttributes {
+                var: variable,
+                expected: self.attributes,
+                actual: attributes,
+            });
+        }
+
+        Ok(data)
+    }
+
+    /// decode the value of the variable, failing if it holds more data than what is decoded
+    pub fn read_with<T>(
+        &self,
+        reader: &(impl ?Sized + VarReader),
+        decode: impl FnOnce(&mut ParseCursor) -> crate::Result<T>,
+    ) -> crate::Result<T> {
+        let data = self.read(reader)?;
+        let mut buf = ParseCursor::new(&data);
+
+        decode(&mut buf)
+            .and_then(|value| {
+                if buf.is_empty() {
+                    Ok(value)
+                } else {
+                    Err(ParseError::invalid(
+                        buf.offset(),
+                        self.name,
+                        format!("{} unexpected trailing bytes", buf.remaining().len()),
+                    )
+                    .into())
+                }
+            })
+            .map_err(|err| err.with_variable(&self.variable()))
+    }
+
+    /// decode an ASCII string, with an optional null terminator
+    pub fn read_ascii(&self, reader: &(impl ?Sized + VarReader)) -> crate::Result<String> {
+        self.read_with(reader, |buf| {
+            let bytes = buf.read_rest();
+            let bytes = bytes.strip_suffix(&[0x00]).unwrap_or(bytes);
+
+            if !bytes.is_ascii() || bytes.contains(&0x00) {
+                return Err(ParseError::invalid(0, self.name, "not an ASCII string").into());
+            }
+
+            Ok(String::from_utf8(bytes.to_vec()).unwrap())
+        })
+    }
+
+    /// write the raw value of the variable, with the attributes of the specification
+    pub fn write(&self, writer: &mut (impl ?Sized + VarWriter), value: &[u8]) -> crate::Result<()> {
+        writer.write(&self.variable(), self.attributes, value)?;
+
+        log::debug!("Set {} to {value:02x?}", self.name);
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+//! This module contains the indications the OS gives the firmware for next boot, such as entering
+//! the firmware setup
+
+use crate::{Error, VarReader, VarWriter};
+
+use super::{GlobalVarReader, GlobalVarWriter};
+
+bitflags::bitflags! {
+    /// Content of the `OsIndications` and `OsIndicationsSupported` variables: features the OS
+    /// asks the firmware to run on next boot, or that the firmware supports. See spec, 8.5.4
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct OsIndications : u64 {
+        const BOOT_TO_FW_UI = 0x1;
+        const TIMESTAMP_REVOCATION = 0x2;
+        const FILE_CAPSULE_DELIVERY_SUPPORTED = 0x4;
+        const FMP_CAPSULE_SUPPORTED = 0x8;
+        const CAPSULE_RESULT_VAR_SUPPORTED = 0x10;
+        const START_OS_RECOVERY = 0x20;
+        const START_PLATFORM_RECOVERY = 0x40;
+        const JSON_CONFIG_DATA_REFRESH = 0x80;
+    }
+}
+
+pub trait OsIndicationsManager {
+    /// indications that are both supported by the firmware and requested for next boot
+    fn get_requested_os_indications(&self) -> crate::Result<OsIndications>;
+    /// request these indications for next boot, keeping the other requested ones.
+    /// Fails if the firmware does not list all of them in `OsIndicationsSupported`
+    fn set_os_indication(&mut self, indications: OsIndications) -> crate::Result<()>;
+    /// withdraw these indications, keeping the other requested ones
+    fn clear_os_indication(&mut self, indications: OsIndications) -> crate::Result<()>;
+}
+
+/// a missing `OsIndications` variable means nothing is requested
+fn get_os_indications_or_empty(reader: &(impl ?Sized + VarReader)) -> crate::Result<OsIndications> {
+    match reader.get_os_indications() {
+        Err(Error::VarNotFound { .. }) => Ok(OsIndications::empty()),
+        res => res,
+    }
+}
+
+impl<T: ?Sized + VarReader + VarWriter> OsIndicationsManager for T {
+    fn get_requested_os_indications(&self) -> crate::Result<OsIndications> {
+        Ok(get_os_indications_or_empty(self)? & self.get_os_indications_supported()?)
+    }
+
+    fn set_os_indication(&mut self, indications: OsIndications) -> crate::Result<()> {
+        let supported = match self.get_os_indications_supported() {
+            Err(Error::VarNotFound { .. }) => OsIndications::empty(),
+            res => res?,
+        };
+        let unsupported = indications.difference(supported);
+        if !unsupported.is_empty() {
+            return Err(Error::UnsupportedByFirmware {
+                what: format!("OS indications {unsupported:?}"),
+            });
+        }
+
+        self.set_os_indications(get_os_indications_or_empty(self)? | indications)
+    }
+
+    fn clear_os_indication(&mut self, indications: OsIndications) -> crate::Result<()> {
+        let current = get_os_indications_or_empty(self)?;
+        if !current.intersects(indications) {
+            return Ok(());
+        }
+
+        self.set_os_indications(current.difference(indications))
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "store")]
+mod tests {
+    use crate::{
+        efi::{Variable, VariableFlags},
+        globals::{GlobalVarReader, GlobalVarWriter},
+        store::MemoryStore,
+        Error, VarWriter,
+    };
+
+    use super::{OsIndications, OsIndicationsManager};
+
+    fn set_supported(store: &mut MemoryStore, supported: OsIndications) {
+        store
+            .write(
+                &Variable::new("OsIndicationsSupported"),
+                VariableFlags::BOOTSERVICE_ACCESS | VariableFlags::RUNTIME_ACCESS,
+                &supported.bits().to_le_bytes(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn set_clear_keeps_other_bits() {
+        let mut store = MemoryStore::new();
+        set_supported(
+            &mut store,
+            OsIndications::BOOT_TO_FW_UI | OsIndications::FILE_CAPSULE_DELIVERY_SUPPORTED,
+        );
+        store
+            .set_os_indications(OsIndications::FILE_CAPSULE_DELIVERY_SUPPORTED)
+            .unwrap();
+
+        store
+            .set_os_indication(OsIndications::BOOT_TO_FW_UI)
+            .unwrap();
+        assert_eq!(
+            store.get_os_indications().unwrap(),
+            OsIndications::BOOT_TO_FW_UI | OsIndications::FILE_CAPSULE_DELIVERY_SUPPORTED
+        );
+
+        store
+            .clear_os_indication(OsIndications::BOOT_TO_FW_UI)
+            .unwrap();
+        assert_eq!(
+            store.get_os_indications().unwrap(),
+            OsIndications::FILE_CAPSULE_DELIVERY_SUPPORTED
+        );
+    }
+
+    #[test]
+    fn unsupported() {
+        let mut store = MemoryStore::new();
+        assert!(matches!(
+            store.set_os_indication(OsIndications::BOOT_TO_FW_UI),
+            Err(Error::UnsupportedByFirmware { .. })
+        ));
+
+        set_supported(&mut store, OsIndications::FILE_CAPSULE_DELIVERY_SUPPORTED);
+        assert!(matches!(
+            store.set_os_indication(OsIndications::BOOT_TO_FW_UI),
+            Err(Error::UnsupportedByFirmware { .. })
+        ));
+        assert!(store.get_os_indications().is_err());
+
+        // clearing a missing variable does nothing
+        store
+            .clear_os_indication(OsIndications::BOOT_TO_FW_UI)
+            .unwrap();
+        assert!(store.get_os_indications().is_err());
+    }
+}
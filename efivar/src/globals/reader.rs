@@ -0,0 +1,306 @@
+//! This module contains functions to read the variables of the EFI global namespace
+
+#[cfg(feature = "secureboot")]
+use crate::secureboot::SignatureType;
+use crate::{
+    boot::{BootOptionSupport, FilePathList},
+    ParseError, VarReader,
+};
+
+use super::{GlobalVariable, OsIndications};
+
+pub trait GlobalVarReader {
+    /// seconds the boot manager waits before booting the first entry of the boot order.
+    /// 0xFFFF means waiting for user input
+    fn get_timeout(&self) -> crate::Result<u16>;
+    /// ID of the boot entry the system booted from
+    fn get_boot_current(&self) -> crate::Result<u16>;
+    /// ID of the boot entry to boot on next boot only
+    fn get_boot_next(&self) -> crate::Result<u16>;
+    fn get_boot_option_support(&self) -> crate::Result<BootOptionSupport>;
+    /// language of the firmware, as an ISO-639-2 code (e.g. `eng`). Deprecated by `PlatformLang`
+    fn get_lang(&self) -> crate::Result<String>;
+    /// languages supported by the firmware, as ISO-639-2 codes
+    fn get_lang_codes(&self) -> crate::Result<Vec<String>>;
+    /// language of the firmware, as an RFC 4646 code (e.g. `en-US`)
+    fn get_platform_lang(&self) -> crate::Result<String>;
+    /// languages supported by the firmware, as RFC 4646 codes
+    fn get_platform_lang_codes(&self) -> crate::Result<Vec<String>>;
+    /// default input console
+    fn get_con_in(&self) -> crate::Result<FilePathList>;
+    /// default output console
+    fn get_con_out(&self) -> crate::Result<FilePathList>;
+    /// default error output
+    fn get_err_out(&self) -> crate::Result<FilePathList>;
+    /// all possible input consoles
+    fn get_con_in_dev(&self) -> crate::Result<FilePathList>;
+    /// all possible output consoles
+    fn get_con_out_dev(&self) -> crate::Result<FilePathList>;
+    /// all possible error outputs
+    fn get_err_out_dev(&self) -> crate::Result<FilePathList>;
+    /// features requested to the firmware for next boot
+    fn get_os_indications(&self) -> crate::Result<OsIndications>;
+    /// features the firmware may be requested through `OsIndications`
+    fn get_os_indications_supported(&self) -> crate::Result<OsIndications>;
+    /// level of persistence of hardware error records supported by the platform
+    fn get_hw_err_rec_support(&self) -> crate::Result<u16>;
+    /// whether the firmware enforces Secure Boot on this boot
+    fn get_secure_boot(&self) -> crate::Result<bool>;
+    /// whether no Platform Key is enrolled, so that the signature databases may be freely written
+    fn get_setup_mode(&self) -> crate::Result<bool>;
+    /// whether image verification failures are only logged instead of preventing execution
+    fn get_audit_mode(&self) -> crate::Result<bool>;
+    /// whether the firmware refuses to leave user mode without a platform-specific action
+    fn get_deployed_mode(&self) -> crate::Result<bool>;
+    /// whether the Secure Boot keys are still the ones shipped with the platform
+    fn get_vendor_keys(&self) -> crate::Result<bool>;
+    /// signature types the firmware supports in the signature databases
+    #[cfg(feature = "secureboot")]
+    fn get_signature_support(&self) -> crate::Result<Vec<SignatureType>>;
+}
+
+fn read_u16(reader: &(impl ?Sized + VarReader), global: GlobalVariable) -> crate::Result<u16> {
+    global.read_with(reader, |buf| buf.read_u16(global.name))
+}
+
+fn read_bool(reader: &(impl ?Sized + VarReader), global: GlobalVariable) -> crate::Result<bool> {
+    global.read_with(reader, |buf| match buf.read_u8(global.name)? {
+        0 => Ok(false),
+        1 => Ok(true),
+        value => {
+            Err(ParseError::invalid(0, global.name, format!("expected 0 or 1, got {value}")).into())
+        }
+    })
+}
+
+fn read_file_path_list(
+    reader: &(impl ?Sized + VarReader),
+    global: GlobalVariable,
+) -> crate::Result<FilePathList> {
+    global.read_with(reader, FilePathList::parse)
+}
+
+fn read_os_indications(
+    reader: &(impl ?Sized + VarReader),
+    global: GlobalVariable,
+) -> crate::Result<OsIndications> {
+    global
+        .read_with(reader, |buf| buf.read_u64(global.name))
+        // keep unknown bits, so that writing the value back does not lose them
+        .map(OsIndications::from_bits_retain)
+}
+
+impl<T: ?Sized + VarReader> GlobalVarReader for T {
+    fn get_timeout(&self) -> crate::Result<u16> {
+        read_u16(self, GlobalVariable::TIMEOUT)
+    }
+
+    fn get_boot_current(&self) -> crate::Result<u16> {
+        read_u16(self, GlobalVariable::BOOT_CURRENT)
+    }
+
+    fn get_boot_next(&self) -> crate::Result<u16> {
+        read_u16(self, GlobalVariable::BOOT_NEXT)
+    }
+
+    fn get_boot_option_support(&self) -> crate::Result<BootOptionSupport> {
+        GlobalVariable::BOOT_OPTION_SUPPORT
+            .read_with(self, |buf| buf.read_u32("BootOptionSupport"))
+            .map(BootOptionSupport::from_bits_retain)
+    }
+
+    fn get_lang(&self) -> crate::Result<String> {
+        GlobalVariable::LANG.read_ascii(self)
+    }
+
+    fn get_lang_codes(&self) -> crate::Result<Vec<String>> {
+        // codes are 3 characters long, and are not separated
+        let codes = GlobalVariable::LANG_CODES.read_ascii(self)?;
+        Ok(codes
+            .as_bytes()
+            .chunks(3)
+            .map(|code| String::from_utf8_lossy(code).into_owned())
+            .collect())
+    }
+
+    fn get_platform_lang(&self) -> crate::Result<String> {
+        GlobalVariable::PLATFORM_LANG.read_ascii(self)
+    }
+
+    fn get_platform_lang_codes(&self) -> crate::Result<Vec<String>> {
+        let codes = GlobalVariable::PLATFORM_LANG_CODES.read_ascii(self)?;
+        Ok(codes
+            .split(';')
+            .filter(|code| !code.is_empty())
+            .map(str::to_owned)
+            .collect())
+    }
+
+    fn get_con_in(&self) -> crate::Result<FilePathList> {
+        read_file_path_list(self, GlobalVariable::CON_IN)
+    }
+
+    fn get_con_out(&self) -> crate::Result<FilePathList> {
+        read_file_path_list(self, GlobalVariable::CON_OUT)
+    }
+
+    fn get_err_out(&self) -> crate::Result<FilePathList> {
+        read_file_path_list(self, GlobalVariable::ERR_OUT)
+    }
+
+    fn get_con_in_dev(&self) -> crate::Result<FilePathList> {
+        read_file_path_list(self, GlobalVariable::CON_IN_DEV)
+    }
+
+    fn get_con_out_dev(&self) -> crate::Result<FilePathList> {
+        read_file_path_list(self, GlobalVariable::CON_OUT_DEV)
+    }
+
+    fn get_err_out_dev(&self) -> crate::Result<FilePathList> {
+        read_file_path_list(self, GlobalVariable::ERR_OUT_DEV)
+    }
+
+    fn get_os_indications(&self) -> crate::Result<OsIndications> {
+        read_os_indications(self, GlobalVariable::OS_INDICATIONS)
+    }
+
+    fn get_os_indications_supported(&self) -> crate::Result<OsIndications> {
+        read_os_indications(self, GlobalVariable::OS_INDICATIONS_SUPPORTED)
+    }
+
+    fn get_hw_err_rec_support(&self) -> crate::Result<u16> {
+        read_u16(self, GlobalVariable::HW_ERR_REC_SUPPORT)
+    }
+
+    fn get_secure_boot(&self) -> crate::Result<bool> {
+        read_bool(self, GlobalVariable::SECURE_BOOT)
+    }
+
+    fn get_setup_mode(&self) -> crate::Result<bool> {
+        read_bool(self, GlobalVariable::SETUP_MODE)
+    }
+
+    fn get_audit_mode(&self) -> crate::Result<bool> {
+        read_bool(self, GlobalVariable::AUDIT_MODE)
+    }
+
+    fn get_deployed_mode(&self) -> crate::Result<bool> {
+        read_bool(self, GlobalVariable::DEPLOYED_MODE)
+    }
+
+    fn get_vendor_keys(&self) -> crate::Result<bool> {
+        read_bool(self, GlobalVariable::VENDOR_KEYS)
+    }
+
+    #[cfg(feature = "secureboot")]
+    fn get_signature_support(&self) -> crate::Result<Vec<SignatureType>> {
+        let global = GlobalVariable::SIGNATURE_SUPPORT;
+        global.read_with(self, |buf| {
+            let mut types = vec![];
+            while !buf.is_empty() {
+                types.push(SignatureType::from_guid(buf.read_guid(global.name)?));
+            }
+            Ok(types)
+        })
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "store")]
+mod tests {
+    use crate::{
+        efi::{Variable, VariableFlags},
+        globals::{GlobalVarWriter, OsIndications},
+        store::MemoryStore,
+        Error, VarWriter,
+    };
+
+    use super::GlobalVarReader;
+
+    #[test]
+    fn missing() {
+        let store = MemoryStore::new();
+
+        assert!(matches!(
+            store.get_boot_current(),
+            Err(Error::VarNotFound { var }) if var == Variable::new("BootCurrent")
+        ));
+    }
+
+    #[test]
+    fn wrong_size() {
+        let mut store = MemoryStore::new();
+        for value in [&[0x01][..], &[0x01, 0x00, 0x00]] {
+            store
+                .write(&Variable::new("Timeout"), VariableFlags::default(), value)
+                .unwrap();
+
+            let err = store.get_timeout().unwrap_err();
+            assert!(matches!(err, Error::ParseError(_)));
+            assert!(err.to_string().contains("'Timeout-"));
+        }
+    }
+
+    #[test]
+    fn wrong_attributes() {
+        let mut store = MemoryStore::new();
+        store
+            .write(
+                &Variable::new("BootCurrent"),
+                VariableFlags::BOOTSERVICE_ACCESS,
+                &[0x01, 0x00],
+            )
+            .unwrap();
+
+        assert!(matches!(
+            store.get_boot_current(),
+            Err(Error::InvalidAttributes { .. })
+        ));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut store = MemoryStore::new();
+
+        store.set_timeout(5).unwrap();
+        assert_eq!(store.get_timeout().unwrap(), 5);
+
+        store.set_boot_next(0x1000).unwrap();
+        assert_eq!(store.get_boot_next().unwrap(), 0x1000);
+        store.delete_boot_next().unwrap();
+        assert!(store.get_boot_next().is_err());
+
+        store.set_platform_lang("en-US").unwrap();
+        assert_eq!(store.get_platform_lang().unwrap(), "en-US");
+        assert!(store.set_lang("english").is_err());
+
+        let indications = OsIndications::BOOT_TO_FW_UI | OsIndications::from_bits_retain(1 << 40);
+        store.set_os_indications(indications).unwrap();
+        assert_eq!(store.get_os_indications().unwrap(), indications);
+    }
+
+    #[test]
+    fn lang_codes() {
+        let mut store = MemoryStore::new();
+        store
+            .write(
+                &Variable::new("LangCodes"),
+                VariableFlags::BOOTSERVICE_ACCESS | VariableFlags::RUNTIME_ACCESS,
+                b"engfra\0",
+            )
+            .unwrap();
+        store
+            .write(
+                &Variable::new("PlatformLangCodes"),
+                VariableFlags::BOOTSERVICE_ACCESS | VariableFlags::RUNTIME_ACCESS,
+                b"en-US;fr-FR\0",
+            )
+            .unwrap();
+
+        assert_eq!(store.get_lang_codes().unwrap(), vec!["eng", "fra"]);
+        assert_eq!(
+            store.get_platform_lang_codes().unwrap(),
+            vec!["en-US", "fr-FR"]
+        );
+    }
+}
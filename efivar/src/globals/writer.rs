@@ -0,0 +1,91 @@
+//! This module contains functions to write the variables of the EFI global namespace.
+//! Variables that are read-only for the OS, such as `BootCurrent`, have no setter
+
+use crate::{boot::FilePathList, Error, VarWriter};
+
+use super::{GlobalVariable, OsIndications};
+
+pub trait GlobalVarWriter {
+    fn set_timeout(&mut self, timeout: u16) -> crate::Result<()>;
+    fn delete_timeout(&mut self) -> crate::Result<()>;
+    fn set_boot_next(&mut self, id: u16) -> crate::Result<()>;
+    fn delete_boot_next(&mut self) -> crate::Result<()>;
+    /// set the language of the firmware, as an ISO-639-2 code (e.g. `eng`)
+    fn set_lang(&mut self, lang: &str) -> crate::Result<()>;
+    /// set the language of the firmware, as an RFC 4646 code (e.g. `en-US`)
+    fn set_platform_lang(&mut self, lang: &str) -> crate::Result<()>;
+    fn set_con_in(&mut self, devices: &FilePathList) -> crate::Result<()>;
+    fn set_con_out(&mut self, devices: &FilePathList) -> crate::Result<()>;
+    fn set_err_out(&mut self, devices: &FilePathList) -> crate::Result<()>;
+    fn set_os_indications(&mut self, indications: OsIndications) -> crate::Result<()>;
+    fn delete_os_indications(&mut self) -> crate::Result<()>;
+}
+
+/// write a null-terminated ASCII string
+fn write_ascii(
+    writer: &mut (impl ?Sized + VarWriter),
+    global: GlobalVariable,
+    value: &str,
+) -> crate::Result<()> {
+    let mut bytes = value.as_bytes().to_vec();
+    bytes.push(0x00);
+    global.write(writer, &bytes)
+}
+
+fn check_lang(lang: &str, valid: bool) -> crate::Result<()> {
+    if valid && !lang.is_empty() && lang.is_ascii() && !lang.contains(['\0', ';']) {
+        Ok(())
+    } else {
+        Err(Error::InvalidLanguage {
+            lang: lang.to_owned(),
+        })
+    }
+}
+
+impl<T: ?Sized + VarWriter> GlobalVarWriter for T {
+    fn set_timeout(&mut self, timeout: u16) -> crate::Result<()> {
+        GlobalVariable::TIMEOUT.write(self, &timeout.to_le_bytes())
+    }
+
+    fn delete_timeout(&mut self) -> crate::Result<()> {
+        self.delete(&GlobalVariable::TIMEOUT.variable())
+    }
+
+    fn set_boot_next(&mut self, id: u16) -> crate::Result<()> {
+        GlobalVariable::BOOT_NEXT.write(self, &id.to_le_bytes())
+    }
+
+    fn delete_boot_next(&mut self) -> crate::Result<()> {
+        self.delete(&GlobalVariable::BOOT_NEXT.variable())
+    }
+
+    fn set_lang(&mut self, lang: &str) -> crate::Result<()> {
+        check_lang(lang, lang.len() == 3)?;
+        write_ascii(self, GlobalVariable::LANG, lang)
+    }
+
+    fn set_platform_lang(&mut self, lang: &str) -> crate::Result<()> {
+        check_lang(lang, true)?;
+        write_ascii(self, GlobalVariable::PLATFORM_LANG, lang)
+    }
+
+    fn set_con_in(&mut self, devices: &FilePathList) -> crate::Result<()> {
+        GlobalVariable::CON_IN.write(self, &devices.to_bytes()?)
+    }
+
+    fn set_con_out(&mut self, devices: &FilePathList) -> crate::Result<()> {
+        GlobalVariable::CON_OUT.write(self, &devices.to_bytes()?)
+    }
+
+    fn set_err_out(&mut self, devices: &FilePathList) -> crate::Result<()> {
+        GlobalVariable::ERR_OUT.write(self, &devices.to_bytes()?)
+    }
+
+    fn set_os_indications(&mut self, indications: OsIndications) -> crate::Result<()> {
+        GlobalVariable::OS_INDICATIONS.write(self, &indications.bits().to_le_bytes())
+    }
+
+    fn delete_os_indications(&mut self) -> crate::Result<()> {
+        self.delete(&GlobalVariable::OS_INDICATIONS.variable())
+    }
+}
@@ -22,8 +22,13 @@ pub mod store;
 pub mod boot;
 mod enumerator;
 mod error;
+pub mod globals;
+#[cfg(feature = "secureboot")]
+pub mod mok;
 pub mod push;
 mod reader;
+#[cfg(feature = "secureboot")]
+pub mod secureboot;
 mod sys;
 pub mod test_utils;
 pub mod utils;
@@ -35,7 +40,7 @@ pub use crate::enumerator::VarEnumerator;
 pub use crate::reader::*;
 pub use crate::writer::VarWriter;
 
-pub use crate::error::Error;
+pub use crate::error::{Error, ParseError, ParseErrorKind};
 
 /// Result type for this crate's API functions
 pub type Result<T> = std::result::Result<T, Error>;
@@ -100,6 +105,14 @@ pub fn file_store<P: Into<std::path::PathBuf>>(filename: P) -> Box<dyn VarManage
     Box::new(store::FileStore::new(filename.into()))
 }
 
+/// Returns a `VarManager` which reads and writes variables in the NVRAM image of an edk2
+/// firmware, such as the `OVMF_VARS.fd` file of a virtual machine. Changes are written to the
+/// file immediately.
+#[cfg(feature = "store")]
+pub fn ovmf_vars<P: Into<std::path::PathBuf>>(filename: P) -> crate::Result<Box<dyn VarManager>> {
+    Ok(Box::new(store::OvmfStore::open(filename.into())?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,133 @@
+//! This module contains accessors for the variables of shim, the first-stage bootloader that lets
+//! the machine owner trust keys besides the ones of `db`: the Machine Owner Keys (MOK).
+//!
+//! shim mirrors its boot-time variables to runtime ones suffixed with `RT`, such as `MokListRT`.
+//! Changes are not made directly: the OS writes a request, such as `MokNew`, along with the hash of
+//! a password, and MokManager asks the user for this password on next boot before applying it.
+
+mod reader;
+mod sbat;
+mod writer;
+
+use std::str::FromStr;
+
+pub use reader::MokVarReader;
+pub use sbat::{read_sbat_level, ImageSbat, SbatCheck, SbatEntry, SbatRevocation};
+pub use writer::MokVarWriter;
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+    efi::{Variable, VariableFlags, VariableVendor},
+    Error, VarWriter,
+};
+
+lazy_static::lazy_static! {
+    /// Vendor GUID of the variables of shim, also used as the owner of the signatures it enrolls
+    pub static ref SHIM_LOCK_GUID: uuid::Uuid =
+        uuid::Uuid::from_str("605dab50-e046-4300-abb6-3dd810dd8b23").unwrap();
+}
+
+const BS_RT: VariableFlags = VariableFlags::BOOTSERVICE_ACCESS.union(VariableFlags::RUNTIME_ACCESS);
+const NV_BS_RT: VariableFlags = VariableFlags::NON_VOLATILE.union(BS_RT);
+
+/// longest password of a request, in characters
+const PASSWORD_MAX_LEN: usize = 256;
+/// shortest and longest password of a `MokSB` request, whose characters MokManager asks one by
+/// one
+const SB_PASSWORD_MIN_LEN: usize = 8;
+const SB_PASSWORD_MAX_LEN: usize = 16;
+
+/// A variable of shim, along with the attributes shim gives it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MokVariable {
+    pub name: &'static str,
+    pub attributes: VariableFlags,
+}
+
+impl MokVariable {
+    /// keys trusted in addition to `db`
+    pub const MOK_LIST_RT: MokVariable = MokVariable::new("MokListRT", BS_RT);
+    /// keys and hashes refused in addition to `dbx`
+    pub const MOK_LIST_X_RT: MokVariable = MokVariable::new("MokListXRT", BS_RT);
+    /// whether shim does not validate images
+    pub const MOK_SB_STATE_RT: MokVariable = MokVariable::new("MokSBStateRT", BS_RT);
+    /// SBAT revocations applied by shim
+    pub const SBAT_LEVEL_RT: MokVariable = MokVariable::new("SbatLevelRT", BS_RT);
+    /// keys to enroll on next boot
+    pub const MOK_NEW: MokVariable = MokVariable::new("MokNew", NV_BS_RT);
+    /// password hash of `MokNew`
+    pub const MOK_AUTH: MokVariable = MokVariable::new("MokAuth", NV_BS_RT);
+    /// keys to delete on next boot
+    pub const MOK_DEL: MokVariable = MokVariable::new("MokDel", NV_BS_RT);
+    /// password hash of `MokDel`
+    pub const MOK_DEL_AUTH: MokVariable = MokVariable::new("MokDelAuth", NV_BS_RT);
+    /// request to change the validation state on next boot
+    pub const MOK_SB: MokVariable = MokVariable::new("MokSB", NV_BS_RT);
+
+    const fn new(name: &'static str, attributes: VariableFlags) -> MokVariable {
+        MokVariable { name, attributes }
+    }
+
+    pub fn variable(&self) -> Variable {
+        Variable::new_with_vendor(self.name, VariableVendor::Custom(*SHIM_LOCK_GUID))
+    }
+
+    /// write the raw value of the variable, with the attributes shim gives it
+    pub fn write(&self, writer: &mut (impl ?Sized + VarWriter), value: &[u8]) -> crate::Result<()> {
+        writer.write(&self.variable(), self.attributes, value)?;
+
+        log::debug!("Set {} to {value:02x?}", self.name);
+        Ok(())
+    }
+}
+
+/// MokManager reads passwords as UCS-2 keystrokes, so only ASCII is accepted
+fn check_password(password: &str, min_len: usize, max_len: usize) -> crate::Result<()> {
+    if !password.is_ascii() {
+        return Err(Error::InvalidPassword {
+            reason: "only ASCII characters can be typed in MokManager".to_owned(),
+        });
+    }
+    if password.len() < min_len || password.len() > max_len {
+        return Err(Error::InvalidPassword {
+            reason: format!("must be {min_len} to {max_len} characters long"),
+        });
+    }
+    Ok(())
+}
+
+/// The hash shim checks a request against: SHA-256 of the request followed by the password in
+/// UCS-2, as `mokutil --simple-hash` computes it
+pub fn password_hash(request: &[u8], password: &str) -> crate::Result<[u8; 32]> {
+    check_password(password, 1, PASSWORD_MAX_LEN)?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(request);
+    for c in password.encode_utf16() {
+        hasher.update(c.to_le_bytes());
+    }
+    Ok(hasher.finalize().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::hex;
+
+    use super::password_hash;
+
+    #[test]
+    fn hash() {
+        assert_eq!(
+            hex(&password_hash(&[], "password").unwrap()),
+            "e201065d0554652615c320c00a1d5bc8edca469d72c2790e24152d0c1e2b6189"
+        );
+        assert_eq!(
+            hex(&password_hash(&[0x01, 0x02], "abc").unwrap()),
+            "5539d1cb0ba34960489c3c9b83fa7ebd40b2767298f1ffc55b0e0493b8b45234"
+        );
+
+        assert!(password_hash(&[], "").is_err());
+        assert!(password_hash(&[], "pässword").is_err());
+    }
+}
@@ -0,0 +1,123 @@
+//! This module contains functions to read the variables shim mirrors at runtime, and the pending
+//! requests
+
+use crate::{secureboot::SignatureDatabase, Error, ParseError, VarReader};
+
+use super::MokVariable;
+
+pub trait MokVarReader {
+    /// keys trusted by shim in addition to `db`
+    fn get_mok_list(&self) -> crate::Result<SignatureDatabase>;
+    /// keys and hashes refused by shim in addition to `dbx`
+    fn get_mok_list_x(&self) -> crate::Result<SignatureDatabase>;
+    /// whether shim runs images without validating them. `false` if `MokSBStateRT` is not set
+    fn get_mok_validation_disabled(&self) -> crate::Result<bool>;
+    /// SBAT revocations applied by shim, as CSV lines (e.g. `sbat,1,2023012900`)
+    fn get_sbat_level(&self) -> crate::Result<String>;
+    /// keys requested to be enrolled on next boot
+    fn get_mok_new(&self) -> crate::Result<SignatureDatabase>;
+    /// keys requested to be deleted on next boot
+    fn get_mok_del(&self) -> crate::Result<SignatureDatabase>;
+}
+
+fn read_list(
+    reader: &(impl ?Sized + VarReader),
+    mok: MokVariable,
+) -> crate::Result<SignatureDatabase> {
+    let variable = mok.variable();
+    let (value, _flags) = reader.read(&variable)?;
+    SignatureDatabase::parse(&value).map_err(|err| err.with_variable(&variable))
+}
+
+impl<T: ?Sized + VarReader> MokVarReader for T {
+    fn get_mok_list(&self) -> crate::Result<SignatureDatabase> {
+        read_list(self, MokVariable::MOK_LIST_RT)
+    }
+
+    fn get_mok_list_x(&self) -> crate::Result<SignatureDatabase> {
+        read_list(self, MokVariable::MOK_LIST_X_RT)
+    }
+
+    fn get_mok_validation_disabled(&self) -> crate::Result<bool> {
+        let variable = MokVariable::MOK_SB_STATE_RT.variable();
+        match self.read(&variable) {
+            Ok((value, _flags)) => match value[..] {
+                [state] => Ok(state == 1),
+                _ => Err(Error::from(ParseError::invalid(
+                    0,
+                    MokVariable::MOK_SB_STATE_RT.name,
+                    format!("expected 1 byte, got {}", value.len()),
+                ))
+                .with_variable(&variable)),
+            },
+            Err(Error::VarNotFound { .. }) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn get_sbat_level(&self) -> crate::Result<String> {
+        let variable = MokVariable::SBAT_LEVEL_RT.variable();
+        let (value, _flags) = self.read(&variable)?;
+        let value = value.strip_suffix(&[0x00]).unwrap_or(&value);
+
+        String::from_utf8(value.to_vec()).map_err(|_| {
+            Error::from(ParseError::invalid(
+                0,
+                MokVariable::SBAT_LEVEL_RT.name,
+                "not an UTF-8 string",
+            ))
+            .with_variable(&variable)
+        })
+    }
+
+    fn get_mok_new(&self) -> crate::Result<SignatureDatabase> {
+        read_list(self, MokVariable::MOK_NEW)
+    }
+
+    fn get_mok_del(&self) -> crate::Result<SignatureDatabase> {
+        read_list(self, MokVariable::MOK_DEL)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "store")]
+mod tests {
+    use crate::{efi::VariableFlags, mok::MokVariable, store::MemoryStore, Error, VarWriter};
+
+    use super::MokVarReader;
+
+    #[test]
+    fn read() {
+        let mut store = MemoryStore::new();
+        assert!(matches!(
+            store.get_mok_list(),
+            Err(Error::VarNotFound { .. })
+        ));
+        assert!(!store.get_mok_validation_disabled().unwrap());
+
+        let attributes = VariableFlags::BOOTSERVICE_ACCESS | VariableFlags::RUNTIME_ACCESS;
+        for (mok, value) in [
+            (MokVariable::MOK_LIST_RT, &[][..]),
+            (MokVariable::MOK_SB_STATE_RT, &[0x01]),
+            (MokVariable::SBAT_LEVEL_RT, b"sbat,1,2022052400\ngrub,2\n"),
+        ] {
+            store.write(&mok.variable(), attributes, value).unwrap();
+        }
+
+        assert!(store.get_mok_list().unwrap().lists.is_empty());
+        assert!(store.get_mok_validation_disabled().unwrap());
+        assert_eq!(
+            store.get_sbat_level().unwrap(),
+            "sbat,1,2022052400\ngrub,2\n"
+        );
+
+        store
+            .write(
+                &MokVariable::MOK_SB_STATE_RT.variable(),
+                attributes,
+                &[0x01, 0x00],
+            )
+            .unwrap();
+        assert!(store.get_mok_validation_disabled().is_err());
+    }
+}
@@ -0,0 +1,221 @@
+//! This module checks the SBAT (Secure Boot Advanced Targeting) metadata of EFI images against the
+//! revocations shim applies, found in `SbatLevelRT`.
+//!
+//! Both are CSV lines starting with a component name and its generation. shim refuses an image if
+//! one of its components has a lower generation than the one required for that component
+
+use crate::{secureboot::PeImage, ParseError, VarReader};
+
+use super::MokVarReader;
+
+/// name of the PE section holding the SBAT metadata of an image
+const SBAT_SECTION: &str = ".sbat";
+
+/// A line of SBAT metadata, such as `grub,3,Free Software Foundation,grub,2.06,https://...`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SbatEntry {
+    pub component: String,
+    pub generation: u32,
+    /// the other fields: for images, the vendor name, package name, version and URL. For
+    /// `SbatLevelRT`, the date of the `sbat` entry
+    pub fields: Vec<String>,
+}
+
+impl SbatEntry {
+    /// Parse SBAT CSV lines, stopping at the first null byte. Empty lines are skipped
+    pub fn parse_csv(csv: &[u8], field: &'static str) -> crate::Result<Vec<SbatEntry>> {
+        let csv = &csv[..csv.iter().position(|&c| c == 0).unwrap_or(csv.len())];
+
+        let mut entries = vec![];
+        let mut offset = 0;
+        for line in csv.split(|&c| c == b'\n') {
+            let line_offset = offset;
+            offset += line.len() + 1;
+
+            let line = std::str::from_utf8(line)
+                .map_err(|_| ParseError::invalid(line_offset, field, "not an UTF-8 string"))?
+                .trim_end_matches('\r');
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut fields = line.split(',').map(ToString::to_string);
+            let component = fields.next().unwrap_or_default();
+            let generation = fields
+                .next()
+                .and_then(|generation| generation.trim().parse().ok())
+                .ok_or_else(|| {
+                    ParseError::invalid(
+                        line_offset,
+                        field,
+                        format!("invalid generation in '{line}'"),
+                    )
+                })?;
+
+            entries.push(SbatEntry {
+                component,
+                generation,
+                fields: fields.collect(),
+            });
+        }
+
+        Ok(entries)
+    }
+}
+
+/// The SBAT metadata of an image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageSbat {
+    /// entries of the `.sbat` section, or `None` if the image has no such section
+    pub entries: Option<Vec<SbatEntry>>,
+}
+
+/// A component of an image older than what the SBAT level requires
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SbatRevocation {
+    pub component: String,
+    pub image_generation: u32,
+    pub required_generation: u32,
+}
+
+/// Whether shim would load an image according to its SBAT metadata
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SbatCheck {
+    /// the image has no SBAT metadata while the level revokes some, which shim refuses
+    pub missing_section: bool,
+    pub revocations: Vec<SbatRevocation>,
+}
+
+impl SbatCheck {
+    pub fn is_allowed(&self) -> bool {
+        !self.missing_section && self.revocations.is_empty()
+    }
+}
+
+impl ImageSbat {
+    /// Read the `.sbat` section of a PE/COFF image
+    pub fn parse(image: &[u8]) -> crate::Result<ImageSbat> {
+        let image = PeImage::parse(image)?;
+
+        Ok(ImageSbat {
+            entries: image
+                .section_data(SBAT_SECTION)
+                .map(|section| SbatEntry::parse_csv(section, SBAT_SECTION))
+                .transpose()?,
+        })
+    }
+
+    /// Compare the image with SBAT level entries, as parsed from `SbatLevelRT`
+    pub fn check(&self, level: &[SbatEntry]) -> SbatCheck {
+        let entries = match &self.entries {
+            Some(entries) => entries,
+            None => {
+                return SbatCheck {
+                    missing_section: !level.is_empty(),
+                    revocations: vec![],
+                }
+            }
+        };
+
+        let revocations = level
+            .iter()
+            .flat_map(|required| {
+                entries
+                    .iter()
+                    .filter(move |entry| {
+                        entry.component == required.component
+                            && entry.generation < required.generation
+                    })
+                    .map(move |entry| SbatRevocation {
+                        component: entry.component.clone(),
+                        image_generation: entry.generation,
+                        required_generation: required.generation,
+                    })
+            })
+            .collect();
+
+        SbatCheck {
+            missing_section: false,
+            revocations,
+        }
+    }
+
+    /// Compare the image with the SBAT level shim applied on this boot
+    pub fn check_variables(&self, reader: &(impl ?Sized + VarReader)) -> crate::Result<SbatCheck> {
+        Ok(self.check(&read_sbat_level(reader)?))
+    }
+}
+
+/// The entries of `SbatLevelRT`
+pub fn read_sbat_level(reader: &(impl ?Sized + VarReader)) -> crate::Result<Vec<SbatEntry>> {
+    let level = reader.get_sbat_level()?;
+    SbatEntry::parse_csv(level.as_bytes(), super::MokVariable::SBAT_LEVEL_RT.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ImageSbat, SbatEntry, SbatRevocation};
+
+    const SIGNED_IMAGE: &[u8] = include_bytes!("../secureboot/tests/signed.efi");
+
+    #[test]
+    fn parse() {
+        let entries =
+            SbatEntry::parse_csv(b"sbat,1,2023012900\r\n\ngrub,4\n\0\0garbage", "SBAT").unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                SbatEntry {
+                    component: "sbat".to_owned(),
+                    generation: 1,
+                    fields: vec!["2023012900".to_owned()],
+                },
+                SbatEntry {
+                    component: "grub".to_owned(),
+                    generation: 4,
+                    fields: vec![],
+                },
+            ]
+        );
+
+        assert!(SbatEntry::parse_csv(b"sbat,1\ngrub\n", "SBAT").is_err());
+        assert!(SbatEntry::parse_csv(b"grub,x\n", "SBAT").is_err());
+    }
+
+    #[test]
+    fn check() {
+        let image = ImageSbat::parse(SIGNED_IMAGE).unwrap();
+        let entries = image.entries.as_ref().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].component, "grub");
+        assert_eq!(entries[1].fields[2], "2.06");
+
+        assert!(image.check(&[]).is_allowed());
+        let level = SbatEntry::parse_csv(b"sbat,1,2022052400\ngrub,3\nshim,2\n", "SBAT").unwrap();
+        assert!(image.check(&level).is_allowed());
+
+        let level = SbatEntry::parse_csv(b"sbat,1,2023012900\ngrub,4\n", "SBAT").unwrap();
+        let check = image.check(&level);
+        assert!(!check.is_allowed());
+        assert_eq!(
+            check.revocations,
+            vec![SbatRevocation {
+                component: "grub".to_owned(),
+                image_generation: 3,
+                required_generation: 4,
+            }]
+        );
+
+        // rename the section
+        let mut unnamed = SIGNED_IMAGE.to_vec();
+        let name = unnamed
+            .windows(5)
+            .position(|name| name == b".sbat")
+            .unwrap();
+        unnamed[name + 1] = b'x';
+        let image = ImageSbat::parse(&unnamed).unwrap();
+        assert_eq!(image.entries, None);
+        assert!(image.check(&[]).is_allowed());
+        assert!(image.check(&level).missing_section);
+    }
+}
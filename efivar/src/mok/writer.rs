@@ -0,0 +1,187 @@
+//! This module contains functions to write requests that MokManager applies on next boot, once the
+//! user confirms them with their password
+
+use x509_cert::Certificate;
+
+use crate::{
+    secureboot::{SignatureDatabase, SignatureList},
+    Error, VarReader, VarWriter,
+};
+
+use super::{
+    check_password, password_hash, MokVariable, SB_PASSWORD_MAX_LEN, SB_PASSWORD_MIN_LEN,
+    SHIM_LOCK_GUID,
+};
+
+pub trait MokVarWriter {
+    /// request the enrollment of certificates, adding to the pending request if there is one.
+    /// The password, which MokManager asks for, protects the whole request
+    fn request_mok_import(
+        &mut self,
+        certificates: &[Certificate],
+        password: &str,
+    ) -> crate::Result<()>;
+    /// request the deletion of enrolled certificates, adding to the pending request if there is
+    /// one
+    fn request_mok_delete(
+        &mut self,
+        certificates: &[Certificate],
+        password: &str,
+    ) -> crate::Result<()>;
+    /// request shim to stop validating images. MokManager asks for some characters of the
+    /// password, which must be 8 to 16 characters long
+    fn request_disable_validation(&mut self, password: &str) -> crate::Result<()>;
+}
+
+/// add certificates to a request, then write it along with its password hash
+fn write_request(
+    manager: &mut (impl ?Sized + VarReader + VarWriter),
+    list: MokVariable,
+    auth: MokVariable,
+    certificates: &[Certificate],
+    password: &str,
+) -> crate::Result<()> {
+    // fail before touching a pending request
+    password_hash(&[], password)?;
+
+    let variable = list.variable();
+    let mut request = match manager.read(&variable) {
+        Ok((value, _flags)) => {
+            SignatureDatabase::parse(&value).map_err(|err| err.with_variable(&variable))?
+        }
+        Err(Error::VarNotFound { .. }) => SignatureDatabase::default(),
+        Err(err) => return Err(err),
+    };
+
+    for certificate in certificates {
+        let certificate = SignatureList::x509(*SHIM_LOCK_GUID, certificate)?;
+        if !request.lists.contains(&certificate) {
+            request.lists.push(certificate);
+        }
+    }
+
+    let value = request.to_bytes()?;
+    list.write(manager, &value)?;
+    auth.write(manager, &password_hash(&value, password)?)
+}
+
+impl<T: ?Sized + VarReader + VarWriter> MokVarWriter for T {
+    fn request_mok_import(
+        &mut self,
+        certificates: &[Certificate],
+        password: &str,
+    ) -> crate::Result<()> {
+        write_request(
+            self,
+            MokVariable::MOK_NEW,
+            MokVariable::MOK_AUTH,
+            certificates,
+            password,
+        )
+    }
+
+    fn request_mok_delete(
+        &mut self,
+        certificates: &[Certificate],
+        password: &str,
+    ) -> crate::Result<()> {
+        write_request(
+            self,
+            MokVariable::MOK_DEL,
+            MokVariable::MOK_DEL_AUTH,
+            certificates,
+            password,
+        )
+    }
+
+    fn request_disable_validation(&mut self, password: &str) -> crate::Result<()> {
+        check_password(password, SB_PASSWORD_MIN_LEN, SB_PASSWORD_MAX_LEN)?;
+
+        // MokSBvar: the requested state (0 to disable validation), then the password in clear
+        // since MokManager checks it character by character
+        let mut value = vec![];
+        value.extend_from_slice(&0u32.to_le_bytes());
+        value.extend_from_slice(&(password.len() as u32).to_le_bytes());
+        let mut chars = password.encode_utf16().collect::<Vec<_>>();
+        chars.resize(SB_PASSWORD_MAX_LEN, 0);
+        for c in chars {
+            value.extend_from_slice(&c.to_le_bytes());
+        }
+
+        MokVariable::MOK_SB.write(self, &value)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "store")]
+mod tests {
+    use x509_cert::{der::Decode, Certificate};
+
+    use crate::{
+        mok::{password_hash, MokVarReader, MokVariable, SHIM_LOCK_GUID},
+        store::MemoryStore,
+        test_utils::TEST_CERT,
+        VarReader,
+    };
+
+    use super::MokVarWriter;
+
+    #[test]
+    fn import() {
+        let mut store = MemoryStore::new();
+        let certificate = Certificate::from_der(TEST_CERT).unwrap();
+
+        assert!(store
+            .request_mok_import(std::slice::from_ref(&certificate), "")
+            .is_err());
+        assert!(store.read(&MokVariable::MOK_NEW.variable()).is_err());
+
+        store
+            .request_mok_import(std::slice::from_ref(&certificate), "password")
+            .unwrap();
+        // already requested
+        store
+            .request_mok_import(&[certificate], "password")
+            .unwrap();
+
+        let request = store.get_mok_new().unwrap();
+        assert_eq!(request.lists.len(), 1);
+        assert_eq!(request.lists[0].signatures[0].owner, *SHIM_LOCK_GUID);
+        assert_eq!(request.lists[0].signatures[0].data, TEST_CERT);
+
+        let (auth, _) = store.read(&MokVariable::MOK_AUTH.variable()).unwrap();
+        assert_eq!(
+            auth,
+            password_hash(&request.to_bytes().unwrap(), "password").unwrap()
+        );
+    }
+
+    #[test]
+    fn delete() {
+        let mut store = MemoryStore::new();
+        let certificate = Certificate::from_der(TEST_CERT).unwrap();
+
+        store
+            .request_mok_delete(&[certificate], "password")
+            .unwrap();
+        assert_eq!(store.get_mok_del().unwrap().lists.len(), 1);
+        assert!(store.read(&MokVariable::MOK_DEL_AUTH.variable()).is_ok());
+        assert!(store.read(&MokVariable::MOK_NEW.variable()).is_err());
+    }
+
+    #[test]
+    fn disable_validation() {
+        let mut store = MemoryStore::new();
+
+        assert!(store.request_disable_validation("short").is_err());
+        assert!(store
+            .request_disable_validation("much too long password")
+            .is_err());
+
+        store.request_disable_validation("password").unwrap();
+        let (value, attributes) = store.read(&MokVariable::MOK_SB.variable()).unwrap();
+        assert_eq!(attributes, MokVariable::MOK_SB.attributes);
+        assert_eq!(value.len(), 40);
+        assert_eq!(value[..10], [0, 0, 0, 0, 8, 0, 0, 0, b'p', 0]);
+    }
+}
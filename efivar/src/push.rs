@@ -3,6 +3,8 @@ pub trait PushVecU8 {
     fn push_u16(&mut self, value: u16);
     fn push_u32(&mut self, value: u32);
     fn push_u64(&mut self, value: u64);
+    /// push a GUID in the EFI (mixed-endian) binary layout
+    fn push_guid(&mut self, value: &uuid::Uuid);
 }
 impl PushVecU8 for Vec<u8> {
     fn push_u8(&mut self, value: u8) {
@@ -20,4 +22,8 @@ impl PushVecU8 for Vec<u8> {
     fn push_u64(&mut self, value: u64) {
         self.append(&mut value.to_le_bytes().to_vec())
     }
+
+    fn push_guid(&mut self, value: &uuid::Uuid) {
+        self.append(&mut value.to_bytes_le().to_vec())
+    }
 }
@@ -0,0 +1,134 @@
+//! This module handles the Secure Boot signature databases: `PK`, `KEK`, `db`, `dbx`, `dbt` and
+//! `dbr`. See spec, 32.4
+
+mod authenticated;
+mod authenticode;
+mod certificates;
+mod drift;
+mod keys;
+mod pe;
+mod signature_list;
+mod signing_key;
+mod state;
+
+use std::{fmt::Display, str::FromStr};
+
+pub use crate::efi::EfiTime;
+pub use authenticated::{AuthenticatedPayload, EFI_CERT_TYPE_PKCS7_GUID};
+pub use authenticode::{AuthenticodeImage, AuthenticodeSignature, ImageCheck, ImageMatch};
+pub use certificates::{
+    CertificateExpiry, CertificateInfo, DatabaseCertificate, CERTIFICATE_VARIABLES,
+    MICROSOFT_UEFI_CA_2023, WINDOWS_UEFI_CA_2023,
+};
+pub use drift::{DatabaseDrift, DriftChange, DriftEntry, DRIFT_VARIABLES};
+pub use keys::SecureBootKeys;
+pub use pe::{PeImage, PeSection};
+pub use signature_list::{
+    Signature, SignatureData, SignatureDatabase, SignatureList, SignatureType,
+};
+pub use signing_key::{parse_certificate, SigningKey};
+pub use state::{SecureBootMode, SecureBootState};
+pub use x509_cert::Certificate;
+
+use crate::{
+    efi::{Variable, VariableFlags, VariableVendor},
+    Error,
+};
+
+lazy_static::lazy_static! {
+    /// Vendor GUID of the `db`, `dbx`, `dbt` and `dbr` variables
+    pub static ref IMAGE_SECURITY_DATABASE_GUID: uuid::Uuid =
+        uuid::Uuid::from_str("d719b2cb-3d3a-4596-a3bc-dad00e67656f").unwrap();
+    /// Owner GUID of the Microsoft signatures in `KEK`, `db` and `dbx`
+    pub static ref MICROSOFT_OWNER_GUID: uuid::Uuid =
+        uuid::Uuid::from_str("77fa9abd-0359-4d32-bd60-28f4e78f784b").unwrap();
+}
+
+/// A variable holding a signature database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SecureBootVariable {
+    /// Platform Key, which authorizes updates to `KEK`
+    Pk,
+    /// Key Exchange Keys, which authorize updates to the other databases
+    Kek,
+    /// allowed signatures
+    Db,
+    /// forbidden signatures
+    Dbx,
+    /// timestamping authorities, used to check revocation times in `dbx`
+    Dbt,
+    /// OS recovery signatures
+    Dbr,
+}
+
+impl SecureBootVariable {
+    pub const ALL: [SecureBootVariable; 6] = [
+        SecureBootVariable::Pk,
+        SecureBootVariable::Kek,
+        SecureBootVariable::Db,
+        SecureBootVariable::Dbx,
+        SecureBootVariable::Dbt,
+        SecureBootVariable::Dbr,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SecureBootVariable::Pk => "PK",
+            SecureBootVariable::Kek => "KEK",
+            SecureBootVariable::Db => "db",
+            SecureBootVariable::Dbx => "dbx",
+            SecureBootVariable::Dbt => "dbt",
+            SecureBootVariable::Dbr => "dbr",
+        }
+    }
+
+    /// `PK` and `KEK` are in the EFI namespace, the other ones in the image security database one
+    pub fn variable(self) -> Variable {
+        match self {
+            SecureBootVariable::Pk | SecureBootVariable::Kek => Variable::new(self.name()),
+            _ => Variable::new_with_vendor(
+                self.name(),
+                VariableVendor::Custom(*IMAGE_SECURITY_DATABASE_GUID),
+            ),
+        }
+    }
+
+    /// the read-only variable holding the factory default of the database, such as `dbDefault`.
+    /// Default variables are all in the EFI namespace
+    pub fn default_variable(self) -> Variable {
+        Variable::new(&format!("{}Default", self.name()))
+    }
+
+    /// attributes of an authenticated write to the variable, which may append to the database
+    /// instead of replacing it
+    pub fn write_attributes(self, append: bool) -> VariableFlags {
+        let attributes = VariableFlags::NON_VOLATILE
+            | VariableFlags::BOOTSERVICE_ACCESS
+            | VariableFlags::RUNTIME_ACCESS
+            | VariableFlags::TIME_BASED_AUTHENTICATED_WRITE_ACCESS;
+
+        if append {
+            attributes | VariableFlags::APPEND_WRITE
+        } else {
+            attributes
+        }
+    }
+}
+
+impl Display for SecureBootVariable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for SecureBootVariable {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SecureBootVariable::ALL
+            .iter()
+            .copied()
+            .find(|var| var.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| Error::InvalidVarName { name: s.to_owned() })
+    }
+}
@@ -0,0 +1,479 @@
+//! This module builds and verifies the EFI_VARIABLE_AUTHENTICATION_2 descriptors that prefix
+//! writes to variables with the `TIME_BASED_AUTHENTICATED_WRITE_ACCESS` attribute. See spec, 8.2.2
+
+use std::{convert::TryFrom, str::FromStr};
+
+use cms::{
+    builder::{SignedDataBuilder, SignerInfoBuilder},
+    cert::{CertificateChoices, IssuerAndSerialNumber},
+    content_info::ContentInfo,
+    signed_data::{EncapsulatedContentInfo, SignedData, SignerIdentifier},
+};
+use rsa::{pkcs1v15, signature::Verifier};
+use sha2::{digest::const_oid::AssociatedOid, Digest, Sha256};
+use uuid::Uuid;
+use x509_cert::{
+    der::{
+        asn1::OctetStringRef,
+        oid::db::rfc5911::{ID_DATA, ID_MESSAGE_DIGEST, ID_SIGNED_DATA},
+        Decode, Encode,
+    },
+    spki::AlgorithmIdentifierOwned,
+    Certificate,
+};
+
+use super::{
+    signing_key::certificate_public_key, EfiTime, SecureBootVariable, SignatureDatabase, SigningKey,
+};
+use crate::{
+    boot::ParseCursor,
+    efi::{Variable, VariableFlags},
+    push::PushVecU8,
+    Error, ParseError, VarWriter,
+};
+
+lazy_static::lazy_static! {
+    /// Type of a WIN_CERTIFICATE_UEFI_GUID holding a PKCS#7 SignedData
+    pub static ref EFI_CERT_TYPE_PKCS7_GUID: Uuid =
+        Uuid::from_str("4aafd29d-68df-49ee-8aa9-347d375665a7").unwrap();
+}
+
+/// `wRevision` of WIN_CERTIFICATE
+const WIN_CERT_REVISION: u16 = 0x0200;
+/// `wCertificateType` of WIN_CERTIFICATE_UEFI_GUID
+const WIN_CERT_TYPE_EFI_GUID: u16 = 0x0EF1;
+/// size of the WIN_CERTIFICATE_UEFI_GUID header: length, revision, type and GUID
+const WIN_CERT_HEADER_SIZE: usize = 24;
+
+fn invalid_signature(reason: impl ToString) -> Error {
+    Error::InvalidSignature {
+        reason: reason.to_string(),
+    }
+}
+
+/// The value written to a time-based authenticated variable: a timestamp, a PKCS#7 signature and
+/// the new data of the variable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticatedPayload {
+    /// must be later than the one of the previous write, unless appending
+    pub timestamp: EfiTime,
+    /// DER-encoded PKCS#7 SignedData, without content
+    pub signature: Vec<u8>,
+    pub data: Vec<u8>,
+}
+
+impl AuthenticatedPayload {
+    /// The bytes covered by the signature: the variable name (without null terminator), vendor
+    /// GUID, attributes, timestamp and new data
+    pub fn signed_bytes(
+        variable: &Variable,
+        attributes: VariableFlags,
+        timestamp: &EfiTime,
+        data: &[u8],
+    ) -> Vec<u8> {
+        let mut bytes = vec![];
+        for c in variable.name().encode_utf16() {
+            bytes.push_u16(c);
+        }
+        bytes.push_guid(variable.vendor().as_ref());
+        bytes.push_u32(attributes.bits());
+        bytes.append(&mut timestamp.to_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    /// Sign a write of `data` to `variable`. `attributes` must be the ones the payload will be
+    /// written with. Only the date and time of `timestamp` are kept, as the specification
+    /// requires the other fields to be zero
+    pub fn sign(
+        variable: &Variable,
+        attributes: VariableFlags,
+        timestamp: EfiTime,
+        data: Vec<u8>,
+        signer: &SigningKey,
+    ) -> crate::Result<AuthenticatedPayload> {
+        let timestamp = EfiTime {
+            nanosecond: 0,
+            time_zone: 0,
+            daylight: 0,
+            ..timestamp
+        };
+        let digest = Sha256::digest(Self::signed_bytes(variable, attributes, &timestamp, &data));
+
+        let certificate = signer.certificate();
+        let content = EncapsulatedContentInfo {
+            econtent_type: ID_DATA,
+            econtent: None,
+        };
+        let digest_algorithm = AlgorithmIdentifierOwned {
+            oid: Sha256::OID,
+            parameters: None,
+        };
+        let rsa_signer = pkcs1v15::SigningKey::<Sha256>::new(signer.key().clone());
+
+        let signer_info = SignerInfoBuilder::new(
+            &rsa_signer,
+            SignerIdentifier::IssuerAndSerialNumber(IssuerAndSerialNumber {
+                issuer: certificate.tbs_certificate.issuer.clone(),
+                serial_number: certificate.tbs_certificate.serial_number.clone(),
+            }),
+            digest_algorithm.clone(),
+            &content,
+            Some(&digest),
+        )
+        .map_err(invalid_signature)?;
+
+        let content_info = SignedDataBuilder::new(&content)
+            .add_digest_algorithm(digest_algorithm)
+            .and_then(|builder| {
+                builder.add_certificate(CertificateChoices::Certificate(certificate.clone()))
+            })
+            .and_then(|builder| builder.add_signer_info::<_, pkcs1v15::Signature>(signer_info))
+            .and_then(|builder| builder.build())
+            .map_err(invalid_signature)?;
+
+        Ok(AuthenticatedPayload {
+            timestamp,
+            // the firmware expects the SignedData itself, not wrapped in a ContentInfo
+            signature: content_info.content.to_der().map_err(invalid_signature)?,
+            data,
+        })
+    }
+
+    /// Sign a write of a signature database, replacing the current content of the variable or
+    /// appending to it
+    pub fn sign_database(
+        variable: SecureBootVariable,
+        database: &SignatureDatabase,
+        timestamp: EfiTime,
+        signer: &SigningKey,
+        append: bool,
+    ) -> crate::Result<AuthenticatedPayload> {
+        Self::sign(
+            &variable.variable(),
+            variable.write_attributes(append),
+            timestamp,
+            database.to_bytes()?,
+            signer,
+        )
+    }
+
+    /// Decode a payload, such as the content of a `.auth` file produced by other tools
+    pub fn parse(value: &[u8]) -> crate::Result<AuthenticatedPayload> {
+        let mut buf = ParseCursor::new(value);
+
+        let timestamp = EfiTime::parse(&mut buf)?;
+
+        let offset = buf.offset();
+        let length = buf.read_u32("WIN_CERTIFICATE.dwLength")? as usize;
+        if length < WIN_CERT_HEADER_SIZE {
+            return Err(ParseError::invalid(
+                offset,
+                "WIN_CERTIFICATE.dwLength",
+                format!("{length} is smaller than the certificate header"),
+            )
+            .into());
+        }
+
+        let offset = buf.offset();
+        let revision = buf.read_u16("WIN_CERTIFICATE.wRevision")?;
+        if revision != WIN_CERT_REVISION {
+            return Err(ParseError::invalid(
+                offset,
+                "WIN_CERTIFICATE.wRevision",
+                format!("unsupported revision 0x{revision:04x}"),
+            )
+            .into());
+        }
+
+        let offset = buf.offset();
+        let certificate_type = buf.read_u16("WIN_CERTIFICATE.wCertificateType")?;
+        let guid = buf.read_guid("WIN_CERTIFICATE_UEFI_GUID.CertType")?;
+        if certificate_type != WIN_CERT_TYPE_EFI_GUID || guid != *EFI_CERT_TYPE_PKCS7_GUID {
+            return Err(ParseError::invalid(
+                offset,
+                "WIN_CERTIFICATE.wCertificateType",
+                "not a PKCS#7 certificate",
+            )
+            .into());
+        }
+
+        let signature = buf
+            .read_bytes(
+                length - WIN_CERT_HEADER_SIZE,
+                "WIN_CERTIFICATE_UEFI_GUID.CertData",
+            )?
+            .to_vec();
+
+        Ok(AuthenticatedPayload {
+            timestamp,
+            signature,
+            data: buf.read_rest().to_vec(),
+        })
+    }
+
+    pub fn to_bytes(&self) -> crate::Result<Vec<u8>> {
+        let length = u32::try_from(WIN_CERT_HEADER_SIZE + self.signature.len()).map_err(|_| {
+            Error::ValueTooLarge {
+                what: "authenticated variable signature",
+            }
+        })?;
+
+        let mut bytes = self.timestamp.to_bytes();
+        bytes.push_u32(length);
+        bytes.push_u16(WIN_CERT_REVISION);
+        bytes.push_u16(WIN_CERT_TYPE_EFI_GUID);
+        bytes.push_guid(&EFI_CERT_TYPE_PKCS7_GUID);
+        bytes.extend_from_slice(&self.signature);
+        bytes.extend_from_slice(&self.data);
+        Ok(bytes)
+    }
+
+    /// Check that the payload is signed by `certificate` for a write to `variable` with
+    /// `attributes`. Only SHA-256 RSA signatures are supported
+    pub fn verify(
+        &self,
+        variable: &Variable,
+        attributes: VariableFlags,
+        certificate: &Certificate,
+    ) -> crate::Result<()> {
+        let signed_data = decode_signed_data(&self.signature)?;
+        if signed_data.encap_content_info.econtent.is_some() {
+            return Err(invalid_signature("the signature embeds its content"));
+        }
+
+        let digest = Sha256::digest(Self::signed_bytes(
+            variable,
+            attributes,
+            &self.timestamp,
+            &self.data,
+        ));
+        let verifying_key =
+            pkcs1v15::VerifyingKey::<Sha256>::new(certificate_public_key(certificate)?);
+
+        let tbs = &certificate.tbs_certificate;
+        let mut last_error = invalid_signature("no signer matches the certificate");
+        for signer_info in signed_data.signer_infos.0.iter() {
+            match &signer_info.sid {
+                SignerIdentifier::IssuerAndSerialNumber(sid)
+                    if sid.issuer == tbs.issuer && sid.serial_number == tbs.serial_number => {}
+                _ => continue,
+            }
+
+            if signer_info.digest_alg.oid != Sha256::OID {
+                last_error = invalid_signature(format!(
+                    "unsupported digest algorithm {}",
+                    signer_info.digest_alg.oid
+                ));
+                continue;
+            }
+
+            // with signed attributes, the signature covers them, and they hold the digest
+            let message = match &signer_info.signed_attrs {
+                Some(attributes) => {
+                    let message_digest = attributes
+                        .iter()
+                        .find(|attribute| attribute.oid == ID_MESSAGE_DIGEST)
+                        .and_then(|attribute| attribute.values.iter().next())
+                        .and_then(|value| value.decode_as::<OctetStringRef>().ok());
+                    if message_digest.map(|digest| digest.as_bytes()) != Some(digest.as_slice()) {
+                        last_error = invalid_signature("the digest does not match the data");
+                        continue;
+                    }
+
+                    attributes.to_der().map_err(invalid_signature)?
+                }
+                None => Self::signed_bytes(variable, attributes, &self.timestamp, &self.data),
+            };
+
+            let signature = pkcs1v15::Signature::try_from(signer_info.signature.as_bytes())
+                .map_err(invalid_signature)?;
+            match verifying_key.verify(&message, &signature) {
+                Ok(()) => return Ok(()),
+                Err(err) => last_error = invalid_signature(err),
+            }
+        }
+
+        Err(last_error)
+    }
+
+    /// Write the payload to `variable`. `attributes` must be the signed ones
+    pub fn write(
+        &self,
+        writer: &mut (impl ?Sized + VarWriter),
+        variable: &Variable,
+        attributes: VariableFlags,
+    ) -> crate::Result<()> {
+        writer.write_authenticated(variable, attributes, self)
+    }
+}
+
+/// Decode a SignedData, bare as the specification requires or wrapped in a ContentInfo as some
+/// tools produce it
+fn decode_signed_data(der: &[u8]) -> crate::Result<SignedData> {
+    if let Ok(signed_data) = SignedData::from_der(der) {
+        return Ok(signed_data);
+    }
+
+    let content_info = ContentInfo::from_der(der).map_err(invalid_signature)?;
+    if content_info.content_type != ID_SIGNED_DATA {
+        return Err(invalid_signature("not a PKCS#7 SignedData"));
+    }
+    content_info
+        .content
+        .decode_as::<SignedData>()
+        .map_err(invalid_signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        secureboot::{EfiTime, SecureBootVariable, SignatureDatabase, SignatureType, SigningKey},
+        test_utils::single_signature_database,
+        Error,
+    };
+
+    use super::AuthenticatedPayload;
+
+    const TEST_CERT: &[u8] = include_bytes!("tests/test_cert.pem");
+    const TEST_CERT_DER: &[u8] = include_bytes!("tests/test_cert.der");
+    const TEST_KEY: &[u8] = include_bytes!("tests/test_key.pem");
+    /// `database()` written to `db`, signed by `openssl cms -sign` with the test key
+    const OPENSSL_AUTH: &[u8] = include_bytes!("tests/db_openssl.auth");
+
+    fn timestamp() -> EfiTime {
+        EfiTime {
+            year: 2024,
+            month: 5,
+            day: 17,
+            hour: 12,
+            minute: 30,
+            second: 1,
+            ..Default::default()
+        }
+    }
+
+    fn database() -> SignatureDatabase {
+        single_signature_database(SignatureType::Sha256, &[0x42; 32])
+    }
+
+    #[test]
+    fn sign_verify() {
+        let signer = SigningKey::from_bytes(TEST_CERT, TEST_KEY).unwrap();
+        let db = SecureBootVariable::Db;
+
+        let payload =
+            AuthenticatedPayload::sign_database(db, &database(), timestamp(), &signer, false)
+                .unwrap();
+        payload
+            .verify(
+                &db.variable(),
+                db.write_attributes(false),
+                signer.certificate(),
+            )
+            .unwrap();
+
+        // the signature is bound to the variable, attributes and data
+        assert!(payload
+            .verify(
+                &SecureBootVariable::Kek.variable(),
+                db.write_attributes(false),
+                signer.certificate(),
+            )
+            .is_err());
+        assert!(payload
+            .verify(
+                &db.variable(),
+                db.write_attributes(true),
+                signer.certificate(),
+            )
+            .is_err());
+        let mut tampered = payload.clone();
+        tampered.data[40] ^= 0x01;
+        assert!(matches!(
+            tampered.verify(
+                &db.variable(),
+                db.write_attributes(false),
+                signer.certificate()
+            ),
+            Err(Error::InvalidSignature { .. })
+        ));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let signer = SigningKey::from_bytes(TEST_CERT_DER, TEST_KEY).unwrap();
+        let payload = AuthenticatedPayload::sign(
+            &SecureBootVariable::Pk.variable(),
+            SecureBootVariable::Pk.write_attributes(false),
+            timestamp(),
+            vec![],
+            &signer,
+        )
+        .unwrap();
+
+        let bytes = payload.to_bytes().unwrap();
+        assert_eq!(AuthenticatedPayload::parse(&bytes).unwrap(), payload);
+        assert_eq!(&bytes[..16], &timestamp().to_bytes()[..]);
+        // WIN_CERTIFICATE_UEFI_GUID header
+        assert_eq!(
+            &bytes[16..24],
+            &[
+                (payload.signature.len() + 24) as u8,
+                ((payload.signature.len() + 24) >> 8) as u8,
+                0x00,
+                0x00,
+                0x00,
+                0x02,
+                0xF1,
+                0x0E
+            ]
+        );
+    }
+
+    #[test]
+    fn external_payload() {
+        let signer = SigningKey::from_bytes(TEST_CERT, TEST_KEY).unwrap();
+        let db = SecureBootVariable::Db;
+
+        let payload = AuthenticatedPayload::parse(OPENSSL_AUTH).unwrap();
+        assert_eq!(payload.timestamp, timestamp());
+        assert_eq!(SignatureDatabase::parse(&payload.data).unwrap(), database());
+        assert_eq!(payload.to_bytes().unwrap(), OPENSSL_AUTH);
+
+        payload
+            .verify(
+                &db.variable(),
+                db.write_attributes(false),
+                signer.certificate(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let mut bytes = timestamp().to_bytes();
+        bytes.extend_from_slice(&[0x18, 0x00, 0x00, 0x00, 0x00, 0x01, 0xF1, 0x0E]);
+        bytes.extend_from_slice(&[0x00; 16]);
+        assert!(matches!(
+            AuthenticatedPayload::parse(&bytes),
+            Err(Error::ParseError(_))
+        ));
+
+        assert!(AuthenticatedPayload::parse(&timestamp().to_bytes()).is_err());
+    }
+
+    #[test]
+    fn wrong_key() {
+        let key = rsa::RsaPrivateKey::new(&mut rsa::rand_core::OsRng, 1024).unwrap();
+        let cert = SigningKey::from_bytes(TEST_CERT, TEST_KEY)
+            .unwrap()
+            .certificate()
+            .clone();
+
+        assert!(matches!(
+            SigningKey::new(cert, key),
+            Err(Error::InvalidPrivateKey { .. })
+        ));
+    }
+}
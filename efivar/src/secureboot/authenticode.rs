@@ -0,0 +1,525 @@
+//! This module checks EFI images against the `db` and `dbx` signature databases, the way firmware
+//! does when Secure Boot is enabled: by Authenticode hash, or by a certificate of the chain that
+//! signed the image
+
+use std::{convert::TryFrom, fmt::Display};
+
+use cms::{
+    cert::CertificateChoices,
+    content_info::ContentInfo,
+    signed_data::{SignedData, SignerIdentifier},
+};
+use rsa::{pkcs1v15, signature::Verifier};
+use sha2::{digest::const_oid::AssociatedOid, Digest, Sha256, Sha384, Sha512};
+use uuid::Uuid;
+use x509_cert::{
+    der::{
+        asn1::{OctetString, OctetStringRef},
+        oid::db::{
+            rfc5911::{ID_MESSAGE_DIGEST, ID_SIGNED_DATA},
+            rfc5912::{
+                SHA_256_WITH_RSA_ENCRYPTION, SHA_384_WITH_RSA_ENCRYPTION,
+                SHA_512_WITH_RSA_ENCRYPTION,
+            },
+        },
+        Any, Decode, Encode, Reader, SliceReader, Tag, Tagged,
+    },
+    spki::AlgorithmIdentifierOwned,
+    Certificate,
+};
+
+use super::{
+    pe::PeImage, signing_key::certificate_public_key, EfiTime, SecureBootVariable, Signature,
+    SignatureDatabase,
+};
+use crate::{Error, VarReader};
+
+fn invalid_signature(reason: impl ToString) -> Error {
+    Error::InvalidSignature {
+        reason: reason.to_string(),
+    }
+}
+
+/// An Authenticode signature embedded in an image
+#[derive(Debug)]
+pub struct AuthenticodeSignature {
+    /// certificates embedded in the signature, from the signer to the last issuer found. Empty
+    /// if the signature could not be decoded
+    pub chain: Vec<Certificate>,
+    /// whether the signature is valid and covers the image. Firmware ignores invalid signatures
+    pub status: crate::Result<()>,
+}
+
+/// A signature of a database that matches an image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageMatch {
+    /// the Authenticode hash of the image
+    Hash { owner: Uuid },
+    /// a certificate of the signing chain, or its issuer
+    Certificate {
+        owner: Uuid,
+        certificate: Certificate,
+    },
+    /// the hash of a certificate of the signing chain
+    CertificateHash {
+        owner: Uuid,
+        certificate: Certificate,
+        revocation_time: EfiTime,
+    },
+}
+
+impl Display for ImageMatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageMatch::Hash { owner } => write!(f, "image hash (owner {owner})"),
+            ImageMatch::Certificate { owner, certificate } => write!(
+                f,
+                "certificate {} (owner {owner})",
+                certificate.tbs_certificate.subject
+            ),
+            ImageMatch::CertificateHash {
+                owner,
+                certificate,
+                revocation_time,
+            } => {
+                write!(
+                    f,
+                    "hash of certificate {} (owner {owner})",
+                    certificate.tbs_certificate.subject
+                )?;
+                if !revocation_time.is_zero() {
+                    write!(f, ", revoked since {revocation_time}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Whether the signature databases allow an image to run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageCheck {
+    /// signatures of `db` matching the image
+    pub allowed_by: Vec<ImageMatch>,
+    /// signatures of `dbx` matching the image
+    pub revoked_by: Vec<ImageMatch>,
+}
+
+impl ImageCheck {
+    /// an image runs if `db` allows it and `dbx` does not revoke it
+    pub fn is_allowed(&self) -> bool {
+        !self.allowed_by.is_empty() && self.revoked_by.is_empty()
+    }
+}
+
+/// The Authenticode hash and signatures of an EFI image
+#[derive(Debug)]
+pub struct AuthenticodeImage {
+    /// SHA-256 Authenticode hash, which `db` and `dbx` may list
+    pub hash: [u8; 32],
+    pub signatures: Vec<AuthenticodeSignature>,
+}
+
+/// whether `certificate` was signed by `issuer`. Only RSA signatures are supported
+fn is_issued_by(certificate: &Certificate, issuer: &Certificate) -> bool {
+    if certificate.tbs_certificate.issuer != issuer.tbs_certificate.subject {
+        return false;
+    }
+
+    let (key, tbs, signature) = match (
+        certificate_public_key(issuer),
+        certificate.tbs_certificate.to_der(),
+        pkcs1v15::Signature::try_from(certificate.signature.raw_bytes()),
+    ) {
+        (Ok(key), Ok(tbs), Ok(signature)) => (key, tbs, signature),
+        _ => return false,
+    };
+
+    match certificate.signature_algorithm.oid {
+        SHA_256_WITH_RSA_ENCRYPTION => pkcs1v15::VerifyingKey::<Sha256>::new(key)
+            .verify(&tbs, &signature)
+            .is_ok(),
+        SHA_384_WITH_RSA_ENCRYPTION => pkcs1v15::VerifyingKey::<Sha384>::new(key)
+            .verify(&tbs, &signature)
+            .is_ok(),
+        SHA_512_WITH_RSA_ENCRYPTION => pkcs1v15::VerifyingKey::<Sha512>::new(key)
+            .verify(&tbs, &signature)
+            .is_ok(),
+        _ => false,
+    }
+}
+
+/// decode the digest of the image from a SpcIndirectDataContent
+fn indirect_data_digest(der: &[u8]) -> x509_cert::der::Result<(AlgorithmIdentifierOwned, Vec<u8>)> {
+    let mut reader = SliceReader::new(der)?;
+    let (algorithm, digest) = reader.sequence(|content| {
+        // SpcAttributeTypeAndOptionalValue, describing the image
+        let _data = Any::decode(content)?;
+        content.sequence(|digest_info| {
+            Ok((
+                AlgorithmIdentifierOwned::decode(digest_info)?,
+                OctetString::decode(digest_info)?,
+            ))
+        })
+    })?;
+    reader.finish(())?;
+
+    Ok((algorithm, digest.into_bytes()))
+}
+
+impl AuthenticodeSignature {
+    /// decode and verify the signature of an image with the given hash
+    fn parse(signed_data: &[u8], hash: &[u8; 32]) -> AuthenticodeSignature {
+        let signed_data = match ContentInfo::from_der(signed_data) {
+            Ok(content_info) if content_info.content_type == ID_SIGNED_DATA => {
+                content_info.content.decode_as::<SignedData>()
+            }
+            Ok(_) => {
+                return AuthenticodeSignature {
+                    chain: vec![],
+                    status: Err(invalid_signature("not a PKCS#7 SignedData")),
+                }
+            }
+            Err(err) => Err(err),
+        };
+        let signed_data = match signed_data {
+            Ok(signed_data) => signed_data,
+            Err(err) => {
+                return AuthenticodeSignature {
+                    chain: vec![],
+                    status: Err(invalid_signature(err)),
+                }
+            }
+        };
+
+        let certificates = signed_data
+            .certificates
+            .iter()
+            .flat_map(|certificates| certificates.0.iter())
+            .filter_map(|certificate| match certificate {
+                CertificateChoices::Certificate(certificate) => Some(certificate),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let signer_info = match signed_data.signer_infos.0.iter().next() {
+            Some(signer_info) => signer_info,
+            None => {
+                return AuthenticodeSignature {
+                    chain: vec![],
+                    status: Err(invalid_signature("no signer")),
+                }
+            }
+        };
+        let signer = certificates.iter().find(|certificate| {
+            matches!(&signer_info.sid, SignerIdentifier::IssuerAndSerialNumber(sid)
+                if sid.issuer == certificate.tbs_certificate.issuer
+                    && sid.serial_number == certificate.tbs_certificate.serial_number)
+        });
+        let signer = match signer {
+            Some(signer) => *signer,
+            None => {
+                return AuthenticodeSignature {
+                    chain: vec![],
+                    status: Err(invalid_signature("the signer certificate is missing")),
+                }
+            }
+        };
+
+        // follow the issuers embedded in the signature
+        let mut chain = vec![signer.clone()];
+        while let Some(issuer) = certificates
+            .iter()
+            .find(|issuer| !chain.contains(issuer) && is_issued_by(chain.last().unwrap(), issuer))
+        {
+            chain.push((*issuer).clone());
+        }
+
+        AuthenticodeSignature {
+            status: Self::verify(&signed_data, signer_info, signer, hash),
+            chain,
+        }
+    }
+
+    fn verify(
+        signed_data: &SignedData,
+        signer_info: &cms::signed_data::SignerInfo,
+        signer: &Certificate,
+        hash: &[u8; 32],
+    ) -> crate::Result<()> {
+        let content = signed_data
+            .encap_content_info
+            .econtent
+            .as_ref()
+            .ok_or_else(|| invalid_signature("the signature has no content"))?;
+        // PKCS#7 embeds the SpcIndirectDataContent directly, CMS in an OCTET STRING
+        let indirect_data = if content.tag() == Tag::OctetString {
+            content.value().to_vec()
+        } else {
+            content.to_der().map_err(invalid_signature)?
+        };
+
+        let (algorithm, digest) =
+            indirect_data_digest(&indirect_data).map_err(invalid_signature)?;
+        if algorithm.oid != Sha256::OID || signer_info.digest_alg.oid != Sha256::OID {
+            return Err(invalid_signature("only SHA-256 signatures are supported"));
+        }
+        if digest != hash {
+            return Err(invalid_signature("the signature is for another image"));
+        }
+
+        let attributes = signer_info
+            .signed_attrs
+            .as_ref()
+            .ok_or_else(|| invalid_signature("the signature has no signed attributes"))?;
+        let message_digest = attributes
+            .iter()
+            .find(|attribute| attribute.oid == ID_MESSAGE_DIGEST)
+            .and_then(|attribute| attribute.values.iter().next())
+            .and_then(|value| value.decode_as::<OctetStringRef>().ok());
+        if message_digest.map(|digest| digest.as_bytes())
+            != Some(Sha256::digest(content.value()).as_slice())
+        {
+            return Err(invalid_signature(
+                "the digest does not match the signed content",
+            ));
+        }
+
+        let signature = pkcs1v15::Signature::try_from(signer_info.signature.as_bytes())
+            .map_err(invalid_signature)?;
+        pkcs1v15::VerifyingKey::<Sha256>::new(certificate_public_key(signer)?)
+            .verify(&attributes.to_der().map_err(invalid_signature)?, &signature)
+            .map_err(invalid_signature)
+    }
+}
+
+impl AuthenticodeImage {
+    /// Compute the hash of a PE/COFF image and decode its signatures. Fails only if the image is
+    /// malformed; invalid signatures are reported in [`AuthenticodeSignature::status`]
+    pub fn parse(data: &[u8]) -> crate::Result<AuthenticodeImage> {
+        let image = PeImage::parse(data)?;
+
+        let mut hasher = Sha256::new();
+        for range in image.authenticode_ranges() {
+            hasher.update(&data[range]);
+        }
+        let hash = hasher.finalize().into();
+
+        let signatures = image
+            .signatures()?
+            .into_iter()
+            .map(|signed_data| AuthenticodeSignature::parse(signed_data, &hash))
+            .collect();
+
+        Ok(AuthenticodeImage { hash, signatures })
+    }
+
+    /// certificates of the valid signatures
+    fn signing_certificates(&self) -> impl Iterator<Item = &Certificate> {
+        self.signatures
+            .iter()
+            .filter(|signature| signature.status.is_ok())
+            .flat_map(|signature| signature.chain.iter())
+    }
+
+    /// signatures of the database that match the image
+    pub fn matches(&self, database: &SignatureDatabase) -> Vec<ImageMatch> {
+        let mut matches = vec![];
+
+        for (signature_type, data) in database.signatures() {
+            let owner = data.owner;
+            let signature = match data.decode(signature_type) {
+                Ok(signature) => signature,
+                Err(_) => continue,
+            };
+
+            // hashes of the TBS part of the certificates of the chain
+            let tbs_hash = |hash: &[u8], digest: fn(&[u8]) -> Vec<u8>| {
+                self.signing_certificates().find(|certificate| {
+                    certificate
+                        .tbs_certificate
+                        .to_der()
+                        .is_ok_and(|tbs| digest(&tbs) == hash)
+                })
+            };
+
+            let found = match &signature {
+                Signature::Sha256(hash) if *hash == self.hash => Some(ImageMatch::Hash { owner }),
+                Signature::X509(_) => signature
+                    .certificate()
+                    .and_then(Result::ok)
+                    .filter(|listed| {
+                        self.signing_certificates().any(|certificate| {
+                            certificate == listed || is_issued_by(certificate, listed)
+                        })
+                    })
+                    .map(|certificate| ImageMatch::Certificate { owner, certificate }),
+                Signature::X509Sha256 {
+                    hash,
+                    revocation_time,
+                } => tbs_hash(hash, |tbs| Sha256::digest(tbs).to_vec()).map(|certificate| {
+                    ImageMatch::CertificateHash {
+                        owner,
+                        certificate: certificate.clone(),
+                        revocation_time: *revocation_time,
+                    }
+                }),
+                Signature::X509Sha384 {
+                    hash,
+                    revocation_time,
+                } => tbs_hash(hash, |tbs| Sha384::digest(tbs).to_vec()).map(|certificate| {
+                    ImageMatch::CertificateHash {
+                        owner,
+                        certificate: certificate.clone(),
+                        revocation_time: *revocation_time,
+                    }
+                }),
+                Signature::X509Sha512 {
+                    hash,
+                    revocation_time,
+                } => tbs_hash(hash, |tbs| Sha512::digest(tbs).to_vec()).map(|certificate| {
+                    ImageMatch::CertificateHash {
+                        owner,
+                        certificate: certificate.clone(),
+                        revocation_time: *revocation_time,
+                    }
+                }),
+                _ => None,
+            };
+
+            matches.extend(found);
+        }
+
+        matches
+    }
+
+    pub fn check(&self, db: &SignatureDatabase, dbx: &SignatureDatabase) -> ImageCheck {
+        ImageCheck {
+            allowed_by: self.matches(db),
+            revoked_by: self.matches(dbx),
+        }
+    }
+
+    /// Check the image against the `db` and `dbx` variables. A missing variable is considered
+    /// empty
+    pub fn check_variables(&self, reader: &(impl ?Sized + VarReader)) -> crate::Result<ImageCheck> {
+        let read = |variable| match SignatureDatabase::read(reader, variable) {
+            Err(Error::VarNotFound { .. }) => Ok(SignatureDatabase::default()),
+            res => res,
+        };
+
+        Ok(self.check(
+            &read(SecureBootVariable::Db)?,
+            &read(SecureBootVariable::Dbx)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use x509_cert::{
+        der::{Decode, Encode},
+        Certificate,
+    };
+
+    use crate::{
+        secureboot::{EfiTime, SignatureDatabase, SignatureType},
+        test_utils::{hex, single_signature_database, TEST_CERT},
+    };
+
+    use super::{AuthenticodeImage, ImageMatch};
+
+    /// PE32+ image with a `.text` and a `.sbat` section, signed by `SIGNER_CERT`
+    const SIGNED_IMAGE: &[u8] = include_bytes!("tests/signed.efi");
+    /// issued by the test certificate
+    const SIGNER_CERT: &[u8] = include_bytes!("tests/signer.der");
+    const IMAGE_HASH: &str = "19e3a560b8114718b46888ed1b4123be6c1acc2fd61ef8d0f29fc258928e1a16";
+
+    #[test]
+    fn hash_and_signature() {
+        let image = AuthenticodeImage::parse(SIGNED_IMAGE).unwrap();
+        assert_eq!(hex(&image.hash), IMAGE_HASH);
+
+        assert_eq!(image.signatures.len(), 1);
+        let signature = &image.signatures[0];
+        signature.status.as_ref().unwrap();
+        assert_eq!(
+            signature.chain,
+            vec![Certificate::from_der(SIGNER_CERT).unwrap()]
+        );
+    }
+
+    #[test]
+    fn unsigned() {
+        // the hash does not depend on the signature
+        let mut unsigned = SIGNED_IMAGE.to_vec();
+        let table = AuthenticodeImage::parse(&unsigned).unwrap();
+        assert!(!table.signatures.is_empty());
+        unsigned.truncate(0x608);
+        // remove the certificate table entry
+        unsigned[0x98 + 144..0x98 + 152].fill(0);
+
+        let image = AuthenticodeImage::parse(&unsigned).unwrap();
+        assert_eq!(hex(&image.hash), IMAGE_HASH);
+        assert!(image.signatures.is_empty());
+    }
+
+    #[test]
+    fn tampered() {
+        let mut tampered = SIGNED_IMAGE.to_vec();
+        tampered[0x200] ^= 0xFF;
+
+        let image = AuthenticodeImage::parse(&tampered).unwrap();
+        assert!(image.signatures[0].status.is_err());
+
+        let db = single_signature_database(SignatureType::X509, TEST_CERT);
+        assert!(!image.check(&db, &SignatureDatabase::default()).is_allowed());
+    }
+
+    #[test]
+    fn check() {
+        let image = AuthenticodeImage::parse(SIGNED_IMAGE).unwrap();
+        let empty = SignatureDatabase::default();
+
+        assert!(!image.check(&empty, &empty).is_allowed());
+
+        // allowed by hash, by signer and by issuer
+        for db in [
+            single_signature_database(SignatureType::Sha256, &image.hash),
+            single_signature_database(SignatureType::X509, SIGNER_CERT),
+            single_signature_database(SignatureType::X509, TEST_CERT),
+        ] {
+            let check = image.check(&db, &empty);
+            assert_eq!(check.allowed_by.len(), 1);
+            assert!(check.is_allowed());
+        }
+
+        let db = single_signature_database(SignatureType::X509, TEST_CERT);
+        let signer = Certificate::from_der(SIGNER_CERT).unwrap();
+        let tbs_hash = {
+            use sha2::Digest;
+            sha2::Sha256::digest(signer.tbs_certificate.to_der().unwrap()).to_vec()
+        };
+        let mut revoked_tbs = tbs_hash;
+        revoked_tbs.extend_from_slice(&EfiTime::default().to_bytes());
+
+        // revoked by hash, by certificate and by certificate hash
+        for dbx in [
+            single_signature_database(SignatureType::Sha256, &image.hash),
+            single_signature_database(SignatureType::X509, SIGNER_CERT),
+            single_signature_database(SignatureType::X509Sha256, &revoked_tbs),
+        ] {
+            let check = image.check(&db, &dbx);
+            assert_eq!(check.revoked_by.len(), 1);
+            assert!(!check.is_allowed());
+        }
+
+        // unrelated entries
+        let dbx = single_signature_database(SignatureType::Sha256, &[0x42; 32]);
+        assert!(image.check(&db, &dbx).is_allowed());
+        assert!(matches!(
+            image.check(&db, &dbx).allowed_by[0],
+            ImageMatch::Certificate { .. }
+        ));
+    }
+}
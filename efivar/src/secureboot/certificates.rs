@@ -0,0 +1,270 @@
+//! This module decodes the X.509 certificates enrolled in the signature databases, to find the
+//! ones that expired or are about to
+
+use std::{
+    fmt::Display,
+    time::{Duration, SystemTime},
+};
+
+use sha1::{Digest, Sha1};
+use uuid::Uuid;
+use x509_cert::{
+    der::{oid::db::rfc4519::CN, Encode},
+    name::Name,
+    Certificate,
+};
+
+use super::{SecureBootVariable, SignatureDatabase, SignatureType};
+use crate::{Error, VarReader};
+
+/// Common name of the 2023 CA signing Windows boot managers, replacing the Microsoft Windows
+/// Production PCA 2011
+pub const WINDOWS_UEFI_CA_2023: &str = "Windows UEFI CA 2023";
+/// Common name of the 2023 CA signing third-party images such as shim, replacing the Microsoft
+/// Corporation UEFI CA 2011
+pub const MICROSOFT_UEFI_CA_2023: &str = "Microsoft UEFI CA 2023";
+
+/// Variables whose certificates are inspected
+pub const CERTIFICATE_VARIABLES: [SecureBootVariable; 4] = [
+    SecureBootVariable::Pk,
+    SecureBootVariable::Kek,
+    SecureBootVariable::Db,
+    SecureBootVariable::Dbx,
+];
+
+/// Whether a certificate is valid at a given time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CertificateExpiry {
+    Valid,
+    /// expires within the warning period
+    ExpiringSoon,
+    Expired,
+}
+
+impl CertificateExpiry {
+    pub fn name(self) -> &'static str {
+        match self {
+            CertificateExpiry::Valid => "valid",
+            CertificateExpiry::ExpiringSoon => "expiring soon",
+            CertificateExpiry::Expired => "expired",
+        }
+    }
+}
+
+impl Display for CertificateExpiry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The fields of a certificate that identify it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateInfo {
+    pub subject: String,
+    /// common name of the subject, if it has one
+    pub common_name: Option<String>,
+    pub issuer: String,
+    /// serial number, in hexadecimal
+    pub serial_number: String,
+    pub not_before: SystemTime,
+    pub not_after: SystemTime,
+    /// SHA-1 hash of the certificate, as shown by Windows and OpenSSL
+    pub thumbprint: [u8; 20],
+}
+
+/// the first common name of a distinguished name
+fn common_name(name: &Name) -> Option<String> {
+    name.0
+        .iter()
+        .flat_map(|rdn| rdn.0.iter())
+        .find(|attribute| attribute.oid == CN)
+        .and_then(|attribute| std::str::from_utf8(attribute.value.value()).ok())
+        .map(ToString::to_string)
+}
+
+impl CertificateInfo {
+    pub fn new(certificate: &Certificate) -> crate::Result<CertificateInfo> {
+        let der = certificate
+            .to_der()
+            .map_err(|err| Error::InvalidCertificate {
+                reason: err.to_string(),
+            })?;
+        let tbs = &certificate.tbs_certificate;
+
+        Ok(CertificateInfo {
+            subject: tbs.subject.to_string(),
+            common_name: common_name(&tbs.subject),
+            issuer: tbs.issuer.to_string(),
+            serial_number: tbs
+                .serial_number
+                .as_bytes()
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect(),
+            not_before: tbs.validity.not_before.to_system_time(),
+            not_after: tbs.validity.not_after.to_system_time(),
+            thumbprint: Sha1::digest(der).into(),
+        })
+    }
+
+    /// Whether the certificate expired at `now`, or expires within `warning` of it
+    pub fn expiry(&self, now: SystemTime, warning: Duration) -> CertificateExpiry {
+        if self.not_after <= now {
+            CertificateExpiry::Expired
+        } else if now
+            .checked_add(warning)
+            .is_none_or(|limit| self.not_after <= limit)
+        {
+            CertificateExpiry::ExpiringSoon
+        } else {
+            CertificateExpiry::Valid
+        }
+    }
+}
+
+/// An X.509 signature of a database
+#[derive(Debug)]
+pub struct DatabaseCertificate {
+    pub variable: SecureBootVariable,
+    pub owner: Uuid,
+    /// the decoded certificate, or why it could not be decoded
+    pub info: crate::Result<CertificateInfo>,
+}
+
+impl DatabaseCertificate {
+    /// The X.509 signatures of a database, in order
+    pub fn from_database(
+        variable: SecureBootVariable,
+        database: &SignatureDatabase,
+    ) -> Vec<DatabaseCertificate> {
+        database
+            .signatures()
+            .filter(|(signature_type, _)| *signature_type == SignatureType::X509)
+            .map(|(signature_type, data)| DatabaseCertificate {
+                variable,
+                owner: data.owner,
+                info: data
+                    .decode(signature_type)
+                    .and_then(|signature| CertificateInfo::new(&signature.certificate().unwrap()?)),
+            })
+            .collect()
+    }
+
+    /// The X.509 signatures of `PK`, `KEK`, `db` and `dbx`. Missing variables are skipped
+    pub fn read_all(reader: &(impl ?Sized + VarReader)) -> crate::Result<Vec<DatabaseCertificate>> {
+        let mut certificates = vec![];
+        for variable in CERTIFICATE_VARIABLES.iter().copied() {
+            match SignatureDatabase::read(reader, variable) {
+                Ok(database) => certificates.extend(Self::from_database(variable, &database)),
+                Err(Error::VarNotFound { .. }) => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(certificates)
+    }
+
+    /// Whether a certificate with the given common name is enrolled in `variable`, such as
+    /// [`WINDOWS_UEFI_CA_2023`] in `db`
+    pub fn contains(
+        certificates: &[DatabaseCertificate],
+        variable: SecureBootVariable,
+        name: &str,
+    ) -> bool {
+        certificates.iter().any(|certificate| {
+            certificate.variable == variable
+                && certificate
+                    .info
+                    .as_ref()
+                    .is_ok_and(|info| info.common_name.as_deref() == Some(name))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use crate::{
+        secureboot::{SecureBootVariable, SignatureDatabase, SignatureType},
+        test_utils::{single_signature_database, TEST_CERT},
+    };
+
+    use super::{CertificateExpiry, DatabaseCertificate};
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    #[test]
+    fn decode() {
+        let database = SignatureDatabase {
+            lists: vec![
+                single_signature_database(SignatureType::X509, TEST_CERT),
+                single_signature_database(SignatureType::X509, &[0x30, 0x00]),
+                single_signature_database(SignatureType::Sha256, &[0; 32]),
+            ]
+            .into_iter()
+            .flat_map(|database| database.lists)
+            .collect(),
+        };
+
+        let certificates = DatabaseCertificate::from_database(SecureBootVariable::Db, &database);
+        assert_eq!(certificates.len(), 2);
+        assert!(certificates[1].info.is_err());
+
+        let info = certificates[0].info.as_ref().unwrap();
+        assert_eq!(info.subject, "CN=efivar test key");
+        assert_eq!(info.common_name.as_deref(), Some("efivar test key"));
+        assert_eq!(
+            info.serial_number,
+            "24888c13fbaf00fe5a9c28d1d5363f5ab64b344f"
+        );
+        assert_eq!(
+            info.thumbprint,
+            [
+                0xa8, 0x90, 0x08, 0x51, 0xd4, 0x40, 0x16, 0xb7, 0x35, 0x95, 0xbe, 0x53, 0x54, 0x17,
+                0x82, 0x22, 0xbb, 0x68, 0xa8, 0x75
+            ]
+        );
+
+        assert!(DatabaseCertificate::contains(
+            &certificates,
+            SecureBootVariable::Db,
+            "efivar test key"
+        ));
+        assert!(!DatabaseCertificate::contains(
+            &certificates,
+            SecureBootVariable::Kek,
+            "efivar test key"
+        ));
+    }
+
+    #[test]
+    fn expiry() {
+        let info = DatabaseCertificate::from_database(
+            SecureBootVariable::Db,
+            &single_signature_database(SignatureType::X509, TEST_CERT),
+        )
+        .remove(0)
+        .info
+        .unwrap();
+
+        let warning = 30 * DAY;
+        assert_eq!(
+            info.expiry(SystemTime::UNIX_EPOCH, warning),
+            CertificateExpiry::Valid
+        );
+        assert_eq!(
+            info.expiry(info.not_after - 10 * DAY, warning),
+            CertificateExpiry::ExpiringSoon
+        );
+        assert_eq!(
+            info.expiry(info.not_after, warning),
+            CertificateExpiry::Expired
+        );
+        // a warning period past the representable times does not overflow
+        assert_eq!(
+            info.expiry(SystemTime::UNIX_EPOCH, Duration::MAX),
+            CertificateExpiry::ExpiringSoon
+        );
+    }
+}
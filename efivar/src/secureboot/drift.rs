@@ -0,0 +1,198 @@
+//! This module compares the signature databases with their factory defaults (`PKDefault`,
+//! `KEKDefault`, `dbDefault` and `dbxDefault`), to find custom keys or missing `dbx` updates
+
+use std::collections::BTreeMap;
+
+use uuid::Uuid;
+
+use super::{SecureBootVariable, SignatureData, SignatureDatabase, SignatureType};
+use crate::{Error, VarReader};
+
+/// Databases firmware may provide a default for
+pub const DRIFT_VARIABLES: [SecureBootVariable; 4] = [
+    SecureBootVariable::Pk,
+    SecureBootVariable::Kek,
+    SecureBootVariable::Db,
+    SecureBootVariable::Dbx,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriftChange {
+    /// in the active database only
+    Added,
+    /// in the default database only
+    Removed,
+}
+
+/// A signature that differs between a database and its default
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DriftEntry {
+    pub change: DriftChange,
+    pub signature_type: SignatureType,
+    pub signature: SignatureData,
+}
+
+/// The differences between a database and its default
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatabaseDrift {
+    pub variable: SecureBootVariable,
+    /// removed signatures in the order of the default database, then added ones in the order of
+    /// the active database
+    pub entries: Vec<DriftEntry>,
+}
+
+impl DatabaseDrift {
+    /// Compare the signatures of two databases, regardless of how they are split into lists.
+    /// A signature whose owner changed is both removed and added
+    pub fn compare(
+        variable: SecureBootVariable,
+        default: &SignatureDatabase,
+        active: &SignatureDatabase,
+    ) -> DatabaseDrift {
+        let mut added = active.signatures().collect::<Vec<_>>();
+        let mut entries = vec![];
+
+        for (signature_type, signature) in default.signatures() {
+            match added.iter().position(|(active_type, active)| {
+                *active_type == signature_type && *active == signature
+            }) {
+                Some(index) => {
+                    added.remove(index);
+                }
+                None => entries.push(DriftEntry {
+                    change: DriftChange::Removed,
+                    signature_type,
+                    signature: signature.clone(),
+                }),
+            }
+        }
+
+        entries.extend(
+            added
+                .into_iter()
+                .map(|(signature_type, signature)| DriftEntry {
+                    change: DriftChange::Added,
+                    signature_type,
+                    signature: signature.clone(),
+                }),
+        );
+
+        DatabaseDrift { variable, entries }
+    }
+
+    /// Compare a database with its default. Returns `None` if firmware does not provide a
+    /// default; a missing active database is considered empty
+    pub fn read(
+        reader: &(impl ?Sized + VarReader),
+        variable: SecureBootVariable,
+    ) -> crate::Result<Option<DatabaseDrift>> {
+        let default = match SignatureDatabase::read_default(reader, variable) {
+            Ok(default) => default,
+            Err(Error::VarNotFound { .. }) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let active = match SignatureDatabase::read(reader, variable) {
+            Err(Error::VarNotFound { .. }) => Ok(SignatureDatabase::default()),
+            res => res,
+        }?;
+
+        Ok(Some(DatabaseDrift::compare(variable, &default, &active)))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn count(&self, change: DriftChange) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.change == change)
+            .count()
+    }
+
+    /// the entries, grouped by the owner GUID of their signature
+    pub fn by_owner(&self) -> BTreeMap<Uuid, Vec<&DriftEntry>> {
+        let mut owners = BTreeMap::<_, Vec<_>>::new();
+        for entry in &self.entries {
+            owners.entry(entry.signature.owner).or_default().push(entry);
+        }
+        owners
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::{
+        secureboot::{SecureBootVariable, SignatureData, SignatureType},
+        test_utils::signature_database,
+    };
+
+    use super::{DatabaseDrift, DriftChange};
+
+    fn hash(owner: u128, byte: u8) -> SignatureData {
+        SignatureData {
+            owner: Uuid::from_u128(owner),
+            data: vec![byte; 32],
+        }
+    }
+
+    #[test]
+    fn compare() {
+        let default = signature_database(
+            SignatureType::Sha256,
+            vec![vec![hash(1, 1), hash(1, 2), hash(2, 3)]],
+        );
+
+        // same signatures, split differently
+        let active = signature_database(
+            SignatureType::Sha256,
+            vec![vec![hash(2, 3)], vec![hash(1, 1), hash(1, 2)]],
+        );
+        assert!(DatabaseDrift::compare(SecureBootVariable::Dbx, &default, &active).is_empty());
+
+        let active = signature_database(
+            SignatureType::Sha256,
+            vec![vec![hash(1, 1), hash(3, 2), hash(2, 4), hash(2, 5)]],
+        );
+        let drift = DatabaseDrift::compare(SecureBootVariable::Dbx, &default, &active);
+        assert_eq!(drift.count(DriftChange::Added), 3);
+        assert_eq!(drift.count(DriftChange::Removed), 2);
+        assert_eq!(drift.entries[0].change, DriftChange::Removed);
+        assert_eq!(drift.entries[0].signature, hash(1, 2));
+
+        let owners = drift.by_owner();
+        assert_eq!(
+            owners.keys().copied().collect::<Vec<_>>(),
+            vec![Uuid::from_u128(1), Uuid::from_u128(2), Uuid::from_u128(3)]
+        );
+        assert_eq!(owners[&Uuid::from_u128(2)].len(), 3);
+    }
+
+    #[test]
+    #[cfg(feature = "store")]
+    fn read() {
+        use crate::{efi::VariableFlags, store::MemoryStore, VarWriter};
+
+        let mut store = MemoryStore::new();
+        assert_eq!(
+            DatabaseDrift::read(&store, SecureBootVariable::Db).unwrap(),
+            None
+        );
+
+        store
+            .write(
+                &SecureBootVariable::Db.default_variable(),
+                VariableFlags::BOOTSERVICE_ACCESS | VariableFlags::RUNTIME_ACCESS,
+                &signature_database(SignatureType::Sha256, vec![vec![hash(1, 1)]])
+                    .to_bytes()
+                    .unwrap(),
+            )
+            .unwrap();
+        let drift = DatabaseDrift::read(&store, SecureBootVariable::Db)
+            .unwrap()
+            .unwrap();
+        assert_eq!(drift.count(DriftChange::Removed), 1);
+    }
+}
@@ -0,0 +1,267 @@
+//! This module handles a set of custom Secure Boot keys, stored in a directory, and their
+//! enrollment
+
+use std::{path::Path, str::FromStr};
+
+use rsa::rand_core::{OsRng, RngCore};
+use uuid::Uuid;
+
+use super::{
+    AuthenticatedPayload, EfiTime, SecureBootVariable, SignatureDatabase, SignatureList, SigningKey,
+};
+use crate::{Error, VarWriter};
+
+/// name of the file holding the owner GUID, in the key directory
+const OWNER_FILE: &str = "GUID";
+/// variables a key is generated for
+const KEY_VARIABLES: [SecureBootVariable; 3] = [
+    SecureBootVariable::Pk,
+    SecureBootVariable::Kek,
+    SecureBootVariable::Db,
+];
+
+/// The keys of a platform owner: the Platform Key, a Key Exchange Key signed updates to the
+/// databases are checked against, and a key to sign images with
+#[derive(Debug, Clone)]
+pub struct SecureBootKeys {
+    /// owner GUID of the enrolled signatures
+    pub owner: Uuid,
+    pub pk: SigningKey,
+    pub kek: SigningKey,
+    pub db: SigningKey,
+}
+
+fn io_error(err: std::io::Error) -> Error {
+    Error::UnknownIoError(err)
+}
+
+impl SecureBootKeys {
+    /// Generate new keys and a random owner GUID
+    pub fn generate() -> crate::Result<SecureBootKeys> {
+        let mut owner = [0u8; 16];
+        OsRng.fill_bytes(&mut owner);
+
+        Ok(SecureBootKeys {
+            owner: uuid::Builder::from_random_bytes(owner).into_uuid(),
+            pk: SigningKey::generate("Platform Key")?,
+            kek: SigningKey::generate("Key Exchange Key")?,
+            db: SigningKey::generate("Database Key")?,
+        })
+    }
+
+    /// the key of `PK`, `KEK` or `db`
+    pub fn key(&self, variable: SecureBootVariable) -> Option<&SigningKey> {
+        match variable {
+            SecureBootVariable::Pk => Some(&self.pk),
+            SecureBootVariable::Kek => Some(&self.kek),
+            SecureBootVariable::Db => Some(&self.db),
+            _ => None,
+        }
+    }
+
+    /// Read keys written by [`SecureBootKeys::write`]
+    pub fn read(dir: impl AsRef<Path>) -> crate::Result<SecureBootKeys> {
+        let dir = dir.as_ref();
+
+        let owner = std::fs::read_to_string(dir.join(OWNER_FILE)).map_err(io_error)?;
+        let owner = Uuid::from_str(owner.trim()).map_err(|error| Error::UuidError { error })?;
+
+        let read_key = |variable: SecureBootVariable| {
+            SigningKey::from_files(
+                dir.join(format!("{variable}.pem")),
+                dir.join(format!("{variable}.key")),
+            )
+        };
+
+        Ok(SecureBootKeys {
+            owner,
+            pk: read_key(SecureBootVariable::Pk)?,
+            kek: read_key(SecureBootVariable::Kek)?,
+            db: read_key(SecureBootVariable::Db)?,
+        })
+    }
+
+    /// Write the owner GUID to `GUID`, and each certificate and key to `<variable>.pem` and
+    /// `<variable>.key` (e.g. `KEK.pem`). Fails without writing anything if one of these files
+    /// already exists
+    pub fn write(&self, dir: impl AsRef<Path>) -> crate::Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir).map_err(io_error)?;
+
+        let mut files = vec![dir.join(OWNER_FILE)];
+        for variable in KEY_VARIABLES.iter() {
+            files.push(dir.join(format!("{variable}.pem")));
+            files.push(dir.join(format!("{variable}.key")));
+        }
+        if let Some(existing) = files.iter().find(|file| file.exists()) {
+            return Err(io_error(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("{} already exists", existing.display()),
+            )));
+        }
+
+        std::fs::write(dir.join(OWNER_FILE), format!("{}\n", self.owner)).map_err(io_error)?;
+        for variable in KEY_VARIABLES.iter().copied() {
+            self.key(variable).unwrap().write_files(
+                dir.join(format!("{variable}.pem")),
+                dir.join(format!("{variable}.key")),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Build the signed updates enrolling the certificates of these keys, in the order they
+    /// must be written: `db` (signed by the KEK), `KEK` (signed by the PK) then `PK` (self-signed),
+    /// since writing the PK leaves Setup Mode. `extra_kek` and `extra_db` are enrolled along with
+    /// the certificates, such as the Microsoft ones
+    pub fn signed_updates(
+        &self,
+        timestamp: EfiTime,
+        extra_kek: &[SignatureList],
+        extra_db: &[SignatureList],
+    ) -> crate::Result<Vec<(SecureBootVariable, AuthenticatedPayload)>> {
+        let database = |key: &SigningKey, extra: &[SignatureList]| {
+            let mut lists = vec![SignatureList::x509(self.owner, key.certificate())?];
+            lists.extend_from_slice(extra);
+            Ok::<_, Error>(SignatureDatabase { lists })
+        };
+
+        [
+            (
+                SecureBootVariable::Db,
+                database(&self.db, extra_db)?,
+                &self.kek,
+            ),
+            (
+                SecureBootVariable::Kek,
+                database(&self.kek, extra_kek)?,
+                &self.pk,
+            ),
+            (SecureBootVariable::Pk, database(&self.pk, &[])?, &self.pk),
+        ]
+        .iter()
+        .map(|(variable, database, signer)| {
+            AuthenticatedPayload::sign_database(*variable, database, timestamp, signer, false)
+                .map(|payload| (*variable, payload))
+        })
+        .collect()
+    }
+
+    /// Write updates built by [`SecureBootKeys::signed_updates`], stopping at the first failure
+    pub fn enroll(
+        writer: &mut (impl ?Sized + VarWriter),
+        updates: &[(SecureBootVariable, AuthenticatedPayload)],
+    ) -> crate::Result<()> {
+        for (variable, payload) in updates {
+            payload.write(
+                writer,
+                &variable.variable(),
+                variable.write_attributes(false),
+            )?;
+            log::info!("Enrolled {variable}");
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "store")]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::{
+        secureboot::{
+            EfiTime, SecureBootVariable, SignatureDatabase, SignatureList, SigningKey,
+            MICROSOFT_OWNER_GUID,
+        },
+        store::MemoryStore,
+    };
+
+    use super::SecureBootKeys;
+
+    const TEST_CERT: &[u8] = include_bytes!("tests/test_cert.pem");
+    const TEST_KEY: &[u8] = include_bytes!("tests/test_key.pem");
+    const PK_CERT: &[u8] = include_bytes!("tests/pk_cert.pem");
+    const PK_KEY: &[u8] = include_bytes!("tests/pk_key.pem");
+    const KEK_CERT: &[u8] = include_bytes!("tests/kek_cert.pem");
+    const KEK_KEY: &[u8] = include_bytes!("tests/kek_key.pem");
+
+    /// keys with a distinct key for each variable, so that the signer of each update is checked
+    fn keys() -> SecureBootKeys {
+        SecureBootKeys {
+            owner: Uuid::from_u128(0x1234),
+            pk: SigningKey::from_bytes(PK_CERT, PK_KEY).unwrap(),
+            kek: SigningKey::from_bytes(KEK_CERT, KEK_KEY).unwrap(),
+            db: SigningKey::from_bytes(TEST_CERT, TEST_KEY).unwrap(),
+        }
+    }
+
+    #[test]
+    fn write_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let keys = keys();
+
+        keys.write(dir.path()).unwrap();
+        let read = SecureBootKeys::read(dir.path()).unwrap();
+        assert_eq!(read.owner, keys.owner);
+        assert_eq!(read.kek.certificate(), keys.kek.certificate());
+
+        // does not overwrite existing keys
+        assert!(keys.write(dir.path()).is_err());
+    }
+
+    #[test]
+    fn enroll() {
+        let keys = keys();
+        let extra_db = SignatureList::x509(*MICROSOFT_OWNER_GUID, keys.db.certificate()).unwrap();
+
+        let updates = keys
+            .signed_updates(EfiTime::now(), &[], std::slice::from_ref(&extra_db))
+            .unwrap();
+        assert_eq!(
+            updates
+                .iter()
+                .map(|(variable, _)| *variable)
+                .collect::<Vec<_>>(),
+            vec![
+                SecureBootVariable::Db,
+                SecureBootVariable::Kek,
+                SecureBootVariable::Pk
+            ]
+        );
+        // db is signed by the KEK, KEK and PK by the PK
+        for (variable, payload) in &updates {
+            let (signer, other) = match variable {
+                SecureBootVariable::Db => (&keys.kek, &keys.pk),
+                _ => (&keys.pk, &keys.kek),
+            };
+            let verify = |key: &SigningKey| {
+                payload.verify(
+                    &variable.variable(),
+                    variable.write_attributes(false),
+                    key.certificate(),
+                )
+            };
+            verify(signer).unwrap();
+            assert!(verify(other).is_err());
+            assert!(verify(&keys.db).is_err());
+        }
+
+        let mut store = MemoryStore::new();
+        SecureBootKeys::enroll(&mut store, &updates).unwrap();
+
+        let db = SignatureDatabase::read(&store, SecureBootVariable::Db).unwrap();
+        assert_eq!(db.lists.len(), 2);
+        assert_eq!(db.lists[0].signatures[0].owner, keys.owner);
+        assert_eq!(db.lists[1], extra_db);
+        assert_eq!(
+            SignatureDatabase::read(&store, SecureBootVariable::Pk)
+                .unwrap()
+                .lists
+                .len(),
+            1
+        );
+    }
+}
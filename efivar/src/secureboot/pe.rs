@@ -0,0 +1,270 @@
+//! This module contains a minimal parser for the PE/COFF images run by firmware, giving access to
+//! the parts Secure Boot checks: sections and the Authenticode certificate table
+
+use std::ops::Range;
+
+use crate::{boot::ParseCursor, ParseError};
+
+/// `wCertificateType` of a WIN_CERTIFICATE holding an Authenticode PKCS#7 SignedData
+const WIN_CERT_TYPE_PKCS_SIGNED_DATA: u16 = 0x0002;
+/// index of the certificate table in the data directories
+const CERTIFICATE_TABLE_INDEX: usize = 4;
+
+const PE32_MAGIC: u16 = 0x10B;
+const PE32_PLUS_MAGIC: u16 = 0x20B;
+
+/// A section of a PE image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeSection {
+    /// name, such as `.text`, without padding
+    pub name: String,
+    /// size of the section once loaded, which may be smaller than its raw data
+    pub virtual_size: u32,
+    /// location of the raw data in the file
+    pub raw_data: Range<usize>,
+}
+
+/// A PE/COFF image, such as an EFI application
+#[derive(Debug, Clone)]
+pub struct PeImage<'a> {
+    data: &'a [u8],
+    /// offset of the `CheckSum` field of the optional header
+    checksum_offset: usize,
+    /// offset of the certificate table entry of the data directories, if there is one
+    certificate_entry_offset: Option<usize>,
+    size_of_headers: usize,
+    pub sections: Vec<PeSection>,
+    /// location of the certificate table in the file, empty if the image is not signed
+    pub certificate_table: Range<usize>,
+}
+
+impl<'a> PeImage<'a> {
+    pub fn parse(data: &'a [u8]) -> crate::Result<PeImage<'a>> {
+        let mut buf = ParseCursor::new(data);
+        if buf.read_array::<2>("DOSHeader.e_magic")? != *b"MZ" {
+            return Err(ParseError::invalid(0, "DOSHeader.e_magic", "not a PE image").into());
+        }
+
+        let pe_offset = ParseCursor::at(data, 0x3C, "DOSHeader")?.read_u32("DOSHeader.e_lfanew")?;
+        let mut buf = ParseCursor::at(data, pe_offset as usize, "DOSHeader")?;
+        if buf.read_array::<4>("PE.signature")? != *b"PE\0\0" {
+            return Err(
+                ParseError::invalid(pe_offset as usize, "PE.signature", "not a PE image").into(),
+            );
+        }
+
+        let _machine = buf.read_u16("COFFHeader.machine")?;
+        let number_of_sections = buf.read_u16("COFFHeader.number_of_sections")?;
+        buf.read_bytes(12, "COFFHeader")?;
+        let size_of_optional_header = buf.read_u16("COFFHeader.size_of_optional_header")?;
+        let _characteristics = buf.read_u16("COFFHeader.characteristics")?;
+
+        let optional_offset = buf.offset();
+        let mut optional = buf.read_cursor(size_of_optional_header as usize, "OptionalHeader")?;
+        let magic = optional.read_u16("OptionalHeader.magic")?;
+        // fields before the data directories differ by the size of ImageBase and of the stack
+        // and heap sizes
+        let data_directories_offset = match magic {
+            PE32_MAGIC => 96,
+            PE32_PLUS_MAGIC => 112,
+            _ => {
+                return Err(ParseError::invalid(
+                    optional_offset,
+                    "OptionalHeader.magic",
+                    format!("unknown magic 0x{magic:04x}"),
+                )
+                .into())
+            }
+        };
+
+        optional.read_bytes(58, "OptionalHeader")?;
+        let size_of_headers = optional.read_u32("OptionalHeader.size_of_headers")? as usize;
+        let checksum_offset = optional.offset();
+        optional.read_bytes(data_directories_offset - 68, "OptionalHeader")?;
+        let number_of_rva_and_sizes =
+            optional.read_u32("OptionalHeader.number_of_rva_and_sizes")? as usize;
+
+        let mut certificate_entry_offset = None;
+        let mut certificate_table = 0..0;
+        if number_of_rva_and_sizes > CERTIFICATE_TABLE_INDEX {
+            optional.read_bytes(
+                CERTIFICATE_TABLE_INDEX * 8,
+                "OptionalHeader.data_directories",
+            )?;
+            let entry_offset = optional.offset();
+            certificate_entry_offset = Some(entry_offset);
+
+            let address = optional.read_u32("CertificateTable.virtual_address")? as usize;
+            let size = optional.read_u32("CertificateTable.size")? as usize;
+            if size != 0 {
+                // unlike other data directories, the address is an offset in the file
+                if address.checked_add(size).is_none_or(|end| end > data.len()) {
+                    return Err(ParseError::invalid(
+                        entry_offset,
+                        "CertificateTable",
+                        "out of the file",
+                    )
+                    .into());
+                }
+                certificate_table = address..address + size;
+            }
+        }
+
+        let mut sections = vec![];
+        for _ in 0..number_of_sections {
+            let name = buf.read_array::<8>("SectionHeader.name")?;
+            let virtual_size = buf.read_u32("SectionHeader.virtual_size")?;
+            let _virtual_address = buf.read_u32("SectionHeader.virtual_address")?;
+            let offset = buf.offset();
+            let raw_size = buf.read_u32("SectionHeader.size_of_raw_data")? as usize;
+            let raw_offset = buf.read_u32("SectionHeader.pointer_to_raw_data")? as usize;
+            buf.read_bytes(16, "SectionHeader")?;
+
+            if raw_offset
+                .checked_add(raw_size)
+                .is_none_or(|end| end > data.len())
+            {
+                return Err(ParseError::invalid(
+                    offset,
+                    "SectionHeader.size_of_raw_data",
+                    "section data out of the file",
+                )
+                .into());
+            }
+
+            let name_len = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+            sections.push(PeSection {
+                name: String::from_utf8_lossy(&name[..name_len]).into_owned(),
+                virtual_size,
+                raw_data: raw_offset..raw_offset + raw_size,
+            });
+        }
+
+        if size_of_headers > data.len() || size_of_headers < buf.offset() {
+            return Err(ParseError::invalid(
+                optional_offset,
+                "OptionalHeader.size_of_headers",
+                format!("invalid size of headers {size_of_headers}"),
+            )
+            .into());
+        }
+
+        Ok(PeImage {
+            data,
+            checksum_offset,
+            certificate_entry_offset,
+            size_of_headers,
+            sections,
+            certificate_table,
+        })
+    }
+
+    /// the loaded content of a section, without the padding of the raw data
+    pub fn section_data(&self, name: &str) -> Option<&'a [u8]> {
+        let section = self.sections.iter().find(|section| section.name == name)?;
+        let data = &self.data[section.raw_data.clone()];
+        Some(&data[..data.len().min(section.virtual_size as usize)])
+    }
+
+    /// the parts of the file covered by the Authenticode hash, as computed by firmware: headers
+    /// without the checksum and the certificate table entry, sections by file order, then the
+    /// data after them except the certificate table
+    pub fn authenticode_ranges(&self) -> Vec<Range<usize>> {
+        let mut ranges = match self.certificate_entry_offset {
+            Some(entry) => vec![
+                0..self.checksum_offset,
+                self.checksum_offset + 4..entry,
+                entry + 8..self.size_of_headers,
+            ],
+            None => vec![
+                0..self.checksum_offset,
+                self.checksum_offset + 4..self.size_of_headers,
+            ],
+        };
+
+        let mut sections = self
+            .sections
+            .iter()
+            .filter(|section| !section.raw_data.is_empty())
+            .collect::<Vec<_>>();
+        sections.sort_by_key(|section| section.raw_data.start);
+
+        let mut hashed = self.size_of_headers;
+        for section in sections {
+            ranges.push(section.raw_data.clone());
+            hashed += section.raw_data.len();
+        }
+
+        let end = self.data.len() - self.certificate_table.len();
+        if end > hashed {
+            ranges.push(hashed..end);
+        }
+
+        ranges
+    }
+
+    /// the PKCS#7 SignedData of each Authenticode signature of the certificate table
+    pub fn signatures(&self) -> crate::Result<Vec<&'a [u8]>> {
+        let mut buf = ParseCursor::new(&self.data[..self.certificate_table.end]);
+        buf.read_bytes(self.certificate_table.start, "CertificateTable")?;
+
+        let mut signatures = vec![];
+        while !buf.is_empty() {
+            let offset = buf.offset();
+            let length = buf.read_u32("WIN_CERTIFICATE.dwLength")? as usize;
+            let _revision = buf.read_u16("WIN_CERTIFICATE.wRevision")?;
+            let certificate_type = buf.read_u16("WIN_CERTIFICATE.wCertificateType")?;
+            if length < 8 {
+                return Err(ParseError::invalid(
+                    offset,
+                    "WIN_CERTIFICATE.dwLength",
+                    format!("{length} is smaller than the certificate header"),
+                )
+                .into());
+            }
+            let certificate = buf.read_bytes(length - 8, "WIN_CERTIFICATE.bCertificate")?;
+
+            if certificate_type == WIN_CERT_TYPE_PKCS_SIGNED_DATA {
+                signatures.push(certificate);
+            }
+
+            // entries are aligned on 8 bytes
+            let padding = (8 - length % 8) % 8;
+            buf.read_bytes(padding.min(buf.remaining().len()), "WIN_CERTIFICATE")?;
+        }
+
+        Ok(signatures)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PeImage;
+
+    const SIGNED_IMAGE: &[u8] = include_bytes!("tests/signed.efi");
+
+    #[test]
+    fn parse() {
+        let image = PeImage::parse(SIGNED_IMAGE).unwrap();
+
+        assert_eq!(
+            image
+                .sections
+                .iter()
+                .map(|section| section.name.as_str())
+                .collect::<Vec<_>>(),
+            vec![".sbat", ".text"]
+        );
+        assert!(image
+            .section_data(".sbat")
+            .unwrap()
+            .starts_with(b"sbat,1,SBAT Version"));
+        assert!(image.section_data(".data").is_none());
+
+        assert_eq!(image.certificate_table, 0x608..SIGNED_IMAGE.len());
+        assert_eq!(image.signatures().unwrap().len(), 1);
+
+        assert!(PeImage::parse(b"MZ").is_err());
+        assert!(PeImage::parse(&SIGNED_IMAGE[..0x100]).is_err());
+    }
+}
@@ -0,0 +1,556 @@
+//! This module contains parsing code for signature databases (lists of EFI_SIGNATURE_LIST)
+
+use std::{convert::TryFrom, fmt::Display, str::FromStr};
+
+use uuid::Uuid;
+use x509_cert::{
+    der::{Decode, Encode},
+    Certificate,
+};
+
+use super::{EfiTime, SecureBootVariable};
+use crate::{boot::ParseCursor, push::PushVecU8, Error, ParseError, VarReader};
+
+/// size of the EFI_SIGNATURE_LIST header, before the signature header
+const LIST_HEADER_SIZE: usize = 28;
+/// size of the owner GUID at the start of each EFI_SIGNATURE_DATA
+const OWNER_SIZE: usize = 16;
+
+/// Kind of the signatures of a [`SignatureList`], identified by a GUID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignatureType {
+    /// SHA-256 hash of an image
+    Sha256,
+    /// RSA-2048 public key modulus
+    Rsa2048,
+    /// DER-encoded X.509 certificate
+    X509,
+    /// SHA-256 hash of the TBS part of a certificate, with a revocation time
+    X509Sha256,
+    /// SHA-384 hash of the TBS part of a certificate, with a revocation time
+    X509Sha384,
+    /// SHA-512 hash of the TBS part of a certificate, with a revocation time
+    X509Sha512,
+    Unknown(Uuid),
+}
+
+impl SignatureType {
+    const KNOWN: [SignatureType; 6] = [
+        SignatureType::Sha256,
+        SignatureType::Rsa2048,
+        SignatureType::X509,
+        SignatureType::X509Sha256,
+        SignatureType::X509Sha384,
+        SignatureType::X509Sha512,
+    ];
+
+    pub fn from_guid(guid: Uuid) -> SignatureType {
+        SignatureType::KNOWN
+            .iter()
+            .copied()
+            .find(|signature_type| signature_type.guid() == guid)
+            .unwrap_or(SignatureType::Unknown(guid))
+    }
+
+    pub fn guid(&self) -> Uuid {
+        let guid = match self {
+            SignatureType::Sha256 => "c1c41626-504c-4092-aca9-41f936934328",
+            SignatureType::Rsa2048 => "3c5766e8-269c-4e34-aa14-ed776e85b3b6",
+            SignatureType::X509 => "a5c059a1-94e4-4aa7-87b5-ab155c2bf072",
+            SignatureType::X509Sha256 => "3bd2a492-96c0-4079-b420-fcf98ef103ed",
+            SignatureType::X509Sha384 => "7076876e-80c2-4ee6-aad2-28b349a6865b",
+            SignatureType::X509Sha512 => "446dbf63-2502-4cda-bcfa-2465d2b0fe9d",
+            SignatureType::Unknown(guid) => return *guid,
+        };
+        Uuid::from_str(guid).unwrap()
+    }
+
+    /// size of each signature, not counting the owner GUID. None if it varies (X.509
+    /// certificates) or is unknown
+    pub fn data_size(&self) -> Option<usize> {
+        match self {
+            SignatureType::Sha256 => Some(32),
+            SignatureType::Rsa2048 => Some(256),
+            SignatureType::X509Sha256 => Some(32 + EfiTime::SIZE),
+            SignatureType::X509Sha384 => Some(48 + EfiTime::SIZE),
+            SignatureType::X509Sha512 => Some(64 + EfiTime::SIZE),
+            SignatureType::X509 | SignatureType::Unknown(_) => None,
+        }
+    }
+}
+
+impl Display for SignatureType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignatureType::Sha256 => f.write_str("SHA256"),
+            SignatureType::Rsa2048 => f.write_str("RSA2048"),
+            SignatureType::X509 => f.write_str("X509"),
+            SignatureType::X509Sha256 => f.write_str("X509_SHA256"),
+            SignatureType::X509Sha384 => f.write_str("X509_SHA384"),
+            SignatureType::X509Sha512 => f.write_str("X509_SHA512"),
+            SignatureType::Unknown(guid) => write!(f, "Unknown({guid})"),
+        }
+    }
+}
+
+/// A signature, decoded according to the type of its list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Signature {
+    Sha256([u8; 32]),
+    Rsa2048(Vec<u8>),
+    /// DER-encoded certificate
+    X509(Vec<u8>),
+    X509Sha256 {
+        hash: [u8; 32],
+        revocation_time: EfiTime,
+    },
+    X509Sha384 {
+        hash: [u8; 48],
+        revocation_time: EfiTime,
+    },
+    X509Sha512 {
+        hash: [u8; 64],
+        revocation_time: EfiTime,
+    },
+    Unknown(Vec<u8>),
+}
+
+impl Signature {
+    /// decode the certificate of an X.509 signature
+    pub fn certificate(&self) -> Option<crate::Result<Certificate>> {
+        match self {
+            Signature::X509(der) => {
+                Some(
+                    Certificate::from_der(der).map_err(|err| Error::InvalidCertificate {
+                        reason: err.to_string(),
+                    }),
+                )
+            }
+            _ => None,
+        }
+    }
+}
+
+/// An EFI_SIGNATURE_DATA: a signature and the GUID of the agent that added it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureData {
+    pub owner: Uuid,
+    pub data: Vec<u8>,
+}
+
+impl SignatureData {
+    /// decode the signature according to the type of its list
+    pub fn decode(&self, signature_type: SignatureType) -> crate::Result<Signature> {
+        let mut buf = ParseCursor::new(&self.data);
+
+        let signature = match signature_type {
+            SignatureType::Sha256 => Signature::Sha256(buf.read_array("SignatureData.hash")?),
+            SignatureType::Rsa2048 => {
+                Signature::Rsa2048(buf.read_bytes(256, "SignatureData.modulus")?.to_vec())
+            }
+            SignatureType::X509 => Signature::X509(buf.read_rest().to_vec()),
+            SignatureType::X509Sha256 => Signature::X509Sha256 {
+                hash: buf.read_array("SignatureData.hash")?,
+                revocation_time: EfiTime::parse(&mut buf)?,
+            },
+            SignatureType::X509Sha384 => Signature::X509Sha384 {
+                hash: buf.read_array("SignatureData.hash")?,
+                revocation_time: EfiTime::parse(&mut buf)?,
+            },
+            SignatureType::X509Sha512 => Signature::X509Sha512 {
+                hash: buf.read_array("SignatureData.hash")?,
+                revocation_time: EfiTime::parse(&mut buf)?,
+            },
+            SignatureType::Unknown(_) => Signature::Unknown(buf.read_rest().to_vec()),
+        };
+
+        if !buf.is_empty() {
+            return Err(ParseError::invalid(
+                buf.offset(),
+                "SignatureData.data",
+                format!("{} unexpected trailing bytes", buf.remaining().len()),
+            )
+            .into());
+        }
+
+        Ok(signature)
+    }
+}
+
+/// An EFI_SIGNATURE_LIST: signatures of the same type and size
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureList {
+    pub signature_type: SignatureType,
+    /// type-specific header. Empty for all types defined by the specification
+    pub header: Vec<u8>,
+    /// size of each EFI_SIGNATURE_DATA, including the owner GUID
+    pub signature_size: u32,
+    pub signatures: Vec<SignatureData>,
+}
+
+impl SignatureList {
+    /// build a list of signatures, which must all have the same size
+    pub fn new(
+        signature_type: SignatureType,
+        signatures: Vec<SignatureData>,
+    ) -> crate::Result<SignatureList> {
+        let data_size = signatures
+            .first()
+            .map(|signature| signature.data.len())
+            .or_else(|| signature_type.data_size())
+            .unwrap_or(0);
+
+        if let Some(signature) = signatures
+            .iter()
+            .find(|signature| signature.data.len() != data_size)
+        {
+            return Err(Error::SignatureSizeMismatch {
+                expected: OWNER_SIZE + data_size,
+                actual: OWNER_SIZE + signature.data.len(),
+            });
+        }
+
+        Ok(SignatureList {
+            signature_type,
+            header: vec![],
+            signature_size: u32::try_from(OWNER_SIZE + data_size)
+                .map_err(|_| Error::ValueTooLarge { what: "signature" })?,
+            signatures,
+        })
+    }
+
+    /// build a list holding a single certificate
+    pub fn x509(owner: Uuid, certificate: &Certificate) -> crate::Result<SignatureList> {
+        let data = certificate
+            .to_der()
+            .map_err(|err| Error::InvalidCertificate {
+                reason: err.to_string(),
+            })?;
+
+        SignatureList::new(SignatureType::X509, vec![SignatureData { owner, data }])
+    }
+
+    pub fn parse(buf: &mut ParseCursor) -> crate::Result<SignatureList> {
+        let signature_type =
+            SignatureType::from_guid(buf.read_guid("SignatureList.signature_type")?);
+        let list_size_offset = buf.offset();
+        let list_size = buf.read_u32("SignatureList.list_size")? as usize;
+        let header_size = buf.read_u32("SignatureList.header_size")? as usize;
+        let signature_size = buf.read_u32("SignatureList.signature_size")?;
+
+        if list_size < LIST_HEADER_SIZE {
+            return Err(ParseError::invalid(
+                list_size_offset,
+                "SignatureList.list_size",
+                format!("list size {list_size} is smaller than its header"),
+            )
+            .into());
+        }
+
+        let mut list = buf.read_cursor(list_size - LIST_HEADER_SIZE, "SignatureList.signatures")?;
+        let header = list
+            .read_bytes(header_size, "SignatureList.header")?
+            .to_vec();
+
+        if !list.is_empty()
+            && ((signature_size as usize) < OWNER_SIZE
+                || !list
+                    .remaining()
+                    .len()
+                    .is_multiple_of(signature_size as usize))
+        {
+            return Err(ParseError::invalid(
+                list.offset(),
+                "SignatureList.signatures",
+                format!(
+                    "{} bytes of signatures are not a multiple of the signature size {signature_size}",
+                    list.remaining().len()
+                ),
+            )
+            .into());
+        }
+
+        let mut signatures = vec![];
+        while !list.is_empty() {
+            signatures.push(SignatureData {
+                owner: list.read_guid("SignatureData.owner")?,
+                data: list
+                    .read_bytes(signature_size as usize - OWNER_SIZE, "SignatureData.data")?
+                    .to_vec(),
+            });
+        }
+
+        Ok(SignatureList {
+            signature_type,
+            header,
+            signature_size,
+            signatures,
+        })
+    }
+
+    pub fn to_bytes(&self) -> crate::Result<Vec<u8>> {
+        let list_size = LIST_HEADER_SIZE
+            + self.header.len()
+            + self.signatures.len() * self.signature_size as usize;
+
+        let mut bytes = vec![];
+        bytes.push_guid(&self.signature_type.guid());
+        bytes.push_u32(u32::try_from(list_size).map_err(|_| Error::ValueTooLarge {
+            what: "signature list",
+        })?);
+        bytes.push_u32(self.header.len() as u32);
+        bytes.push_u32(self.signature_size);
+        bytes.extend_from_slice(&self.header);
+
+        for signature in &self.signatures {
+            if OWNER_SIZE + signature.data.len() != self.signature_size as usize {
+                return Err(Error::SignatureSizeMismatch {
+                    expected: self.signature_size as usize,
+                    actual: OWNER_SIZE + signature.data.len(),
+                });
+            }
+            bytes.push_guid(&signature.owner);
+            bytes.extend_from_slice(&signature.data);
+        }
+
+        Ok(bytes)
+    }
+}
+
+/// The content of a signature database variable, such as `db`: a sequence of signature lists
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SignatureDatabase {
+    pub lists: Vec<SignatureList>,
+}
+
+impl SignatureDatabase {
+    pub fn read(
+        manager: &(impl ?Sized + VarReader),
+        variable: SecureBootVariable,
+    ) -> crate::Result<Self> {
+        let variable = variable.variable();
+        let (value, _flags) = manager.read(&variable)?;
+        Self::parse(&value).map_err(|err| err.with_variable(&variable))
+    }
+
+    /// read the factory default of a database, such as `dbDefault`
+    pub fn read_default(
+        manager: &(impl ?Sized + VarReader),
+        variable: SecureBootVariable,
+    ) -> crate::Result<Self> {
+        let variable = variable.default_variable();
+        let (value, _flags) = manager.read(&variable)?;
+        Self::parse(&value).map_err(|err| err.with_variable(&variable))
+    }
+
+    pub fn parse(value: &[u8]) -> crate::Result<Self> {
+        let mut buf = ParseCursor::new(value);
+
+        let mut lists = vec![];
+        while !buf.is_empty() {
+            lists.push(SignatureList::parse(&mut buf)?);
+        }
+
+        Ok(SignatureDatabase { lists })
+    }
+
+    pub fn to_bytes(&self) -> crate::Result<Vec<u8>> {
+        let mut bytes = vec![];
+        for list in &self.lists {
+            bytes.append(&mut list.to_bytes()?);
+        }
+        Ok(bytes)
+    }
+
+    /// loop over all signatures, along with their type
+    pub fn signatures(&self) -> impl Iterator<Item = (SignatureType, &SignatureData)> {
+        self.lists.iter().flat_map(|list| {
+            list.signatures
+                .iter()
+                .map(move |signature| (list.signature_type, signature))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::TryInto, str::FromStr};
+
+    use uuid::Uuid;
+
+    use super::{Signature, SignatureData, SignatureDatabase, SignatureList, SignatureType};
+    use crate::{secureboot::EfiTime, test_utils::TEST_CERT, Error};
+
+    fn owner() -> Uuid {
+        Uuid::from_str("77fa9abd-0359-4d32-bd60-28f4e78f784b").unwrap()
+    }
+
+    fn database() -> SignatureDatabase {
+        SignatureDatabase {
+            lists: vec![
+                SignatureList::new(
+                    SignatureType::X509,
+                    vec![SignatureData {
+                        owner: owner(),
+                        data: TEST_CERT.to_vec(),
+                    }],
+                )
+                .unwrap(),
+                SignatureList::new(
+                    SignatureType::Sha256,
+                    vec![
+                        SignatureData {
+                            owner: owner(),
+                            data: vec![0x11; 32],
+                        },
+                        SignatureData {
+                            owner: Uuid::nil(),
+                            data: vec![0x22; 32],
+                        },
+                    ],
+                )
+                .unwrap(),
+                SignatureList::new(
+                    SignatureType::X509Sha384,
+                    vec![SignatureData {
+                        owner: owner(),
+                        data: [vec![0x33; 48], vec![0x00; 16]].concat(),
+                    }],
+                )
+                .unwrap(),
+            ],
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let bytes = database().to_bytes().unwrap();
+        // X509 list header: type GUID, list size, header size, signature size
+        assert_eq!(
+            &bytes[..16],
+            &[
+                0xA1, 0x59, 0xC0, 0xA5, 0xE4, 0x94, 0xA7, 0x4A, 0x87, 0xB5, 0xAB, 0x15, 0x5C, 0x2B,
+                0xF0, 0x72
+            ]
+        );
+        assert_eq!(
+            u32::from_le_bytes(bytes[16..20].try_into().unwrap()) as usize,
+            28 + 16 + TEST_CERT.len()
+        );
+
+        let parsed = SignatureDatabase::parse(&bytes).unwrap();
+        assert_eq!(parsed, database());
+        assert_eq!(parsed.to_bytes().unwrap(), bytes);
+        assert_eq!(parsed.signatures().count(), 4);
+    }
+
+    #[test]
+    fn roundtrip_unknown() {
+        // unknown type, with a type-specific header
+        let list = SignatureList {
+            signature_type: SignatureType::Unknown(Uuid::from_u128(0x1234)),
+            header: vec![0xAA, 0xBB],
+            signature_size: 20,
+            signatures: vec![SignatureData {
+                owner: owner(),
+                data: vec![0x01, 0x02, 0x03, 0x04],
+            }],
+        };
+        let bytes = list.to_bytes().unwrap();
+        let parsed = SignatureDatabase::parse(&bytes).unwrap();
+        assert_eq!(parsed.lists, vec![list]);
+        assert_eq!(parsed.to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn decode() {
+        let database = database();
+        let mut signatures = database.signatures();
+
+        let (signature_type, data) = signatures.next().unwrap();
+        let certificate = data
+            .decode(signature_type)
+            .unwrap()
+            .certificate()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            certificate.tbs_certificate.subject.to_string(),
+            "CN=efivar test key"
+        );
+
+        let (signature_type, data) = signatures.next().unwrap();
+        assert_eq!(
+            data.decode(signature_type).unwrap(),
+            Signature::Sha256([0x11; 32])
+        );
+
+        let (signature_type, data) = signatures.nth(1).unwrap();
+        assert_eq!(
+            data.decode(signature_type).unwrap(),
+            Signature::X509Sha384 {
+                hash: [0x33; 48],
+                revocation_time: EfiTime::default()
+            }
+        );
+
+        // wrong size for the type
+        assert!(SignatureData {
+            owner: owner(),
+            data: vec![0x11; 33]
+        }
+        .decode(SignatureType::Sha256)
+        .is_err());
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let bytes = database().to_bytes().unwrap();
+
+        // truncated
+        assert!(SignatureDatabase::parse(&bytes[..bytes.len() - 1]).is_err());
+
+        // list size too small
+        let mut broken = bytes.clone();
+        broken[16..20].copy_from_slice(&27u32.to_le_bytes());
+        assert!(SignatureDatabase::parse(&broken).is_err());
+
+        // signature size not matching the list size
+        let mut broken = bytes;
+        broken[24..28].copy_from_slice(&100u32.to_le_bytes());
+        assert!(SignatureDatabase::parse(&broken).is_err());
+
+        // signature not matching the list size
+        let mut list = database().lists.remove(1);
+        list.signatures[0].data.pop();
+        assert!(list.to_bytes().is_err());
+    }
+
+    #[test]
+    fn new_size_mismatch() {
+        let signature = |data: Vec<u8>| SignatureData {
+            owner: owner(),
+            data,
+        };
+
+        assert!(matches!(
+            SignatureList::new(
+                SignatureType::X509,
+                vec![signature(TEST_CERT.to_vec()), signature(vec![0x30, 0x00])],
+            ),
+            Err(Error::SignatureSizeMismatch {
+                expected,
+                actual: 18,
+            }) if expected == 16 + TEST_CERT.len()
+        ));
+        assert_eq!(
+            SignatureList::new(
+                SignatureType::Sha256,
+                vec![signature(vec![0; 32]), signature(vec![1; 32])],
+            )
+            .unwrap()
+            .signature_size,
+            48
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! This module contains the key pair used to sign authenticated variable updates
+
+use std::{fs::OpenOptions, io::Write, path::Path, str::FromStr, time::Duration};
+
+use rsa::{
+    pkcs1::DecodeRsaPrivateKey,
+    pkcs1v15,
+    pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey, LineEnding},
+    rand_core::{OsRng, RngCore},
+    RsaPrivateKey, RsaPublicKey,
+};
+use sha2::Sha256;
+use x509_cert::{
+    builder::{Builder, CertificateBuilder, Profile},
+    der::{Decode, DecodePem, Encode, EncodePem},
+    name::Name,
+    serial_number::SerialNumber,
+    spki::SubjectPublicKeyInfoOwned,
+    time::Validity,
+    Certificate,
+};
+
+use crate::Error;
+
+/// An RSA private key along with its X.509 certificate, such as the ones of a `PK` or `KEK`
+#[derive(Debug, Clone)]
+pub struct SigningKey {
+    certificate: Certificate,
+    key: RsaPrivateKey,
+}
+
+fn is_pem(data: &[u8]) -> bool {
+    data.trim_ascii_start().starts_with(b"-----BEGIN")
+}
+
+fn invalid_key(err: impl ToString) -> Error {
+    Error::InvalidPrivateKey {
+        reason: err.to_string(),
+    }
+}
+
+fn invalid_certificate(err: impl ToString) -> Error {
+    Error::InvalidCertificate {
+        reason: err.to_string(),
+    }
+}
+
+/// decode a certificate, in PEM or DER format
+pub fn parse_certificate(data: &[u8]) -> crate::Result<Certificate> {
+    if is_pem(data) {
+        Certificate::from_pem(data).map_err(invalid_certificate)
+    } else {
+        Certificate::from_der(data).map_err(invalid_certificate)
+    }
+}
+
+/// decode a PKCS#8 or PKCS#1 private key, in PEM or DER format
+fn parse_private_key(data: &[u8]) -> crate::Result<RsaPrivateKey> {
+    if is_pem(data) {
+        let pem = std::str::from_utf8(data).map_err(invalid_key)?;
+        RsaPrivateKey::from_pkcs8_pem(pem)
+            .or_else(|_| RsaPrivateKey::from_pkcs1_pem(pem))
+            .map_err(invalid_key)
+    } else {
+        RsaPrivateKey::from_pkcs8_der(data)
+            .or_else(|_| RsaPrivateKey::from_pkcs1_der(data))
+            .map_err(invalid_key)
+    }
+}
+
+/// the RSA public key of a certificate
+pub(crate) fn certificate_public_key(certificate: &Certificate) -> crate::Result<RsaPublicKey> {
+    let spki = certificate
+        .tbs_certificate
+        .subject_public_key_info
+        .to_der()
+        .map_err(invalid_certificate)?;
+
+    RsaPublicKey::from_public_key_der(&spki)
+        .map_err(|err| invalid_certificate(format!("not an RSA public key: {err}")))
+}
+
+/// size of generated keys, the one firmware is required to support
+const GENERATED_KEY_BITS: usize = 2048;
+/// validity of generated certificates. Firmware usually does not check it
+const GENERATED_VALIDITY: Duration = Duration::from_secs(10 * 365 * 24 * 60 * 60);
+
+/// write a file only readable by its owner
+fn write_private_file(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    options.open(path)?.write_all(content)
+}
+
+impl SigningKey {
+    /// Fails if the key is not the one of the certificate
+    pub fn new(certificate: Certificate, key: RsaPrivateKey) -> crate::Result<SigningKey> {
+        if certificate_public_key(&certificate)? != key.to_public_key() {
+            return Err(invalid_key("the key does not match the certificate"));
+        }
+
+        Ok(SigningKey { certificate, key })
+    }
+
+    /// Decode a certificate and its private key, both in either PEM or DER format. The key may
+    /// be in PKCS#8 or PKCS#1 format
+    pub fn from_bytes(certificate: &[u8], key: &[u8]) -> crate::Result<SigningKey> {
+        SigningKey::new(parse_certificate(certificate)?, parse_private_key(key)?)
+    }
+
+    /// Read a certificate and its private key from files. See [`SigningKey::from_bytes`]
+    pub fn from_files(
+        certificate: impl AsRef<Path>,
+        key: impl AsRef<Path>,
+    ) -> crate::Result<SigningKey> {
+        SigningKey::from_bytes(
+            &std::fs::read(certificate).map_err(Error::UnknownIoError)?,
+            &std::fs::read(key).map_err(Error::UnknownIoError)?,
+        )
+    }
+
+    /// Generate an RSA-2048 key and a self-signed certificate for it, with the subject
+    /// `CN=<common_name>`
+    pub fn generate(common_name: &str) -> crate::Result<SigningKey> {
+        let key = RsaPrivateKey::new(&mut OsRng, GENERATED_KEY_BITS).map_err(invalid_key)?;
+
+        let subject = Name::from_str(&format!("CN={common_name}")).map_err(invalid_certificate)?;
+        let spki = key
+            .to_public_key()
+            .to_public_key_der()
+            .map_err(invalid_key)
+            .and_then(|der| {
+                SubjectPublicKeyInfoOwned::from_der(der.as_bytes()).map_err(invalid_key)
+            })?;
+
+        // positive serial number of 16 random bytes
+        let mut serial = [0u8; 16];
+        OsRng.fill_bytes(&mut serial);
+        serial[0] &= 0x7F;
+
+        let signer = pkcs1v15::SigningKey::<Sha256>::new(key.clone());
+        let certificate = CertificateBuilder::new(
+            Profile::Root,
+            SerialNumber::new(&serial).map_err(invalid_certificate)?,
+            Validity::from_now(GENERATED_VALIDITY).map_err(invalid_certificate)?,
+            subject,
+            spki,
+            &signer,
+        )
+        .and_then(|builder| builder.build::<pkcs1v15::Signature>())
+        .map_err(invalid_certificate)?;
+
+        Ok(SigningKey { certificate, key })
+    }
+
+    /// Write the certificate and the private key in PEM format, the key in PKCS#8 format and
+    /// only readable by its owner
+    pub fn write_files(
+        &self,
+        certificate: impl AsRef<Path>,
+        key: impl AsRef<Path>,
+    ) -> crate::Result<()> {
+        let certificate_pem = self
+            .certificate
+            .to_pem(LineEnding::LF)
+            .map_err(invalid_certificate)?;
+        let key_pem = self.key.to_pkcs8_pem(LineEnding::LF).map_err(invalid_key)?;
+
+        std::fs::write(certificate, certificate_pem).map_err(Error::UnknownIoError)?;
+        write_private_file(key.as_ref(), key_pem.as_bytes()).map_err(Error::UnknownIoError)
+    }
+
+    pub fn certificate(&self) -> &Certificate {
+        &self.certificate
+    }
+
+    pub fn key(&self) -> &RsaPrivateKey {
+        &self.key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SigningKey;
+
+    #[test]
+    fn generate_write_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let (cert, key) = (dir.path().join("db.pem"), dir.path().join("db.key"));
+
+        let generated = SigningKey::generate("efivar test db").unwrap();
+        generated.write_files(&cert, &key).unwrap();
+
+        let read = SigningKey::from_files(&cert, &key).unwrap();
+        assert_eq!(read.certificate(), generated.certificate());
+        assert_eq!(read.key(), generated.key());
+        assert_eq!(
+            read.certificate().tbs_certificate.subject.to_string(),
+            "CN=efivar test db"
+        );
+    }
+}
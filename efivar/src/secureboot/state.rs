@@ -0,0 +1,185 @@
+//! This module combines the global variables describing Secure Boot into a single state. See spec,
+//! 32.3
+
+use std::fmt::Display;
+
+use crate::{globals::GlobalVarReader, Error, VarReader};
+
+use super::SignatureType;
+
+/// The Secure Boot mode the platform is in, as defined by the `SetupMode`, `AuditMode` and
+/// `DeployedMode` variables
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SecureBootMode {
+    /// no Platform Key is enrolled, and the signature databases may be freely written
+    Setup,
+    /// a Platform Key is enrolled, and signatures are checked
+    User,
+    /// no Platform Key is enrolled, and verification failures are only logged
+    Audit,
+    /// like user mode, but leaving it requires a platform-specific action
+    Deployed,
+}
+
+impl SecureBootMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            SecureBootMode::Setup => "setup",
+            SecureBootMode::User => "user",
+            SecureBootMode::Audit => "audit",
+            SecureBootMode::Deployed => "deployed",
+        }
+    }
+}
+
+impl Display for SecureBootMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The Secure Boot state of the platform
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecureBootState {
+    /// whether signatures are enforced on this boot
+    pub enabled: bool,
+    pub mode: SecureBootMode,
+    /// whether the platform-shipped keys were modified, if the firmware reports it
+    pub vendor_keys_modified: Option<bool>,
+    /// signature types supported in the signature databases, if the firmware reports them
+    pub signature_support: Option<Vec<SignatureType>>,
+}
+
+/// a missing variable is reported as `None`
+fn optional<T>(res: crate::Result<T>) -> crate::Result<Option<T>> {
+    match res {
+        Ok(value) => Ok(Some(value)),
+        Err(Error::VarNotFound { .. }) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+impl SecureBootState {
+    /// Fails with [`Error::UnsupportedByFirmware`] if the firmware does not implement Secure Boot.
+    /// `AuditMode` and `DeployedMode`, introduced in UEFI 2.5, are considered disabled when missing
+    pub fn read(reader: &(impl ?Sized + VarReader)) -> crate::Result<SecureBootState> {
+        let setup_mode =
+            optional(reader.get_setup_mode())?.ok_or_else(|| Error::UnsupportedByFirmware {
+                what: "Secure Boot".to_owned(),
+            })?;
+        let audit_mode = optional(reader.get_audit_mode())?.unwrap_or(false);
+        let deployed_mode = optional(reader.get_deployed_mode())?.unwrap_or(false);
+
+        let mode = match (setup_mode, audit_mode, deployed_mode) {
+            (true, false, false) => SecureBootMode::Setup,
+            (false, false, false) => SecureBootMode::User,
+            (true, true, false) => SecureBootMode::Audit,
+            (false, false, true) => SecureBootMode::Deployed,
+            _ => {
+                return Err(Error::InvalidSecureBootMode {
+                    setup_mode,
+                    audit_mode,
+                    deployed_mode,
+                })
+            }
+        };
+
+        Ok(SecureBootState {
+            enabled: optional(reader.get_secure_boot())?.unwrap_or(false),
+            mode,
+            vendor_keys_modified: optional(reader.get_vendor_keys())?
+                .map(|vendor_keys| !vendor_keys),
+            signature_support: optional(reader.get_signature_support())?,
+        })
+    }
+
+    /// whether a Platform Key is enrolled, which is the case in user and deployed modes
+    pub fn keys_enrolled(&self) -> bool {
+        matches!(self.mode, SecureBootMode::User | SecureBootMode::Deployed)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "store")]
+mod tests {
+    use crate::{
+        efi::{Variable, VariableFlags},
+        secureboot::SignatureType,
+        store::MemoryStore,
+        Error, VarWriter,
+    };
+
+    use super::{SecureBootMode, SecureBootState};
+
+    fn set(store: &mut MemoryStore, name: &str, value: &[u8]) {
+        store
+            .write(
+                &Variable::new(name),
+                VariableFlags::BOOTSERVICE_ACCESS | VariableFlags::RUNTIME_ACCESS,
+                value,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn modes() {
+        for (setup, audit, deployed, expected) in [
+            (1, 0, 0, SecureBootMode::Setup),
+            (0, 0, 0, SecureBootMode::User),
+            (1, 1, 0, SecureBootMode::Audit),
+            (0, 0, 1, SecureBootMode::Deployed),
+        ] {
+            let mut store = MemoryStore::new();
+            set(&mut store, "SetupMode", &[setup]);
+            set(&mut store, "AuditMode", &[audit]);
+            set(&mut store, "DeployedMode", &[deployed]);
+
+            let state = SecureBootState::read(&store).unwrap();
+            assert_eq!(state.mode, expected);
+            assert_eq!(state.keys_enrolled(), setup == 0);
+        }
+    }
+
+    #[test]
+    fn full_state() {
+        let mut store = MemoryStore::new();
+        set(&mut store, "SecureBoot", &[1]);
+        set(&mut store, "SetupMode", &[0]);
+        set(&mut store, "VendorKeys", &[0]);
+        let mut signature_support = SignatureType::Sha256.guid().to_bytes_le().to_vec();
+        signature_support.extend_from_slice(&SignatureType::X509.guid().to_bytes_le());
+        set(&mut store, "SignatureSupport", &signature_support);
+
+        assert_eq!(
+            SecureBootState::read(&store).unwrap(),
+            SecureBootState {
+                enabled: true,
+                mode: SecureBootMode::User,
+                vendor_keys_modified: Some(true),
+                signature_support: Some(vec![SignatureType::Sha256, SignatureType::X509]),
+            }
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let mut store = MemoryStore::new();
+        assert!(matches!(
+            SecureBootState::read(&store),
+            Err(Error::UnsupportedByFirmware { .. })
+        ));
+
+        set(&mut store, "SetupMode", &[0]);
+        set(&mut store, "AuditMode", &[1]);
+        assert!(matches!(
+            SecureBootState::read(&store),
+            Err(Error::InvalidSecureBootMode { .. })
+        ));
+
+        set(&mut store, "AuditMode", &[2]);
+        assert!(matches!(
+            SecureBootState::read(&store),
+            Err(Error::ParseError(_))
+        ));
+    }
+}
@@ -1,6 +1,9 @@
+#[cfg(feature = "secureboot")]
+mod authenticated;
 mod file;
 mod guid_group;
 mod memory;
+mod ovmf;
 mod store_value;
 mod variable;
 mod vendor_group;
@@ -12,3 +15,4 @@ use self::vendor_group::VendorGroup;
 
 pub use self::file::FileStore;
 pub use self::memory::MemoryStore;
+pub use self::ovmf::{NvramImage, NvramVariable, OvmfStore, VariableStoreFormat};
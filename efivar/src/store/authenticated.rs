@@ -0,0 +1,21 @@
+use crate::efi::VariableFlags;
+use crate::secureboot::AuthenticatedPayload;
+
+/// The value of a variable after a store receives an authenticated write, or None if the write
+/// deletes it
+///
+/// Like firmware, only the data of the payload is stored, after `current` when appending. Its
+/// signature and timestamp are not checked, and a payload without data deletes the variable.
+pub(super) fn authenticated_value(
+    current: Option<Vec<u8>>,
+    attributes: VariableFlags,
+    payload: &AuthenticatedPayload,
+) -> Option<Vec<u8>> {
+    if !attributes.contains(VariableFlags::APPEND_WRITE) {
+        return Some(payload.data.clone()).filter(|data| !data.is_empty());
+    }
+
+    let mut data = current.unwrap_or_default();
+    data.extend_from_slice(&payload.data);
+    Some(data)
+}
@@ -26,7 +26,7 @@ impl VariableStore for MemoryStore {
 mod tests {
     use super::*;
     use crate::efi::{Variable, VariableFlags};
-    use crate::VarWriter;
+    use crate::{VarReader, VarWriter};
 
     #[test]
     fn missing_vendor() {
@@ -68,4 +68,59 @@ mod tests {
         assert_eq!(variable.0, vec![]);
         assert_eq!(variable.1, VariableFlags::empty());
     }
+
+    #[cfg(feature = "secureboot")]
+    #[test]
+    fn authenticated_write() {
+        use crate::secureboot::{AuthenticatedPayload, EfiTime};
+
+        let mut store = MemoryStore::new();
+        let var = Variable::new("PK");
+        let attributes =
+            VariableFlags::NON_VOLATILE | VariableFlags::TIME_BASED_AUTHENTICATED_WRITE_ACCESS;
+        let payload = |data: &[u8]| AuthenticatedPayload {
+            timestamp: EfiTime::default(),
+            signature: vec![0x30, 0x00],
+            data: data.to_vec(),
+        };
+
+        store
+            .write_authenticated(&var, attributes, &payload(&[1, 2]))
+            .unwrap();
+        assert_eq!(store.read(&var).unwrap(), (vec![1, 2], attributes));
+
+        store
+            .write_authenticated(
+                &var,
+                attributes | VariableFlags::APPEND_WRITE,
+                &payload(&[3]),
+            )
+            .unwrap();
+        assert_eq!(store.read(&var).unwrap(), (vec![1, 2, 3], attributes));
+
+        store
+            .write_authenticated(&var, attributes, &payload(&[]))
+            .unwrap();
+        assert!(store.read(&var).is_err());
+    }
+
+    #[test]
+    fn raw_write() {
+        // plain writes store the value as is, such as an exported authenticated variable
+        let mut store = MemoryStore::new();
+        let var = Variable::new("PK");
+        let attributes =
+            VariableFlags::NON_VOLATILE | VariableFlags::TIME_BASED_AUTHENTICATED_WRITE_ACCESS;
+
+        store.write(&var, attributes, &[1, 2, 3]).unwrap();
+        assert_eq!(store.read(&var).unwrap(), (vec![1, 2, 3], attributes));
+
+        store
+            .write(&var, attributes | VariableFlags::APPEND_WRITE, &[4])
+            .unwrap();
+        assert_eq!(
+            store.read(&var).unwrap(),
+            (vec![4], attributes | VariableFlags::APPEND_WRITE)
+        );
+    }
 }
@@ -0,0 +1,447 @@
+mod nvram;
+
+pub use self::nvram::{NvramImage, NvramVariable, VariableStoreFormat};
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "secureboot")]
+use super::authenticated::authenticated_value;
+#[cfg(feature = "secureboot")]
+use crate::efi::EfiTime;
+use crate::efi::{Variable, VariableFlags};
+#[cfg(feature = "secureboot")]
+use crate::secureboot::AuthenticatedPayload;
+use crate::{Error, VarEnumerator, VarManager, VarReader, VarWriter};
+
+/// Implements support for reading and writing EFI variables in the NVRAM image of an edk2
+/// firmware, such as the `OVMF_VARS.fd` file of a virtual machine
+///
+/// Every change is written to the file right away. The variable store is garbage-collected on
+/// each write: deleted variables are dropped, and the rest of the file is kept as is.
+pub struct OvmfStore {
+    filename: PathBuf,
+    image: NvramImage,
+}
+
+fn save_image(filename: &Path, image: &NvramImage) -> crate::Result<()> {
+    let bytes = image.to_bytes()?;
+
+    // replace the file a symlink points to, not the symlink itself
+    let filename = fs::canonicalize(filename).map_err(Error::UnknownIoError)?;
+    let metadata = fs::metadata(&filename).map_err(Error::UnknownIoError)?;
+
+    // write a copy first, so that the image is not corrupted if writing fails
+    let mut temporary = filename.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let res = write_copy(Path::new(&temporary), &bytes, &metadata)
+        .and_then(|()| fs::rename(&temporary, &filename));
+    if res.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+
+    res.map_err(Error::UnknownIoError)
+}
+
+/// write `bytes` to a new file with the permissions and, where allowed, the owner of the image
+fn write_copy(path: &Path, bytes: &[u8], metadata: &fs::Metadata) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(metadata.permissions().mode());
+    }
+
+    let mut file = options.open(path)?;
+    // the mode given on creation is masked by the umask
+    file.set_permissions(metadata.permissions())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        // only privileged users can give a file away, others keep it as their own
+        let _ = std::os::unix::fs::fchown(&file, Some(metadata.uid()), Some(metadata.gid()));
+    }
+
+    file.write_all(bytes)?;
+    file.sync_all()
+}
+
+#[cfg(feature = "secureboot")]
+fn time_key(time: &EfiTime) -> (u16, u8, u8, u8, u8, u8, u32) {
+    (
+        time.year,
+        time.month,
+        time.day,
+        time.hour,
+        time.minute,
+        time.second,
+        time.nanosecond,
+    )
+}
+
+impl OvmfStore {
+    /// Open an NVRAM image
+    ///
+    /// # Arguments
+    ///
+    /// * `filename`: Path to the firmware volume holding the variables
+    pub fn open(filename: PathBuf) -> crate::Result<Self> {
+        let image = NvramImage::parse(fs::read(&filename).map_err(Error::UnknownIoError)?)?;
+
+        Ok(Self { filename, image })
+    }
+
+    /// The parsed image, including the variable metadata of authenticated stores
+    pub fn image(&self) -> &NvramImage {
+        &self.image
+    }
+
+    /// Add or replace a variable, and save the image
+    fn save_variable(&mut self, var: &Variable, variable: NvramVariable) -> crate::Result<()> {
+        // edk2 keeps volatile variables in memory, they never reach the flash
+        if !variable.attributes.contains(VariableFlags::NON_VOLATILE) {
+            return Err(Error::InvalidAttributes {
+                var: var.clone(),
+                expected: VariableFlags::NON_VOLATILE,
+                actual: variable.attributes,
+            });
+        }
+
+        let mut image = self.image.clone();
+        image.set_variable(variable);
+        save_image(&self.filename, &image)?;
+        self.image = image;
+
+        Ok(())
+    }
+}
+
+impl VarEnumerator for OvmfStore {
+    fn get_all_vars<'a>(&'a self) -> crate::Result<Box<dyn Iterator<Item = Variable> + 'a>> {
+        Ok(Box::new(self.image.variables.iter().map(|variable| {
+            Variable::new_with_vendor(&variable.name, variable.vendor)
+        })))
+    }
+}
+
+impl VarReader for OvmfStore {
+    fn read(&self, var: &Variable) -> crate::Result<(Vec<u8>, VariableFlags)> {
+        self.image
+            .variable(var.name(), var.vendor().as_ref())
+            .map(|variable| (variable.data.clone(), variable.attributes))
+            .ok_or_else(|| Error::VarNotFound { var: var.clone() })
+    }
+}
+
+impl VarWriter for OvmfStore {
+    /// Write a value as is. Like firmware, `APPEND_WRITE` appends it to the current value
+    fn write(
+        &mut self,
+        var: &Variable,
+        attributes: VariableFlags,
+        value: &[u8],
+    ) -> crate::Result<()> {
+        let current = self.image.variable(var.name(), var.vendor().as_ref());
+
+        let mut data = match current {
+            Some(current) if attributes.contains(VariableFlags::APPEND_WRITE) => {
+                current.data.clone()
+            }
+            _ => vec![],
+        };
+        data.extend_from_slice(value);
+
+        let variable = NvramVariable {
+            timestamp: current.map(|current| current.timestamp).unwrap_or_default(),
+            ..NvramVariable::new(
+                var.name(),
+                *var.vendor().as_ref(),
+                attributes.difference(VariableFlags::APPEND_WRITE),
+                data,
+            )
+        };
+        self.save_variable(var, variable)
+    }
+
+    fn delete(&mut self, var: &Variable) -> crate::Result<()> {
+        let mut image = self.image.clone();
+        if !image.remove_variable(var.name(), var.vendor().as_ref()) {
+            return Err(Error::VarNotFound { var: var.clone() });
+        }
+        save_image(&self.filename, &image)?;
+        self.image = image;
+
+        Ok(())
+    }
+
+    /// Store the data of the payload, along with its timestamp in authenticated stores
+    #[cfg(feature = "secureboot")]
+    fn write_authenticated(
+        &mut self,
+        var: &Variable,
+        attributes: VariableFlags,
+        payload: &AuthenticatedPayload,
+    ) -> crate::Result<()> {
+        let current = self
+            .image
+            .variable(var.name(), var.vendor().as_ref())
+            .cloned();
+        let data = match authenticated_value(
+            current.as_ref().map(|current| current.data.clone()),
+            attributes,
+            payload,
+        ) {
+            Some(data) => data,
+            None => {
+                return match self.delete(var) {
+                    Err(Error::VarNotFound { .. }) => Ok(()),
+                    res => res,
+                }
+            }
+        };
+
+        // appending keeps the most recent timestamp
+        let timestamp = match current {
+            Some(current)
+                if attributes.contains(VariableFlags::APPEND_WRITE)
+                    && time_key(&current.timestamp) > time_key(&payload.timestamp) =>
+            {
+                current.timestamp
+            }
+            _ => payload.timestamp,
+        };
+
+        let variable = NvramVariable {
+            timestamp,
+            ..NvramVariable::new(
+                var.name(),
+                *var.vendor().as_ref(),
+                attributes.difference(VariableFlags::APPEND_WRITE),
+                data,
+            )
+        };
+        self.save_variable(var, variable)
+    }
+}
+
+impl VarManager for OvmfStore {}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use tempfile::TempDir;
+    use uuid::Uuid;
+
+    use super::nvram::tests::{image, variable, IMAGE_SIZE};
+    use super::{OvmfStore, VariableStoreFormat};
+    use crate::efi::{Variable, VariableFlags};
+    use crate::{Error, VarEnumerator, VarReader, VarWriter};
+
+    fn temp_image(dir: &TempDir, bytes: &[u8]) -> PathBuf {
+        let path = dir.path().join("OVMF_VARS.fd");
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn read_write() {
+        let format = VariableStoreFormat::Normal;
+        let vendor = Uuid::from_u128(0x1234);
+        let dir = tempfile::tempdir().unwrap();
+        let path = temp_image(
+            &dir,
+            &image(
+                format,
+                &[
+                    variable(format, 0x3C, "Old", &vendor, &[1]),
+                    variable(format, 0x3F, "Kept", &vendor, &[1, 2, 3]),
+                ]
+                .concat(),
+            ),
+        );
+
+        let kept = Variable::new_with_vendor("Kept", vendor);
+        let boot_order = Variable::new("BootOrder");
+        {
+            let mut store = OvmfStore::open(path.clone()).unwrap();
+            assert_eq!(
+                store.get_all_vars().unwrap().collect::<Vec<_>>(),
+                vec![kept.clone()]
+            );
+            assert!(matches!(
+                store.read(&Variable::new_with_vendor("Old", vendor)),
+                Err(Error::VarNotFound { .. })
+            ));
+
+            store
+                .write(&boot_order, VariableFlags::NON_VOLATILE, &[1, 0])
+                .unwrap();
+            store
+                .write(
+                    &boot_order,
+                    VariableFlags::NON_VOLATILE | VariableFlags::APPEND_WRITE,
+                    &[2, 0],
+                )
+                .unwrap();
+            store.delete(&kept).unwrap();
+            assert!(matches!(
+                store.delete(&kept),
+                Err(Error::VarNotFound { .. })
+            ));
+        }
+
+        let bytes = fs::read(&path).unwrap();
+        assert_eq!(bytes.len(), IMAGE_SIZE);
+        let store = OvmfStore::open(path).unwrap();
+        assert_eq!(
+            store.read(&boot_order).unwrap(),
+            (vec![1, 0, 2, 0], VariableFlags::NON_VOLATILE)
+        );
+        assert!(!store.exists(&kept).unwrap());
+    }
+
+    #[test]
+    fn volatile() {
+        let original = image(VariableStoreFormat::Normal, &[]);
+        let dir = tempfile::tempdir().unwrap();
+        let path = temp_image(&dir, &original);
+        let mut store = OvmfStore::open(path.clone()).unwrap();
+
+        assert!(matches!(
+            store.write(
+                &Variable::new("BootNext"),
+                VariableFlags::BOOTSERVICE_ACCESS | VariableFlags::RUNTIME_ACCESS,
+                &[1, 0]
+            ),
+            Err(Error::InvalidAttributes { .. })
+        ));
+        assert!(!store.exists(&Variable::new("BootNext")).unwrap());
+        assert_eq!(fs::read(&path).unwrap(), original);
+    }
+
+    #[cfg(feature = "secureboot")]
+    #[test]
+    fn authenticated_write() {
+        use crate::secureboot::{AuthenticatedPayload, EfiTime};
+
+        let format = VariableStoreFormat::Authenticated;
+        let dir = tempfile::tempdir().unwrap();
+        let path = temp_image(&dir, &image(format, &[]));
+        let mut store = OvmfStore::open(path.clone()).unwrap();
+
+        let db = Variable::new_with_vendor(
+            "db",
+            Uuid::parse_str("d719b2cb-3d3a-4596-a3bc-dad00e67656f").unwrap(),
+        );
+        let attributes = VariableFlags::NON_VOLATILE
+            | VariableFlags::BOOTSERVICE_ACCESS
+            | VariableFlags::RUNTIME_ACCESS
+            | VariableFlags::TIME_BASED_AUTHENTICATED_WRITE_ACCESS;
+        let timestamp = EfiTime {
+            year: 2024,
+            month: 1,
+            day: 2,
+            ..Default::default()
+        };
+        let payload = AuthenticatedPayload {
+            timestamp,
+            signature: vec![0x30, 0x00],
+            data: vec![1, 2, 3],
+        };
+        store
+            .write_authenticated(&db, attributes, &payload)
+            .unwrap();
+
+        // appending an older update keeps the timestamp
+        let older = AuthenticatedPayload {
+            timestamp: EfiTime {
+                year: 2023,
+                ..timestamp
+            },
+            data: vec![4],
+            ..payload.clone()
+        };
+        store
+            .write_authenticated(&db, attributes | VariableFlags::APPEND_WRITE, &older)
+            .unwrap();
+
+        let mut store = OvmfStore::open(path).unwrap();
+        assert_eq!(store.read(&db).unwrap(), (vec![1, 2, 3, 4], attributes));
+        assert_eq!(
+            store
+                .image()
+                .variable("db", db.vendor().as_ref())
+                .unwrap()
+                .timestamp,
+            timestamp
+        );
+
+        let empty = AuthenticatedPayload {
+            data: vec![],
+            ..payload
+        };
+        store.write_authenticated(&db, attributes, &empty).unwrap();
+        assert!(!store.exists(&db).unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let target = temp_image(&dir, &image(VariableStoreFormat::Normal, &[]));
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o640)).unwrap();
+        let link = dir.path().join("link.fd");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        let boot_order = Variable::new("BootOrder");
+        let mut store = OvmfStore::open(link.clone()).unwrap();
+        store
+            .write(&boot_order, VariableFlags::NON_VOLATILE, &[1, 0])
+            .unwrap();
+
+        // the symlink is kept, and its target keeps its mode
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(
+            fs::metadata(&target).unwrap().permissions().mode() & 0o777,
+            0o640
+        );
+        assert_eq!(
+            fs::read_dir(dir.path()).unwrap().count(),
+            2,
+            "temporary file left behind"
+        );
+        assert!(OvmfStore::open(target)
+            .unwrap()
+            .exists(&boot_order)
+            .unwrap());
+    }
+
+    #[test]
+    fn full() {
+        let format = VariableStoreFormat::Normal;
+        let original = image(format, &[]);
+        let dir = tempfile::tempdir().unwrap();
+        let path = temp_image(&dir, &original);
+        let mut store = OvmfStore::open(path.clone()).unwrap();
+
+        assert!(matches!(
+            store.write(
+                &Variable::new("Huge"),
+                VariableFlags::NON_VOLATILE,
+                &[0; 0x1000]
+            ),
+            Err(Error::VariableStoreFull { .. })
+        ));
+        assert!(!store.exists(&Variable::new("Huge")).unwrap());
+        assert_eq!(fs::read(&path).unwrap(), original);
+    }
+}
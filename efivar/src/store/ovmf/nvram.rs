@@ -0,0 +1,613 @@
+//! This module parses and writes the variable store of edk2 firmware images: a firmware volume
+//! starting with a VARIABLE_STORE_HEADER, followed by a VARIABLE_HEADER (or an
+//! AUTHENTICATED_VARIABLE_HEADER) and the name and data of each variable
+
+use std::{convert::TryFrom, ops::Range, str::FromStr};
+
+use uuid::Uuid;
+
+use crate::{
+    boot::ParseCursor,
+    efi::{EfiTime, VariableFlags},
+    push::PushVecU8,
+    Error, ParseError,
+};
+
+lazy_static::lazy_static! {
+    /// FileSystemGuid of the firmware volume holding the variables (EFI_SYSTEM_NV_DATA_FV_GUID)
+    static ref SYSTEM_NV_DATA_FV_GUID: Uuid =
+        Uuid::from_str("fff12b8d-7696-4c8b-a985-2747075b4f50").unwrap();
+    /// signature of a store of VARIABLE_HEADERs
+    static ref VARIABLE_GUID: Uuid =
+        Uuid::from_str("ddcf3616-3275-4164-98b6-fe85707ffe7d").unwrap();
+    /// signature of a store of AUTHENTICATED_VARIABLE_HEADERs
+    static ref AUTHENTICATED_VARIABLE_GUID: Uuid =
+        Uuid::from_str("aaf32c78-947b-439a-a180-2e144ec37792").unwrap();
+}
+
+const FVH_SIGNATURE: [u8; 4] = *b"_FVH";
+/// size of EFI_FIRMWARE_VOLUME_HEADER without its block map
+const FV_HEADER_MIN_SIZE: usize = 56;
+
+const VARIABLE_STORE_HEADER_SIZE: usize = 28;
+const VARIABLE_STORE_FORMATTED: u8 = 0x5A;
+const VARIABLE_STORE_HEALTHY: u8 = 0xFE;
+
+const VARIABLE_START_ID: u16 = 0x55AA;
+/// Variable states are updated by clearing bits of erased flash: a variable being replaced is
+/// `VAR_ADDED & VAR_IN_DELETED_TRANSITION` until its new version is `VAR_ADDED`
+const VAR_ADDED: u8 = 0x3F;
+const VAR_IN_DELETED_TRANSITION: u8 = 0xFE;
+/// headers start on 4-byte boundaries
+const HEADER_ALIGNMENT: usize = 4;
+/// value of erased flash, which the free space of the store holds
+const ERASED: u8 = 0xFF;
+
+/// The header format of the variables of a store
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariableStoreFormat {
+    /// VARIABLE_HEADER
+    Normal,
+    /// AUTHENTICATED_VARIABLE_HEADER, with the timestamp and key of authenticated variables.
+    /// Used by firmware supporting Secure Boot
+    Authenticated,
+}
+
+impl VariableStoreFormat {
+    fn header_size(self) -> usize {
+        match self {
+            VariableStoreFormat::Normal => 32,
+            VariableStoreFormat::Authenticated => 60,
+        }
+    }
+}
+
+/// A variable of the store
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NvramVariable {
+    pub name: String,
+    pub vendor: Uuid,
+    pub attributes: VariableFlags,
+    pub data: Vec<u8>,
+    /// counter of count-based authenticated variables. Authenticated stores only
+    pub monotonic_count: u64,
+    /// time of the last time-based authenticated write. Authenticated stores only
+    pub timestamp: EfiTime,
+    /// index of the key of count-based authenticated variables. Authenticated stores only
+    pub pubkey_index: u32,
+}
+
+impl NvramVariable {
+    pub fn new(name: &str, vendor: Uuid, attributes: VariableFlags, data: Vec<u8>) -> Self {
+        NvramVariable {
+            name: name.to_owned(),
+            vendor,
+            attributes,
+            data,
+            monotonic_count: 0,
+            timestamp: EfiTime::default(),
+            pubkey_index: 0,
+        }
+    }
+}
+
+/// An edk2 firmware volume holding a variable store, such as an `OVMF_VARS.fd` file
+#[derive(Debug, Clone)]
+pub struct NvramImage {
+    /// the whole file, written back as is except for the variable store
+    image: Vec<u8>,
+    pub format: VariableStoreFormat,
+    /// location of the variable store in the file, header included
+    store: Range<usize>,
+    /// variables that are not deleted, in store order
+    pub variables: Vec<NvramVariable>,
+}
+
+fn align(offset: usize) -> usize {
+    offset.next_multiple_of(HEADER_ALIGNMENT)
+}
+
+/// check the firmware volume header, and return its length
+fn parse_volume_header(image: &[u8]) -> crate::Result<usize> {
+    let mut buf = ParseCursor::new(image);
+    buf.read_bytes(16, "FirmwareVolume.zero_vector")?;
+    if buf.read_guid("FirmwareVolume.file_system_guid")? != *SYSTEM_NV_DATA_FV_GUID {
+        return Err(ParseError::invalid(
+            16,
+            "FirmwareVolume.file_system_guid",
+            "not a variable store volume",
+        )
+        .into());
+    }
+    let volume_length = buf.read_u64("FirmwareVolume.fv_length")?;
+    if buf.read_array::<4>("FirmwareVolume.signature")? != FVH_SIGNATURE {
+        return Err(
+            ParseError::invalid(40, "FirmwareVolume.signature", "not a firmware volume").into(),
+        );
+    }
+    let _attributes = buf.read_u32("FirmwareVolume.attributes")?;
+    let header_length = buf.read_u16("FirmwareVolume.header_length")? as usize;
+
+    if volume_length > image.len() as u64 {
+        return Err(ParseError::invalid(
+            32,
+            "FirmwareVolume.fv_length",
+            format!(
+                "volume of {volume_length} bytes in a file of {}",
+                image.len()
+            ),
+        )
+        .into());
+    }
+    if header_length < FV_HEADER_MIN_SIZE || header_length > volume_length as usize {
+        return Err(ParseError::invalid(
+            48,
+            "FirmwareVolume.header_length",
+            format!("invalid header length {header_length}"),
+        )
+        .into());
+    }
+
+    // the 16-bit words of the header sum to zero
+    let sum = image[..header_length]
+        .chunks(2)
+        .map(|word| u16::from_le_bytes([word[0], *word.get(1).unwrap_or(&0)]))
+        .fold(0u16, u16::wrapping_add);
+    if sum != 0 {
+        return Err(ParseError::invalid(50, "FirmwareVolume.checksum", "invalid checksum").into());
+    }
+
+    Ok(header_length)
+}
+
+/// parse the variable at the cursor. `None` past the last variable
+fn parse_variable(
+    buf: &mut ParseCursor,
+    format: VariableStoreFormat,
+) -> crate::Result<Option<(u8, NvramVariable)>> {
+    if buf.remaining().len() < format.header_size()
+        || buf.remaining()[..2] != VARIABLE_START_ID.to_le_bytes()
+    {
+        return Ok(None);
+    }
+
+    let _start_id = buf.read_u16("Variable.start_id")?;
+    let state = buf.read_u8("Variable.state")?;
+    let _reserved = buf.read_u8("Variable.reserved")?;
+    let attributes = VariableFlags::from_bits_retain(buf.read_u32("Variable.attributes")?);
+    let (monotonic_count, timestamp, pubkey_index) = match format {
+        VariableStoreFormat::Normal => (0, EfiTime::default(), 0),
+        VariableStoreFormat::Authenticated => (
+            buf.read_u64("Variable.monotonic_count")?,
+            EfiTime::parse(buf)?,
+            buf.read_u32("Variable.pubkey_index")?,
+        ),
+    };
+    let name_size = buf.read_u32("Variable.name_size")? as usize;
+    let data_size = buf.read_u32("Variable.data_size")? as usize;
+    let vendor = buf.read_guid("Variable.vendor_guid")?;
+
+    // a variable whose write was interrupted may have garbage sizes, and ends the store
+    if state != VAR_ADDED
+        && state != VAR_ADDED & VAR_IN_DELETED_TRANSITION
+        && name_size.saturating_add(data_size) > buf.remaining().len()
+    {
+        return Ok(None);
+    }
+
+    let name = buf
+        .read_cursor(name_size, "Variable.name")?
+        .read_nt_utf16_string("Variable.name")?;
+    let data = buf.read_bytes(data_size, "Variable.data")?.to_vec();
+
+    Ok(Some((
+        state,
+        NvramVariable {
+            name,
+            vendor,
+            attributes,
+            data,
+            monotonic_count,
+            timestamp,
+            pubkey_index,
+        },
+    )))
+}
+
+impl NvramImage {
+    pub fn parse(image: Vec<u8>) -> crate::Result<NvramImage> {
+        let store_offset = parse_volume_header(&image)?;
+
+        let mut buf = ParseCursor::at(&image, store_offset, "VariableStore")?;
+        let signature = buf.read_guid("VariableStore.signature")?;
+        let format = if signature == *VARIABLE_GUID {
+            VariableStoreFormat::Normal
+        } else if signature == *AUTHENTICATED_VARIABLE_GUID {
+            VariableStoreFormat::Authenticated
+        } else {
+            return Err(ParseError::invalid(
+                store_offset,
+                "VariableStore.signature",
+                format!("unknown variable store format {signature}"),
+            )
+            .into());
+        };
+        let size = buf.read_u32("VariableStore.size")? as usize;
+        if buf.read_u8("VariableStore.format")? != VARIABLE_STORE_FORMATTED {
+            return Err(ParseError::invalid(
+                store_offset + 20,
+                "VariableStore.format",
+                "the store is not formatted",
+            )
+            .into());
+        }
+        if buf.read_u8("VariableStore.state")? != VARIABLE_STORE_HEALTHY {
+            return Err(ParseError::invalid(
+                store_offset + 21,
+                "VariableStore.state",
+                "the store is not healthy",
+            )
+            .into());
+        }
+        if size < VARIABLE_STORE_HEADER_SIZE || store_offset + size > image.len() {
+            return Err(ParseError::invalid(
+                store_offset + 16,
+                "VariableStore.size",
+                format!("invalid store size {size}"),
+            )
+            .into());
+        }
+        let store = store_offset..store_offset + size;
+
+        let mut buf = ParseCursor::at(
+            &image[..store.end],
+            store.start + VARIABLE_STORE_HEADER_SIZE,
+            "VariableStore",
+        )?;
+        let mut states = vec![];
+        let mut variables = vec![];
+        loop {
+            let offset = align(buf.offset());
+            if offset >= store.end {
+                break;
+            }
+            buf = ParseCursor::at(&image[..store.end], offset, "Variable")?;
+
+            match parse_variable(&mut buf, format)? {
+                Some((state, variable)) => {
+                    states.push(state);
+                    variables.push(variable);
+                }
+                None => break,
+            }
+        }
+
+        // a variable in deleted transition is only valid if its new version was not added
+        let is_added = |variable: &NvramVariable| {
+            variables.iter().zip(&states).any(|(other, &state)| {
+                state == VAR_ADDED && other.name == variable.name && other.vendor == variable.vendor
+            })
+        };
+        let variables = variables
+            .iter()
+            .zip(&states)
+            .filter(|(variable, &state)| {
+                state == VAR_ADDED
+                    || (state == VAR_ADDED & VAR_IN_DELETED_TRANSITION && !is_added(variable))
+            })
+            .map(|(variable, _)| variable.clone())
+            .collect();
+
+        Ok(NvramImage {
+            image,
+            format,
+            store,
+            variables,
+        })
+    }
+
+    /// size of the variable store, headers included
+    pub fn store_size(&self) -> usize {
+        self.store.len()
+    }
+
+    pub fn variable(&self, name: &str, vendor: &Uuid) -> Option<&NvramVariable> {
+        self.variables
+            .iter()
+            .find(|variable| variable.name == name && variable.vendor == *vendor)
+    }
+
+    /// Add or replace a variable, keeping its position in the store if it exists
+    pub fn set_variable(&mut self, variable: NvramVariable) {
+        match self
+            .variables
+            .iter_mut()
+            .find(|other| other.name == variable.name && other.vendor == variable.vendor)
+        {
+            Some(other) => *other = variable,
+            None => self.variables.push(variable),
+        }
+    }
+
+    /// Remove a variable, returning whether it existed
+    pub fn remove_variable(&mut self, name: &str, vendor: &Uuid) -> bool {
+        let len = self.variables.len();
+        self.variables
+            .retain(|variable| variable.name != name || variable.vendor != *vendor);
+        self.variables.len() != len
+    }
+
+    /// Encode the image with a garbage-collected variable store: deleted variables are dropped,
+    /// and the free space is erased. The rest of the file, such as the firmware volume header and
+    /// the fault-tolerant write areas, is kept as is
+    pub fn to_bytes(&self) -> crate::Result<Vec<u8>> {
+        let mut image = self.image.clone();
+
+        let mut store =
+            image[self.store.start..self.store.start + VARIABLE_STORE_HEADER_SIZE].to_vec();
+        for variable in &self.variables {
+            store.resize(
+                align(self.store.start + store.len()) - self.store.start,
+                ERASED,
+            );
+
+            let mut name = variable
+                .name
+                .encode_utf16()
+                .flat_map(u16::to_le_bytes)
+                .collect::<Vec<_>>();
+            name.extend_from_slice(&[0x00, 0x00]);
+            let name_size = u32::try_from(name.len()).map_err(|_| Error::ValueTooLarge {
+                what: "variable name",
+            })?;
+            let data_size =
+                u32::try_from(variable.data.len()).map_err(|_| Error::ValueTooLarge {
+                    what: "variable data",
+                })?;
+
+            store.push_u16(VARIABLE_START_ID);
+            store.push_u8(VAR_ADDED);
+            store.push_u8(0);
+            store.push_u32(variable.attributes.bits());
+            if self.format == VariableStoreFormat::Authenticated {
+                store.push_u64(variable.monotonic_count);
+                store.extend_from_slice(&variable.timestamp.to_bytes());
+                store.push_u32(variable.pubkey_index);
+            }
+            store.push_u32(name_size);
+            store.push_u32(data_size);
+            store.push_guid(&variable.vendor);
+            store.extend_from_slice(&name);
+            store.extend_from_slice(&variable.data);
+        }
+
+        if store.len() > self.store.len() {
+            return Err(Error::VariableStoreFull {
+                needed: store.len(),
+                available: self.store.len(),
+            });
+        }
+        store.resize(self.store.len(), ERASED);
+        image[self.store.clone()].copy_from_slice(&store);
+
+        Ok(image)
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use uuid::Uuid;
+
+    use crate::{
+        efi::{EfiTime, VariableFlags},
+        push::PushVecU8,
+        Error,
+    };
+
+    use super::{
+        NvramImage, NvramVariable, VariableStoreFormat, AUTHENTICATED_VARIABLE_GUID,
+        SYSTEM_NV_DATA_FV_GUID, VARIABLE_GUID,
+    };
+
+    /// size of the test images: a volume header, a store of 0x400 bytes, then an area standing
+    /// for the fault-tolerant write blocks
+    pub const IMAGE_SIZE: usize = 0x600;
+    pub const STORE_SIZE: usize = 0x400;
+    const FV_HEADER_LENGTH: usize = 0x48;
+
+    /// build an image laid out like `OVMF_VARS.fd`, with the given raw variable store content
+    pub fn image(format: VariableStoreFormat, variables: &[u8]) -> Vec<u8> {
+        let mut image = vec![];
+        image.extend_from_slice(&[0; 16]);
+        image.push_guid(&SYSTEM_NV_DATA_FV_GUID);
+        image.push_u64(IMAGE_SIZE as u64);
+        image.extend_from_slice(b"_FVH");
+        image.push_u32(0x0004_FEFF);
+        image.push_u16(FV_HEADER_LENGTH as u16);
+        image.push_u16(0); // checksum
+        image.push_u16(0); // ext header offset
+        image.push_u8(0);
+        image.push_u8(2); // revision
+        image.push_u32((IMAGE_SIZE / 0x200) as u32);
+        image.push_u32(0x200);
+        image.push_u64(0);
+        let sum = image
+            .chunks(2)
+            .map(|word| u16::from_le_bytes([word[0], word[1]]))
+            .fold(0u16, u16::wrapping_add);
+        image[50..52].copy_from_slice(&0u16.wrapping_sub(sum).to_le_bytes());
+        assert_eq!(image.len(), FV_HEADER_LENGTH);
+
+        image.push_guid(match format {
+            VariableStoreFormat::Normal => &VARIABLE_GUID,
+            VariableStoreFormat::Authenticated => &AUTHENTICATED_VARIABLE_GUID,
+        });
+        image.push_u32(STORE_SIZE as u32);
+        image.extend_from_slice(&[0x5A, 0xFE, 0, 0, 0, 0, 0, 0]);
+        image.extend_from_slice(variables);
+        image.resize(FV_HEADER_LENGTH + STORE_SIZE, 0xFF);
+        image.resize(IMAGE_SIZE, 0x42);
+        image
+    }
+
+    /// encode a variable header, name and data, aligned for the next one
+    pub fn variable(
+        format: VariableStoreFormat,
+        state: u8,
+        name: &str,
+        vendor: &Uuid,
+        data: &[u8],
+    ) -> Vec<u8> {
+        let name = name
+            .encode_utf16()
+            .chain([0])
+            .flat_map(u16::to_le_bytes)
+            .collect::<Vec<_>>();
+
+        let mut bytes = vec![];
+        bytes.push_u16(0x55AA);
+        bytes.push_u8(state);
+        bytes.push_u8(0);
+        bytes.push_u32(0x7);
+        if format == VariableStoreFormat::Authenticated {
+            bytes.push_u64(0);
+            bytes.extend_from_slice(&[0; 16]);
+            bytes.push_u32(0);
+        }
+        bytes.push_u32(name.len() as u32);
+        bytes.push_u32(data.len() as u32);
+        bytes.push_guid(vendor);
+        bytes.extend_from_slice(&name);
+        bytes.extend_from_slice(data);
+        bytes.resize(bytes.len().next_multiple_of(4), 0xFF);
+        bytes
+    }
+
+    #[test]
+    fn parse_states() {
+        let vendor = Uuid::from_u128(0x1234);
+        for format in [
+            VariableStoreFormat::Normal,
+            VariableStoreFormat::Authenticated,
+        ] {
+            let variables = [
+                variable(format, 0x3C, "Deleted", &vendor, &[1]),
+                variable(format, 0x3E, "Replaced", &vendor, &[1]),
+                variable(format, 0x3F, "Kept", &vendor, &[1, 2, 3]),
+                variable(format, 0x3E, "Interrupted", &vendor, &[1]),
+                variable(format, 0x3F, "Replaced", &vendor, &[2]),
+            ]
+            .concat();
+
+            let image = NvramImage::parse(image(format, &variables)).unwrap();
+            assert_eq!(image.format, format);
+            assert_eq!(
+                image
+                    .variables
+                    .iter()
+                    .map(|variable| (variable.name.as_str(), variable.data.clone()))
+                    .collect::<Vec<_>>(),
+                vec![
+                    ("Kept", vec![1, 2, 3]),
+                    ("Interrupted", vec![1]),
+                    ("Replaced", vec![2])
+                ]
+            );
+            assert_eq!(
+                image.variable("Kept", &vendor).unwrap().attributes,
+                VariableFlags::NON_VOLATILE
+                    | VariableFlags::BOOTSERVICE_ACCESS
+                    | VariableFlags::RUNTIME_ACCESS
+            );
+        }
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let format = VariableStoreFormat::Normal;
+        let valid = image(format, &[]);
+        NvramImage::parse(valid.clone()).unwrap();
+
+        // checksum
+        let mut invalid = valid.clone();
+        invalid[0x20] ^= 1;
+        assert!(NvramImage::parse(invalid).is_err());
+
+        // not a variable store
+        let mut invalid = valid.clone();
+        invalid[0x48] ^= 1;
+        assert!(NvramImage::parse(invalid).is_err());
+
+        // truncated file
+        assert!(NvramImage::parse(valid[..0x100].to_vec()).is_err());
+
+        // a variable overflowing the store
+        let mut overflowing = variable(format, 0x3F, "Big", &Uuid::nil(), &[]);
+        overflowing[12..16].copy_from_slice(&0x1000u32.to_le_bytes());
+        assert!(NvramImage::parse(image(format, &overflowing)).is_err());
+
+        // an interrupted header ends the store
+        let mut interrupted = variable(format, 0x7F, "Big", &Uuid::nil(), &[]);
+        interrupted[12..16].copy_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+        assert!(NvramImage::parse(image(format, &interrupted))
+            .unwrap()
+            .variables
+            .is_empty());
+    }
+
+    #[test]
+    fn garbage_collect() {
+        let format = VariableStoreFormat::Authenticated;
+        let vendor = Uuid::from_u128(0x1234);
+        let original = image(
+            format,
+            &[
+                variable(format, 0x3C, "Deleted", &vendor, &[0xAA; 0x100]),
+                variable(format, 0x3F, "Kept", &vendor, &[1, 2, 3]),
+            ]
+            .concat(),
+        );
+
+        let mut image = NvramImage::parse(original.clone()).unwrap();
+        let timestamp = EfiTime {
+            year: 2024,
+            month: 5,
+            day: 17,
+            ..Default::default()
+        };
+        image.set_variable(NvramVariable {
+            timestamp,
+            ..NvramVariable::new("New", vendor, VariableFlags::default(), vec![4, 5])
+        });
+
+        let bytes = image.to_bytes().unwrap();
+        assert_eq!(bytes.len(), original.len());
+        // volume header and trailing areas are kept
+        assert_eq!(bytes[..0x48 + 28], original[..0x48 + 28]);
+        assert_eq!(bytes[0x448..], original[0x448..]);
+        // the deleted variable was dropped
+        let kept = variable(format, 0x3F, "Kept", &vendor, &[1, 2, 3]);
+        assert_eq!(bytes[0x48 + 28..0x48 + 28 + kept.len()], kept[..]);
+
+        let parsed = NvramImage::parse(bytes).unwrap();
+        assert_eq!(parsed.variables, image.variables);
+        assert_eq!(
+            parsed.variable("New", &vendor).unwrap().timestamp,
+            timestamp
+        );
+
+        assert!(image.remove_variable("Kept", &vendor));
+        assert!(!image.remove_variable("Kept", &vendor));
+
+        image.set_variable(NvramVariable::new(
+            "Huge",
+            vendor,
+            VariableFlags::default(),
+            vec![0; 0x400],
+        ));
+        assert!(matches!(
+            image.to_bytes(),
+            Err(Error::VariableStoreFull { .. })
+        ));
+    }
+}
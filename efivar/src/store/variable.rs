@@ -1,8 +1,11 @@
+#[cfg(feature = "secureboot")]
+use super::authenticated::authenticated_value;
+use super::VendorGroup;
 use crate::efi::{Variable, VariableFlags};
+#[cfg(feature = "secureboot")]
+use crate::secureboot::AuthenticatedPayload;
 use crate::{Error, VarEnumerator, VarManager, VarReader, VarWriter};
 
-use super::VendorGroup;
-
 pub trait VariableStore: VarManager {
     fn get_vendor_group(&self) -> &VendorGroup;
     fn get_vendor_group_mut(&mut self) -> &mut VendorGroup;
@@ -58,6 +61,27 @@ impl<T: VariableStore> VarWriter for T {
             Err(Error::VarNotFound { var: var.clone() })
         }
     }
+
+    #[cfg(feature = "secureboot")]
+    fn write_authenticated(
+        &mut self,
+        var: &Variable,
+        attributes: VariableFlags,
+        payload: &AuthenticatedPayload,
+    ) -> crate::Result<()> {
+        let current = self.read(var).ok().map(|(data, _)| data);
+        match authenticated_value(current, attributes, payload) {
+            Some(data) => self.write(
+                var,
+                attributes.difference(VariableFlags::APPEND_WRITE),
+                &data,
+            ),
+            None => match self.delete(var) {
+                Err(Error::VarNotFound { .. }) => Ok(()),
+                res => res,
+            },
+        }
+    }
 }
 
 impl<T: VariableStore> VarManager for T {}
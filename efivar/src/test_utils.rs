@@ -1,5 +1,12 @@
 use crate::{efi::Variable, Error, VarReader};
 
+#[cfg(all(test, feature = "secureboot"))]
+use crate::secureboot::{SignatureData, SignatureDatabase, SignatureList, SignatureType};
+
+/// DER certificate used to sign test payloads and images
+#[cfg(all(test, feature = "secureboot"))]
+pub const TEST_CERT: &[u8] = include_bytes!("secureboot/tests/test_cert.der");
+
 /// asserts that the variable doesn't exist. Also validates the error
 pub fn assert_var_not_found(manager: &mut dyn VarReader, var: &Variable) {
     if let Error::VarNotFound { var: error_var } = manager.read(var).unwrap_err() {
@@ -8,3 +15,35 @@ pub fn assert_var_not_found(manager: &mut dyn VarReader, var: &Variable) {
         panic!("Reading a non-existent variable should raise VarNotFound");
     }
 }
+
+/// lowercase hexadecimal representation of bytes, such as hashes
+#[cfg(test)]
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// a signature database with one list of `signature_type` for each group of signatures
+#[cfg(all(test, feature = "secureboot"))]
+pub fn signature_database(
+    signature_type: SignatureType,
+    lists: Vec<Vec<SignatureData>>,
+) -> SignatureDatabase {
+    SignatureDatabase {
+        lists: lists
+            .into_iter()
+            .map(|signatures| SignatureList::new(signature_type, signatures).unwrap())
+            .collect(),
+    }
+}
+
+/// a signature database holding a single signature, owned by the nil GUID
+#[cfg(all(test, feature = "secureboot"))]
+pub fn single_signature_database(signature_type: SignatureType, data: &[u8]) -> SignatureDatabase {
+    signature_database(
+        signature_type,
+        vec![vec![SignatureData {
+            owner: uuid::Uuid::nil(),
+            data: data.to_vec(),
+        }]],
+    )
+}
@@ -1,4 +1,6 @@
 use super::efi::{Variable, VariableFlags};
+#[cfg(feature = "secureboot")]
+use crate::secureboot::AuthenticatedPayload;
 
 /// Represents the capability of writing EFI variables
 pub trait VarWriter {
@@ -19,4 +21,19 @@ pub trait VarWriter {
     ) -> crate::Result<()>;
 
     fn delete(&mut self, var: &Variable) -> crate::Result<()>;
+
+    /// Write a signed update to a variable with the `TIME_BASED_AUTHENTICATED_WRITE_ACCESS`
+    /// attribute, such as `db`
+    ///
+    /// Firmware checks the payload and only stores its data, so the payload is written as is by
+    /// default. Variable stores, which have no firmware to do so, store the data themselves.
+    #[cfg(feature = "secureboot")]
+    fn write_authenticated(
+        &mut self,
+        var: &Variable,
+        attributes: VariableFlags,
+        payload: &AuthenticatedPayload,
+    ) -> crate::Result<()> {
+        self.write(var, attributes, &payload.to_bytes()?)
+    }
 }
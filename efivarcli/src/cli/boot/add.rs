@@ -8,23 +8,70 @@ use crate::{
     exit_code::ExitCode,
 };
 
-use byteorder::{LittleEndian, ReadBytesExt};
+use clap::Args;
 use efivar::{
-    boot::{BootEntry, BootEntryAttributes, BootVarFormat, BootVariable, FilePath, FilePathList},
+    boot::{
+        BootEntry, BootEntryAttributes, BootVarFormat, BootVariable, DevicePath, FilePath,
+        FilePathList, LoadOptionKind, OptionalData,
+    },
     efi::Variable,
+    globals::GlobalVarReader,
     VarManager,
 };
 use itertools::Itertools;
+use uuid::Uuid;
 
 use super::partition;
 
-/// get a boot entry ID that isnt used
-pub fn get_used_ids(manager: &dyn VarManager) -> Vec<u16> {
+/// Arguments building the optional data of a new entry
+#[derive(Args)]
+pub struct OptionalDataArgs {
+    /// Arguments to pass to the loaded file, such as a kernel command line for the EFI stub,
+    /// shim or systemd-boot. Stored as UTF-16
+    #[arg(long, conflicts_with_all = ["windows_bcd", "optional_data"])]
+    args: Option<String>,
+
+    /// Build Windows Boot Manager optional data loading this BCD object
+    /// (e.g. 9dea862c-5cdd-4e70-acc1-f32b344d4795 for {bootmgr})
+    #[arg(long, conflicts_with = "optional_data")]
+    windows_bcd: Option<Uuid>,
+
+    /// Raw optional data, in hexadecimal (e.g. "0102ab")
+    #[arg(long, value_parser = parse_hex_optional_data)]
+    optional_data: Option<OptionalData>,
+}
+
+impl OptionalDataArgs {
+    pub fn into_optional_data(self) -> OptionalData {
+        match (self.args, self.windows_bcd, self.optional_data) {
+            (Some(args), _, _) => OptionalData::command_line(&args),
+            (None, Some(bcd_object), _) => OptionalData::windows_boot_manager(bcd_object),
+            (None, None, Some(optional_data)) => optional_data,
+            (None, None, None) => OptionalData::None,
+        }
+    }
+}
+
+fn parse_hex_optional_data(text: &str) -> Result<OptionalData, String> {
+    let hex: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if !hex.len().is_multiple_of(2) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("expected an even number of hexadecimal digits".to_owned());
+    }
+
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect_vec();
+    Ok(OptionalData::parse(&bytes))
+}
+
+/// get the IDs already used by load options of that kind
+pub fn get_used_ids(manager: &dyn VarManager, kind: LoadOptionKind) -> Vec<u16> {
     manager
         .get_all_vars()
         .unwrap()
         .filter(|var| var.vendor().is_efi())
-        .filter_map(|var| var.boot_var_id())
+        .filter_map(|var| var.load_option_id(kind))
         .collect_vec()
 }
 
@@ -59,15 +106,15 @@ fn fix_file_path(mut file_path: String) -> String {
     file_path
 }
 
-pub fn run(
-    manager: &mut dyn VarManager,
+/// build the file path list of a file inside a partition.
+/// Returns None if the file has been identified as non-existent, or the partition could not be found
+fn partition_file_path_list(
+    manager: &dyn VarManager,
     disk: Option<String>,
     partition: Option<String>,
     file_path: String,
-    description: String,
     force: bool,
-    id: Option<u16>,
-) -> ExitCode {
+) -> Option<FilePathList> {
     let efi_partition = {
         if let Some(partition) = partition {
             // query absolute partition
@@ -77,7 +124,7 @@ pub fn run(
             if !force && try_check_if_valid(&abs_partition, &file_path) == Some(false) {
                 // do not continue is the file has been identified as non-existent
                 // ( check() has already printed the error message to the user )
-                return ExitCode::FAILURE;
+                return None;
             }
 
             // retrieve the partition EFI struct
@@ -85,18 +132,26 @@ pub fn run(
         } else {
             // default to currently booted partition
             log::info!("No partition selected. Using active boot partition");
-            let active_id = manager
-                .read(&Variable::new("BootCurrent"))
-                .unwrap()
-                .0
-                .as_slice()
-                .read_u16::<LittleEndian>()
-                .unwrap();
+            let active_id = match manager.get_boot_current() {
+                Ok(active_id) => active_id,
+                Err(err) => {
+                    log::error!(
+                        "Failed to get active boot entry: {err}. Select a partition explicitly"
+                    );
+                    return None;
+                }
+            };
 
             let boot_entry =
-                BootEntry::read(&*manager, &Variable::new(&active_id.boot_var_format())).unwrap();
+                BootEntry::read(manager, &Variable::new(&active_id.boot_var_format())).unwrap();
 
-            boot_entry.file_path_list.unwrap().hard_drive
+            match boot_entry.file_path_list.hard_drive() {
+                Some(hard_drive) => hard_drive.clone(),
+                None => {
+                    log::error!("Active boot entry is not on a partition. Select one explicitly");
+                    return None;
+                }
+            }
         }
     };
 
@@ -107,29 +162,78 @@ pub fn run(
             "File path {file_path} has been fixed to {fixed_file_path} to match EFI requirements"
         );
     }
-    let file_path_list = FilePathList {
-        hard_drive: efi_partition,
-        file_path: FilePath {
+
+    Some(FilePathList::new(vec![
+        DevicePath::HardDrive(efi_partition),
+        DevicePath::FilePath(FilePath {
             path: fixed_file_path,
-        },
+        }),
+    ]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    manager: &mut dyn VarManager,
+    kind: LoadOptionKind,
+    disk: Option<String>,
+    partition: Option<String>,
+    file_path: Option<String>,
+    device_path: Option<FilePathList>,
+    description: String,
+    optional_data: OptionalData,
+    force: bool,
+    id: Option<u16>,
+) -> ExitCode {
+    let file_path_list = match (device_path, file_path) {
+        (Some(device_path), _) => device_path,
+        (None, Some(file_path)) => {
+            match partition_file_path_list(&*manager, disk, partition, file_path, force) {
+                Some(file_path_list) => file_path_list,
+                None => return ExitCode::FAILURE,
+            }
+        }
+        (None, None) => {
+            log::error!("Either a file or a device path is required");
+            return ExitCode::FAILURE;
+        }
     };
 
+    add_entry(
+        manager,
+        kind,
+        file_path_list,
+        description,
+        optional_data,
+        id,
+    )
+}
+
+/// create an active load option loading this file path list, and insert it at the start of its order.
+/// Chooses an unused ID if none is given
+pub fn add_entry(
+    manager: &mut dyn VarManager,
+    kind: LoadOptionKind,
+    file_path_list: FilePathList,
+    description: String,
+    optional_data: OptionalData,
+    id: Option<u16>,
+) -> ExitCode {
     // create boot entry
     let entry = BootEntry {
         attributes: BootEntryAttributes::LOAD_OPTION_ACTIVE,
         description,
-        file_path_list: Some(file_path_list),
-        optional_data: vec![],
+        file_path_list,
+        optional_data: optional_data.to_bytes(),
     };
 
     // assign the boot entry an id
     let id: u16 = {
-        let used_boot_ids = get_used_ids(&*manager);
+        let used_boot_ids = get_used_ids(&*manager, kind);
         if let Some(id) = id {
             if used_boot_ids.contains(&id) {
                 log::error!(
-                    "Boot entry {} already exists. Delete it first",
-                    id.boot_var_format()
+                    "{kind} entry {} already exists. Delete it first",
+                    kind.variable_name(id)
                 );
                 return ExitCode::FAILURE;
             }
@@ -138,21 +242,31 @@ pub fn run(
             let id = (0x0001..0xFFFF)
                 .find(|&i| !used_boot_ids.contains(&i))
                 .unwrap();
-            log::info!("Chose id {} for boot entry", id.boot_id_format());
+            log::info!("Chose id {} for {kind} entry", id.boot_id_format());
             id
         }
     };
 
+    // read the order first, so that no entry is created if it is broken
+    let mut ids = match manager.get_load_option_order(kind) {
+        Ok(ids) => ids,
+        // the order variable may not exist until the first option is added
+        Err(efivar::Error::VarNotFound { .. }) => vec![],
+        Err(err) => {
+            log::error!("Failed to get {kind} order: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
     // create the entry
-    manager.create_boot_entry(id, entry.clone()).unwrap();
+    manager.create_load_option(kind, id, entry.clone()).unwrap();
 
-    // add it to boot order
-    let mut ids = manager.get_boot_order().unwrap();
+    // add it to the order
     ids.insert(0, id);
-    manager.set_boot_order(ids).unwrap();
+    manager.set_load_option_order(kind, ids).unwrap();
 
     log::info!("Added entry with success");
-    print_var(&BootVariable { entry, id }, true, 0x0000);
+    print_var(&BootVariable { entry, id }, true, None);
 
     ExitCode::SUCCESS
 }
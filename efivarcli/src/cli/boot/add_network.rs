@@ -0,0 +1,69 @@
+//! This module hands everything related to the 'boot add-network' subcommand
+
+use efivar::{
+    boot::{FilePathList, LoadOptionKind, NetworkBootPath, OptionalData},
+    VarManager,
+};
+
+use crate::{cli::boot::add::add_entry, exit_code::ExitCode};
+
+/// get the network boot path of a NIC, either from its MAC address or from its interface name
+fn network_boot_path(mac: Option<[u8; 6]>, interface: Option<String>) -> Option<NetworkBootPath> {
+    match (mac, interface) {
+        (Some(mac), _) => Some(NetworkBootPath::new(mac)),
+        #[cfg(target_os = "linux")]
+        (None, Some(interface)) => match NetworkBootPath::from_interface(&interface) {
+            Ok(path) => Some(path),
+            Err(err) => {
+                log::error!("Failed to query interface {interface}: {err}");
+                None
+            }
+        },
+        #[cfg(not(target_os = "linux"))]
+        (None, Some(_)) => {
+            log::error!("Querying network interfaces is not supported on this platform. Use --mac");
+            None
+        }
+        (None, None) => {
+            log::error!("Either a MAC address or a network interface is required");
+            None
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    manager: &mut dyn VarManager,
+    mac: Option<[u8; 6]>,
+    interface: Option<String>,
+    pci_path: Option<FilePathList>,
+    ipv6: bool,
+    uri: Option<String>,
+    description: String,
+    id: Option<u16>,
+) -> ExitCode {
+    let mut path = match network_boot_path(mac, interface) {
+        Some(path) => path,
+        None => return ExitCode::FAILURE,
+    };
+
+    // only the first instance makes sense as the location of the NIC
+    if let Some(instance) = pci_path.and_then(|list| list.instances.into_iter().next()) {
+        path = path.with_device_path(instance.nodes);
+    }
+
+    let file_path_list = match (uri, ipv6) {
+        (Some(uri), ipv6) => path.http(&uri, ipv6),
+        (None, true) => path.pxe_ipv6(),
+        (None, false) => path.pxe_ipv4(),
+    };
+
+    add_entry(
+        manager,
+        LoadOptionKind::Boot,
+        file_path_list,
+        description,
+        OptionalData::None,
+        id,
+    )
+}
@@ -1,39 +1,51 @@
 use crate::exit_code::ExitCode;
 
-use efivar::{boot::BootVarFormat, efi::Variable, VarManager};
+use efivar::{boot::LoadOptionKind, VarManager};
 
-pub fn run(manager: &mut dyn VarManager, id: u16) -> ExitCode {
+pub fn run(manager: &mut dyn VarManager, kind: LoadOptionKind, id: u16) -> ExitCode {
     // in this function, we assume that boot entry presence and boot order id presence are not correlated,
     // so we need to remove both of them, no matter if one of these steps raises an error
 
     let mut result = ExitCode::FAILURE;
 
     // delete the entry
-    match manager.delete(&Variable::new(&id.boot_var_format())) {
+    match manager.delete(&kind.variable(id)) {
         Ok(_) => {
-            log::info!("Deleted boot entry variable with id {id} successfully");
+            log::info!("Deleted {kind} entry variable with id {id} successfully");
             result = ExitCode::SUCCESS;
+
+            if kind == LoadOptionKind::Boot {
+                super::hotkey::delete_boot_entry_hotkeys(manager, id);
+            }
+        }
+        Err(efivar::Error::VarNotFound { var: _ }) => {
+            log::error!("{kind} entry variable not found")
         }
-        Err(efivar::Error::VarNotFound { var: _ }) => log::error!("Boot entry variable not found"),
-        Err(err) => log::warn!("Failed to delete boot entry variable: {err}"),
+        Err(err) => log::warn!("Failed to delete {kind} entry variable: {err}"),
     }
 
-    // remove it from boot order
-    let mut ids = manager.get_boot_order().unwrap();
+    // remove it from the order
+    let mut ids = match manager.get_load_option_order(kind) {
+        Ok(ids) => ids,
+        Err(err) => {
+            log::warn!("Failed to get {kind} order: {err}");
+            return result;
+        }
+    };
     let old_size = ids.len();
     ids.retain(|v| *v != id);
 
     let apply = match (old_size, ids.len()) {
         (old, new) if old == new => {
-            log::warn!("ID {id} was not found in boot order");
+            log::warn!("ID {id} was not found in {kind} order");
             false
         }
         (old, new) if old - new == 1 => {
-            log::info!("Removed id {id} from boot order");
+            log::info!("Removed id {id} from {kind} order");
             true
         }
         (old, new) if old - new > 1 => {
-            log::warn!("Removed id {id} multiple times from boot order");
+            log::warn!("Removed id {id} multiple times from {kind} order");
             true
         }
         _ => {
@@ -46,7 +58,7 @@ pub fn run(manager: &mut dyn VarManager, id: u16) -> ExitCode {
     };
 
     if apply {
-        manager.set_boot_order(ids).unwrap();
+        manager.set_load_option_order(kind, ids).unwrap();
         result = ExitCode::SUCCESS;
     }
 
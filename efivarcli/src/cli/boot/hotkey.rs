@@ -0,0 +1,290 @@
+//! This module handles everything related to the 'boot hotkey' subcommand
+
+use clap::Parser;
+use efivar::{
+    boot::{
+        BootEntry, BootOptionSupport, BootVarFormat, InputKey, KeyModifiers, KeyOption,
+        LoadOptionKind,
+    },
+    efi::{Variable, VariableFlags},
+    Error, VarManager,
+};
+
+use crate::{exit_code::ExitCode, id::BootEntryId};
+
+#[derive(Parser)]
+pub enum HotkeyCommand {
+    /// List hotkeys, and check that they still match the boot entry they launch
+    #[command(alias = "get")]
+    List,
+    /// Add a hotkey launching a boot entry
+    Add {
+        /// ID of the boot entry to launch
+        #[arg(value_name = "BOOT_ID")]
+        boot_id: BootEntryId,
+
+        /// Key to press, such as F12, Esc or a single character. Repeat it for key sequences (3 keys at most)
+        #[arg(short, long = "key", required = true)]
+        keys: Vec<InputKey>,
+
+        /// Shift must be held
+        #[arg(long)]
+        shift: bool,
+
+        /// Control must be held
+        #[arg(long)]
+        ctrl: bool,
+
+        /// Alt must be held
+        #[arg(long)]
+        alt: bool,
+
+        /// Logo (Windows) key must be held
+        #[arg(long)]
+        logo: bool,
+
+        /// Menu key must be held
+        #[arg(long)]
+        menu: bool,
+
+        /// SysReq key must be held
+        #[arg(long)]
+        sysreq: bool,
+
+        /// ID to give to the hotkey
+        #[arg(long)]
+        id: Option<BootEntryId>,
+
+        /// Skip checking that the firmware supports this hotkey
+        #[arg(long)]
+        force: bool,
+    },
+    /// Delete hotkey
+    #[command(alias = "del")]
+    #[command(alias = "remove")]
+    Delete {
+        /// ID of the hotkey to delete
+        #[arg()]
+        id: BootEntryId,
+    },
+}
+
+/// get the IDs and variables of all hotkeys
+fn get_hotkeys(manager: &dyn VarManager) -> efivar::Result<Vec<(u16, Variable)>> {
+    let mut hotkeys: Vec<(u16, Variable)> = manager
+        .get_all_vars()?
+        .filter(|var| var.vendor().is_efi())
+        .filter_map(|var| KeyOption::option_id(var.name()).map(|id| (id, var)))
+        .collect();
+    hotkeys.sort_by_key(|(id, _)| *id);
+
+    Ok(hotkeys)
+}
+
+fn list(manager: &dyn VarManager) -> ExitCode {
+    let hotkeys = match get_hotkeys(manager) {
+        Ok(hotkeys) => hotkeys,
+        Err(err) => {
+            log::error!("Failed to list EFI variables: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!("Hotkeys:");
+
+    for (id, var) in hotkeys {
+        println!();
+        println!("ID: {}", id.boot_id_format());
+
+        let key_option = match KeyOption::read(manager, &var) {
+            Ok(key_option) => key_option,
+            Err(err) => {
+                println!("Error: {err}");
+                continue;
+            }
+        };
+
+        println!("Keys: {key_option}");
+
+        let boot_var = Variable::new(&key_option.boot_option.boot_var_format());
+        let status = match manager.read(&boot_var) {
+            Ok((data, _)) => {
+                let description = BootEntry::parse(data.clone())
+                    .map(|entry| entry.description)
+                    .unwrap_or_default();
+                println!(
+                    "Boot entry: {} ({description})",
+                    key_option.boot_option.boot_id_format()
+                );
+
+                if key_option.matches(&data) {
+                    "true"
+                } else {
+                    "false (boot entry changed since the hotkey was created)"
+                }
+            }
+            Err(_) => {
+                println!("Boot entry: {}", key_option.boot_option.boot_id_format());
+                "false (boot entry not found)"
+            }
+        };
+        println!("Valid: {status}");
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn add(
+    manager: &mut dyn VarManager,
+    boot_id: u16,
+    keys: Vec<InputKey>,
+    modifiers: KeyModifiers,
+    id: Option<u16>,
+    force: bool,
+) -> ExitCode {
+    let boot_entry = match BootEntry::read(&*manager, &LoadOptionKind::Boot.variable(boot_id)) {
+        Ok(boot_entry) => boot_entry,
+        Err(Error::VarNotFound { var: _ }) => {
+            log::error!("No boot entry with id {} found", boot_id.boot_id_format());
+            return ExitCode::FAILURE;
+        }
+        Err(err) => {
+            log::error!("Failed to read boot entry: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let key_option = match KeyOption::new(boot_id, &boot_entry, modifiers, keys) {
+        Ok(key_option) => key_option,
+        Err(err) => {
+            log::error!("Invalid hotkey: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if !force {
+        let check = BootOptionSupport::read(&*manager)
+            .and_then(|support| support.check_key_option(&key_option));
+        if let Err(err) = check {
+            log::error!("Cannot add hotkey: {err}. Pass argument --force to skip this check");
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let used_ids: Vec<u16> = match get_hotkeys(&*manager) {
+        Ok(hotkeys) => hotkeys.into_iter().map(|(id, _)| id).collect(),
+        Err(err) => {
+            log::error!("Failed to list EFI variables: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let id = match id {
+        Some(id) if used_ids.contains(&id) => {
+            log::error!(
+                "Hotkey {} already exists. Delete it first",
+                KeyOption::variable_name(id)
+            );
+            return ExitCode::FAILURE;
+        }
+        Some(id) => id,
+        None => (0x0000..0xFFFF).find(|i| !used_ids.contains(i)).unwrap(),
+    };
+
+    manager
+        .write(
+            &Variable::new(&KeyOption::variable_name(id)),
+            VariableFlags::default(),
+            &key_option.to_bytes().unwrap(),
+        )
+        .unwrap();
+
+    log::info!(
+        "Added hotkey {} ({key_option}) launching boot entry {}",
+        id.boot_id_format(),
+        boot_id.boot_id_format()
+    );
+
+    ExitCode::SUCCESS
+}
+
+fn delete(manager: &mut dyn VarManager, id: u16) -> ExitCode {
+    match manager.delete(&Variable::new(&KeyOption::variable_name(id))) {
+        Ok(_) => {
+            log::info!("Deleted hotkey {} with success", id.boot_id_format());
+            ExitCode::SUCCESS
+        }
+        Err(Error::VarNotFound { var: _ }) => {
+            log::error!("Hotkey {} not found", id.boot_id_format());
+            ExitCode::FAILURE
+        }
+        Err(err) => {
+            log::error!("Failed to delete hotkey: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// delete the hotkeys launching this boot entry, so that none is left dangling after deleting it
+pub fn delete_boot_entry_hotkeys(manager: &mut dyn VarManager, boot_id: u16) {
+    let hotkeys = match get_hotkeys(&*manager) {
+        Ok(hotkeys) => hotkeys,
+        Err(err) => {
+            log::warn!("Failed to list hotkeys: {err}");
+            return;
+        }
+    };
+
+    for (id, var) in hotkeys {
+        if let Ok(key_option) = KeyOption::read(&*manager, &var) {
+            if key_option.boot_option == boot_id {
+                match manager.delete(&var) {
+                    Ok(_) => log::info!(
+                        "Deleted hotkey {} launching this boot entry",
+                        id.boot_id_format()
+                    ),
+                    Err(err) => log::warn!(
+                        "Failed to delete hotkey {} launching this boot entry: {err}",
+                        id.boot_id_format()
+                    ),
+                }
+            }
+        }
+    }
+}
+
+pub fn run(manager: &mut dyn VarManager, cmd: HotkeyCommand) -> ExitCode {
+    match cmd {
+        HotkeyCommand::List => list(manager),
+        HotkeyCommand::Add {
+            boot_id,
+            keys,
+            shift,
+            ctrl,
+            alt,
+            logo,
+            menu,
+            sysreq,
+            id,
+            force,
+        } => {
+            let mut modifiers = KeyModifiers::empty();
+            modifiers.set(KeyModifiers::SHIFT, shift);
+            modifiers.set(KeyModifiers::CONTROL, ctrl);
+            modifiers.set(KeyModifiers::ALT, alt);
+            modifiers.set(KeyModifiers::LOGO, logo);
+            modifiers.set(KeyModifiers::MENU, menu);
+            modifiers.set(KeyModifiers::SYS_REQ, sysreq);
+
+            add(
+                manager,
+                boot_id.0,
+                keys,
+                modifiers,
+                id.map(|id| id.0),
+                force,
+            )
+        }
+        HotkeyCommand::Delete { id } => delete(manager, id.0),
+    }
+}
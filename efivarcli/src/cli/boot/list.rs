@@ -1,14 +1,14 @@
 use crate::exit_code::ExitCode;
 
-use byteorder::{LittleEndian, ReadBytesExt};
 use efivar::{
-    boot::{BootEntry, BootEntryAttributes, BootVarFormat, BootVariable},
+    boot::{BootEntry, BootEntryAttributes, BootVarFormat, BootVariable, LoadOptionKind},
     efi::Variable,
+    globals::GlobalVarReader,
     VarManager,
 };
 
 /// prints a boot entry to the console, and consume it
-pub fn print_var(boot_var: &BootVariable, verbose: bool, active_boot_id: u16) {
+pub fn print_var(boot_var: &BootVariable, verbose: bool, active_boot_id: Option<u16>) {
     println!();
 
     println!("ID: {}", boot_var.id.boot_id_format());
@@ -23,29 +23,15 @@ pub fn print_var(boot_var: &BootVariable, verbose: bool, active_boot_id: u16) {
 
     println!(
         "Boot file: {}",
-        boot_var
-            .entry
-            .file_path_list
-            .as_ref()
-            .map(|fpl| fpl.to_string())
-            .unwrap_or_else(|| "None/Invalid".to_owned())
+        if boot_var.entry.file_path_list.instances.is_empty() {
+            "None".to_owned()
+        } else {
+            boot_var.entry.file_path_list.to_text(!verbose, true)
+        }
     );
 
     if verbose {
-        println!(
-            "Optional data: {}",
-            if boot_var.entry.optional_data.is_empty() {
-                "None".to_owned()
-            } else {
-                boot_var
-                    .entry
-                    .optional_data
-                    .iter()
-                    .map(|b| format!("{b:02x}"))
-                    .collect::<Vec<String>>()
-                    .join(" ")
-            }
-        );
+        println!("Optional data: {}", boot_var.entry.decoded_optional_data());
 
         println!(
             "Attributes: {}",
@@ -57,16 +43,59 @@ pub fn print_var(boot_var: &BootVariable, verbose: bool, active_boot_id: u16) {
         );
     }
 
-    if active_boot_id == boot_var.id {
+    if active_boot_id == Some(boot_var.id) {
         println!("Active boot entry: true")
     }
 }
 
-pub fn run(manager: &dyn VarManager, verbose: bool) -> ExitCode {
-    let entries = match manager.get_boot_entries() {
+/// describes a boot entry that could not be parsed, with the reason why
+pub(crate) fn format_broken_var(
+    manager: &dyn VarManager,
+    kind: LoadOptionKind,
+    var: &Variable,
+    err: &efivar::Error,
+    verbose: bool,
+) -> String {
+    let mut lines = vec![
+        match var.load_option_id(kind) {
+            Some(id) => format!("ID: {}", id.boot_id_format()),
+            None => format!("Variable: {var}"),
+        },
+        format!("Error: {err}"),
+    ];
+
+    if verbose {
+        if let Ok((data, _)) = manager.read(var) {
+            lines.push(format!(
+                "Raw data: {}",
+                data.iter()
+                    .map(|b| format!("{b:02x}"))
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            ));
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// prints a boot entry that could not be parsed, with the reason why
+fn print_broken_var(
+    manager: &dyn VarManager,
+    kind: LoadOptionKind,
+    var: &Variable,
+    err: &efivar::Error,
+    verbose: bool,
+) {
+    println!();
+    println!("{}", format_broken_var(manager, kind, var, err, verbose));
+}
+
+pub fn run(manager: &dyn VarManager, kind: LoadOptionKind, verbose: bool) -> ExitCode {
+    let entries = match manager.get_load_options(kind) {
         Ok(entries) => entries,
         Err(err) => {
-            log::error!("Failed to get boot entries: {err}");
+            log::error!("Failed to get {kind} entries: {err}");
             return ExitCode::FAILURE;
         }
     };
@@ -75,24 +104,29 @@ pub fn run(manager: &dyn VarManager, verbose: bool) -> ExitCode {
         Ok(vars) => {
             // Only keep EFI variables
             vars.filter(|var| var.vendor().is_efi())
-                .filter_map(|var| var.boot_var_id().map(|id| (id, var)))
+                .filter_map(|var| var.load_option_id(kind).map(|id| (id, var)))
                 .collect()
         }
         Err(err) => {
-            log::warn!("Failed to list EFI variables. You will not be able to see boot variables outside of boot order. Error: {err:?}");
+            log::warn!("Failed to list EFI variables. You will not be able to see {kind} variables outside of {kind} order. Error: {err:?}");
             vec![]
         }
     };
 
-    println!("Boot entries in boot sequence (in boot order):");
+    let sequence = kind.prefix().to_lowercase();
+    println!("{kind} entries in {sequence} sequence (in {sequence} order):");
 
-    let active_id = manager
-        .read(&Variable::new("BootCurrent"))
-        .unwrap()
-        .0
-        .as_slice()
-        .read_u16::<LittleEndian>()
-        .unwrap();
+    // only boot entries can be the active one
+    let active_id = match kind {
+        LoadOptionKind::Boot => match manager.get_boot_current() {
+            Ok(active_id) => Some(active_id),
+            Err(err) => {
+                log::warn!("Failed to get active boot entry: {err}");
+                None
+            }
+        },
+        _ => None,
+    };
 
     for (entry, var) in entries {
         // remove this variable from the list of variables to show
@@ -100,7 +134,7 @@ pub fn run(manager: &dyn VarManager, verbose: bool) -> ExitCode {
 
         match entry {
             Ok(entry) => print_var(&entry, verbose, active_id),
-            Err(err) => log::error!("Failed to get boot entry from variable {var}: {err}"),
+            Err(err) => print_broken_var(manager, kind, &var, &err, verbose),
         }
     }
 
@@ -109,11 +143,11 @@ pub fn run(manager: &dyn VarManager, verbose: bool) -> ExitCode {
     }
 
     println!();
-    println!("Found boot entries not in boot sequence:");
+    println!("Found {kind} entries not in {sequence} sequence:");
     for (boot_id, var) in vars {
         match BootEntry::read(manager, &var) {
             Ok(entry) => print_var(&BootVariable { entry, id: boot_id }, verbose, active_id),
-            Err(err) => log::error!("Failed to get boot entry from variable {var}: {err}"),
+            Err(err) => print_broken_var(manager, kind, &var, &err, verbose),
         };
     }
 
@@ -0,0 +1,95 @@
+//! This module handles the subcommands shared by all kinds of load options, such as 'driver' and 'sysprep'
+
+use clap::Parser;
+use efivar::{
+    boot::{FilePathList, LoadOptionKind},
+    VarManager,
+};
+
+use crate::{exit_code::ExitCode, id::BootEntryId};
+
+use super::{
+    add, add::OptionalDataArgs, delete, disk_help, list, order, order::OrderCommand, partition_help,
+};
+
+#[derive(Parser)]
+pub enum LoadOptionCommand {
+    /// Get all entries found, both in the order, and outside it if the name matchs
+    #[command(alias = "get")]
+    List {
+        /// Show more information, such as optional data
+        #[arg(short, long)]
+        verbose: bool,
+    },
+    Add {
+        #[arg(long, requires = "partition", help=disk_help())]
+        disk: Option<String>,
+
+        #[arg(short, long, help = partition_help())]
+        partition: Option<String>,
+
+        /// File to load, inside the partition
+        #[arg(short, long, required_unless_present = "device_path")]
+        file: Option<String>,
+
+        /// Full device path to load, in the UEFI text format
+        /// (e.g. "PciRoot(0x0)/Pci(0x14,0x0)/USB(0x3,0x0)")
+        #[arg(long, conflicts_with_all = ["disk", "partition", "file"])]
+        device_path: Option<FilePathList>,
+
+        /// Set entry description
+        #[arg(short, long, alias = "desc")]
+        description: String,
+
+        #[command(flatten)]
+        optional_data: OptionalDataArgs,
+
+        /// Skip checks to ensure data is valid
+        #[arg(long)]
+        force: bool,
+
+        /// ID to give to the entry
+        #[arg(long)]
+        id: Option<BootEntryId>,
+    },
+    /// Delete entry
+    #[command(alias = "del")]
+    #[command(alias = "remove")]
+    Delete {
+        /// ID of the entry to delete
+        #[arg()]
+        id: BootEntryId,
+    },
+    /// Manage the order of entries
+    #[command(subcommand)]
+    Order(OrderCommand),
+}
+
+pub fn run(manager: &mut dyn VarManager, kind: LoadOptionKind, cmd: LoadOptionCommand) -> ExitCode {
+    match cmd {
+        LoadOptionCommand::List { verbose } => list::run(manager, kind, verbose),
+        LoadOptionCommand::Add {
+            disk,
+            partition,
+            file,
+            device_path,
+            description,
+            optional_data,
+            force,
+            id,
+        } => add::run(
+            manager,
+            kind,
+            disk,
+            partition,
+            file,
+            device_path,
+            description,
+            optional_data.into_optional_data(),
+            force,
+            id.map(|id| id.0),
+        ),
+        LoadOptionCommand::Delete { id } => delete::run(manager, kind, id.0),
+        LoadOptionCommand::Order(arg) => order::run(manager, kind, arg),
+    }
+}
@@ -1,19 +1,29 @@
 use crate::exit_code::ExitCode;
 
 use clap::Parser;
-use efivar::VarManager;
+use efivar::{
+    boot::{parse_mac_address, FilePathList, LoadOptionKind},
+    VarManager,
+};
 
 use crate::id::BootEntryId;
 
-use self::{next::BootNextCommand, order::OrderCommand};
+use self::{
+    add::OptionalDataArgs, hotkey::HotkeyCommand, next::BootNextCommand, order::OrderCommand,
+    timeout::BootTimeoutCommand,
+};
 
 pub mod add;
+pub mod add_network;
 pub mod delete;
 pub mod enable_disable;
+pub mod hotkey;
 pub mod list;
+pub mod load_option;
 pub mod next;
 pub mod order;
 pub mod partition;
+pub mod timeout;
 
 #[cfg(test)]
 mod tests;
@@ -60,13 +70,21 @@ pub enum BootCommand {
         partition: Option<String>,
 
         /// File to boot from, inside the partition
-        #[arg(short, long)]
-        file: String,
+        #[arg(short, long, required_unless_present = "device_path")]
+        file: Option<String>,
+
+        /// Full device path to boot from, in the UEFI text format
+        /// (e.g. "PciRoot(0x0)/Pci(0x14,0x0)/USB(0x3,0x0)")
+        #[arg(long, conflicts_with_all = ["disk", "partition", "file"])]
+        device_path: Option<FilePathList>,
 
         /// Set entry description
         #[arg(short, long, alias = "desc")]
         description: String,
 
+        #[command(flatten)]
+        optional_data: OptionalDataArgs,
+
         /// Skip checks to ensure data is valid
         #[arg(long)]
         force: bool,
@@ -75,6 +93,41 @@ pub enum BootCommand {
         #[arg(long)]
         id: Option<BootEntryId>,
     },
+    /// Add a network boot entry (PXE, or UEFI HTTP boot if an URI is given)
+    AddNetwork {
+        /// MAC address of the network interface to boot from (e.g. 52:54:00:12:34:56)
+        #[arg(long, required_unless_present = "interface", value_parser = parse_mac_address)]
+        mac: Option<[u8; 6]>,
+
+        /// Network interface to boot from (e.g. enp3s0). Its MAC address and PCI path are read from sysfs
+        #[arg(long, conflicts_with_all = ["mac", "pci_path"])]
+        interface: Option<String>,
+
+        /// Device path of the network controller, in the UEFI text format
+        /// (e.g. "PciRoot(0x0)/Pci(0x3,0x0)")
+        #[arg(long)]
+        pci_path: Option<FilePathList>,
+
+        /// Boot over IPv4 (default)
+        #[arg(long, conflicts_with = "ipv6")]
+        ipv4: bool,
+
+        /// Boot over IPv6
+        #[arg(long)]
+        ipv6: bool,
+
+        /// URI of the file to boot with UEFI HTTP boot (e.g. http://192.168.1.1/boot.efi). Uses PXE if not set
+        #[arg(long)]
+        uri: Option<String>,
+
+        /// Set entry description
+        #[arg(short, long, alias = "desc")]
+        description: String,
+
+        /// ID to give to the boot entry
+        #[arg(long)]
+        id: Option<BootEntryId>,
+    },
     /// Delete boot entry
     #[command(alias = "del")]
     #[command(alias = "remove")]
@@ -101,31 +154,63 @@ pub enum BootCommand {
     /// Manage BootNext variable
     #[command(subcommand)]
     Next(BootNextCommand),
+    /// Manage the boot menu timeout (Timeout variable)
+    #[command(subcommand)]
+    Timeout(BootTimeoutCommand),
+    /// Manage hotkeys launching boot entries (Key#### variables)
+    #[command(subcommand)]
+    Hotkey(HotkeyCommand),
 }
 
 pub fn run(manager: &mut dyn VarManager, cmd: BootCommand) -> ExitCode {
     match cmd {
-        BootCommand::List { verbose } => list::run(manager, verbose),
+        BootCommand::List { verbose } => list::run(manager, LoadOptionKind::Boot, verbose),
         BootCommand::Add {
             disk,
             partition,
             file,
+            device_path,
             description,
+            optional_data,
             force,
             id,
         } => add::run(
             manager,
+            LoadOptionKind::Boot,
             disk,
             partition,
             file,
+            device_path,
             description,
+            optional_data.into_optional_data(),
             force,
             id.map(|id| id.0),
         ),
-        BootCommand::Delete { id } => delete::run(manager, id.0),
+        BootCommand::AddNetwork {
+            mac,
+            interface,
+            pci_path,
+            ipv4: _,
+            ipv6,
+            uri,
+            description,
+            id,
+        } => add_network::run(
+            manager,
+            mac,
+            interface,
+            pci_path,
+            ipv6,
+            uri,
+            description,
+            id.map(|id| id.0),
+        ),
+        BootCommand::Delete { id } => delete::run(manager, LoadOptionKind::Boot, id.0),
         BootCommand::Enable { id } => enable_disable::enable(manager, id.0),
         BootCommand::Disable { id } => enable_disable::disable(manager, id.0),
-        BootCommand::Order(arg) => order::run(manager, arg),
+        BootCommand::Order(arg) => order::run(manager, LoadOptionKind::Boot, arg),
         BootCommand::Next(arg) => next::run(manager, arg),
+        BootCommand::Timeout(arg) => timeout::run(manager, arg),
+        BootCommand::Hotkey(arg) => hotkey::run(manager, arg),
     }
 }
@@ -1,10 +1,10 @@
 use crate::exit_code::ExitCode;
 
-use byteorder::{LittleEndian, ReadBytesExt};
 use clap::Parser;
 use efivar::{
     boot::{BootEntry, BootEntryAttributes, BootVarFormat},
-    efi::{Variable, VariableFlags},
+    efi::Variable,
+    globals::{GlobalVarReader, GlobalVarWriter},
     Error, VarManager,
 };
 
@@ -26,29 +26,20 @@ pub enum BootNextCommand {
 
 pub fn run(manager: &mut dyn VarManager, cmd: BootNextCommand) -> ExitCode {
     match cmd {
-        BootNextCommand::Get => {
-            let res = manager.read(&Variable::new("BootNext"));
-            match res {
-                Ok((data, _)) => {
-                    log::info!(
-                        "Next booting on ID: {}",
-                        data.as_slice()
-                            .read_u16::<LittleEndian>()
-                            .unwrap()
-                            .boot_id_format()
-                    );
-                    ExitCode::SUCCESS
-                }
-                Err(Error::VarNotFound { var: _ }) => {
-                    log::warn!("BootNext is not set");
-                    ExitCode::FAILURE
-                }
-                Err(err) => {
-                    log::error!("Failed to read BootNext: {err}");
-                    ExitCode::FAILURE
-                }
+        BootNextCommand::Get => match manager.get_boot_next() {
+            Ok(id) => {
+                log::info!("Next booting on ID: {}", id.boot_id_format());
+                ExitCode::SUCCESS
             }
-        }
+            Err(Error::VarNotFound { var: _ }) => {
+                log::warn!("BootNext is not set");
+                ExitCode::FAILURE
+            }
+            Err(err) => {
+                log::error!("Failed to read BootNext: {err}");
+                ExitCode::FAILURE
+            }
+        },
         BootNextCommand::Set { id } => {
             let id = id.0;
 
@@ -72,13 +63,10 @@ pub fn run(manager: &mut dyn VarManager, cmd: BootNextCommand) -> ExitCode {
                 log::warn!("Boot entry is not active, and may not boot. Enable it with `efivarcli boot enable {}`", id.boot_id_format());
             }
 
-            manager
-                .write(
-                    &Variable::new("BootNext"),
-                    VariableFlags::default(),
-                    &id.to_le_bytes(),
-                )
-                .unwrap();
+            if let Err(err) = manager.set_boot_next(id) {
+                log::error!("Failed to set BootNext: {err}");
+                return ExitCode::FAILURE;
+            }
 
             log::info!(
                 "BootNext set to ID {} ({}) with success",
@@ -88,7 +76,7 @@ pub fn run(manager: &mut dyn VarManager, cmd: BootNextCommand) -> ExitCode {
 
             ExitCode::SUCCESS
         }
-        BootNextCommand::Unset => match manager.delete(&Variable::new("BootNext")) {
+        BootNextCommand::Unset => match manager.delete_boot_next() {
             Ok(_) => {
                 log::info!("BootNext unset with success");
                 ExitCode::SUCCESS
@@ -1,19 +1,27 @@
 use crate::exit_code::ExitCode;
 
-use efivar::{boot::BootVarFormat, VarManager};
+use efivar::{
+    boot::{BootVarFormat, LoadOptionKind},
+    VarManager,
+};
 
-pub fn run(manager: &mut dyn VarManager, id: u16, position: Option<usize>) -> ExitCode {
-    let mut ids = manager.get_boot_order().unwrap();
+pub fn run(
+    manager: &mut dyn VarManager,
+    kind: LoadOptionKind,
+    id: u16,
+    position: Option<usize>,
+) -> ExitCode {
+    let mut ids = super::get_order(manager, kind).unwrap();
     if let Some(position) = position {
         ids.insert(position, id);
     } else {
         ids.push(id);
     }
 
-    manager.set_boot_order(ids.clone()).unwrap(); // TODO remove clone() call
+    manager.set_load_option_order(kind, ids.clone()).unwrap(); // TODO remove clone() call
 
     log::info!(
-        "Added new id {} to boot order. New boot order: {}",
+        "Added new id {} to {kind} order. New {kind} order: {}",
         id.boot_id_format(),
         super::boot_order_str(&ids)
     );
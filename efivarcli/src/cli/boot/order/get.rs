@@ -1,20 +1,20 @@
 use crate::exit_code::ExitCode;
 
-use efivar::{boot::BootVarFormat, VarManager};
+use efivar::{boot::LoadOptionKind, VarManager};
 
-pub fn run(manager: &dyn VarManager) -> ExitCode {
-    let ids = match manager.get_boot_order() {
+pub fn run(manager: &dyn VarManager, kind: LoadOptionKind) -> ExitCode {
+    let ids = match super::get_order(manager, kind) {
         Ok(ids) => ids,
         Err(err) => {
-            log::error!("Failed to get boot order IDs: {err}");
+            log::error!("Failed to get {kind} order IDs: {err}");
             return ExitCode::FAILURE;
         }
     };
 
-    println!("Boot order:");
+    println!("{kind} order:");
 
     for id in ids {
-        println!("{}", id.boot_var_format());
+        println!("{}", kind.variable_name(id));
     }
 
     ExitCode::SUCCESS
@@ -1,7 +1,10 @@
 use crate::exit_code::ExitCode;
 
 use clap::Parser;
-use efivar::{boot::BootVarFormat, VarManager};
+use efivar::{
+    boot::{BootVarFormat, LoadOptionKind},
+    VarManager,
+};
 use itertools::Itertools;
 
 use crate::id::BootEntryId;
@@ -15,9 +18,9 @@ mod tests;
 
 #[derive(Parser)]
 pub enum OrderCommand {
-    /// Get current boot order IDs. See `efivarcli boot list` to get boot entries information
+    /// Get current order IDs. See `efivarcli boot list` to get boot entries information
     Get,
-    /// Adds an id to the boot order
+    /// Adds an id to the order
     Add {
         /// ID of the entry to add
         #[arg(value_name = "ID")]
@@ -27,7 +30,7 @@ pub enum OrderCommand {
         #[arg(short, long, value_name = "POSITION")]
         position: Option<usize>,
     },
-    /// Remove an id from the boot order
+    /// Remove an id from the order
     #[command(alias = "del")]
     #[command(alias = "delete")]
     Remove {
@@ -39,25 +42,33 @@ pub enum OrderCommand {
         #[arg(long)]
         force: bool,
     },
-    /// Overwrite the boot order with the ids provided
-    /// Warning: the old boot order will be erased !
+    /// Overwrite the order with the ids provided
+    /// Warning: the old order will be erased !
     Set {
-        /// ids that will compose the new boot order
+        /// ids that will compose the new order
         ids: Vec<BootEntryId>,
     },
 }
 
-pub fn run(manager: &mut dyn VarManager, cmd: OrderCommand) -> ExitCode {
+pub fn run(manager: &mut dyn VarManager, kind: LoadOptionKind, cmd: OrderCommand) -> ExitCode {
     match cmd {
-        OrderCommand::Get => get::run(manager),
-        OrderCommand::Add { id, position } => add::run(manager, id.0, position),
-        OrderCommand::Remove { id, force } => remove::run(manager, id.0, force),
+        OrderCommand::Get => get::run(manager, kind),
+        OrderCommand::Add { id, position } => add::run(manager, kind, id.0, position),
+        OrderCommand::Remove { id, force } => remove::run(manager, kind, id.0, force),
         OrderCommand::Set { ids } => {
-            set::run(manager, ids.into_iter().map(|id| id.0).collect_vec())
+            set::run(manager, kind, ids.into_iter().map(|id| id.0).collect_vec())
         }
     }
 }
 
+/// get the order of that kind of load option. A missing order variable is an empty order
+fn get_order(manager: &dyn VarManager, kind: LoadOptionKind) -> efivar::Result<Vec<u16>> {
+    match manager.get_load_option_order(kind) {
+        Err(efivar::Error::VarNotFound { .. }) => Ok(vec![]),
+        res => res,
+    }
+}
+
 /// Generate a string version of the boot order.
 fn boot_order_str(ids: &[u16]) -> String {
     ids.iter().map(|id| id.boot_id_format()).join(" ")
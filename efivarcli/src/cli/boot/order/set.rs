@@ -1,12 +1,12 @@
 use crate::exit_code::ExitCode;
 
-use efivar::VarManager;
+use efivar::{boot::LoadOptionKind, VarManager};
 
-pub fn run(manager: &mut dyn VarManager, ids: Vec<u16>) -> ExitCode {
-    manager.set_boot_order(ids.clone()).unwrap(); // TODO remove clone() call
+pub fn run(manager: &mut dyn VarManager, kind: LoadOptionKind, ids: Vec<u16>) -> ExitCode {
+    manager.set_load_option_order(kind, ids.clone()).unwrap(); // TODO remove clone() call
 
     log::info!(
-        "Overwrote boot order. New boot order: {}",
+        "Overwrote {kind} order. New {kind} order: {}",
         super::boot_order_str(&ids)
     );
 
@@ -1,8 +1,12 @@
 use core::panic;
-use std::{io::BufRead, path::PathBuf, process::Command};
+use std::{
+    io::{BufRead, Read},
+    path::PathBuf,
+    process::Command,
+};
 
 use anyhow::Context;
-use efivar::boot::{EFIHardDrive, EFIHardDriveType};
+use efivar::boot::{EFIHardDrive, PartitionSignature};
 use itertools::Itertools;
 
 /// Absolute name of a partition, should be enough to uniquely identify it
@@ -56,9 +60,10 @@ pub fn query_partition(disk: Option<String>, partition: String) -> anyhow::Resul
     }
 }
 
-/// get the partition UUID from its name
+/// get the partition PARTUUID from its name. This is an UUID on GPT disks,
+/// and the disk signature followed by the partition number on MBR disks (e.g. 'be1afdfa-01')
 /// * `name`: the name of the partition, e.g. '/dev/sda1'
-fn get_partition_uuid(name: &str) -> Option<uuid::Uuid> {
+fn get_partition_uuid(name: &str) -> Option<String> {
     let output = Command::new("blkid").output().unwrap().stdout;
 
     if output.is_empty() {
@@ -78,8 +83,7 @@ fn get_partition_uuid(name: &str) -> Option<uuid::Uuid> {
         for pair in data.split(' ') {
             let (key, value) = pair.split_once('=').unwrap();
             if key == "PARTUUID" {
-                let value = value.trim_matches('"');
-                return Some(uuid::Uuid::parse_str(value).unwrap());
+                return Some(value.trim_matches('"').to_owned());
             }
         }
 
@@ -89,6 +93,41 @@ fn get_partition_uuid(name: &str) -> Option<uuid::Uuid> {
     None
 }
 
+/// get the disk holding a partition, e.g. '/dev/sda' for '/dev/sda1'
+fn get_parent_disk(name: &str) -> anyhow::Result<String> {
+    let stripped_name = name.strip_prefix("/dev/").unwrap();
+    // e.g. /sys/devices/pci0000:00/0000:00:17.0/ata1/host0/target0:0:0/0:0:0:0/block/sda/sda1
+    let sysfs_path = std::fs::canonicalize(format!("/sys/class/block/{stripped_name}"))?;
+    let disk_name = sysfs_path
+        .parent()
+        .and_then(|parent| parent.file_name())
+        .and_then(|disk_name| disk_name.to_str())
+        .context("No parent disk")?;
+
+    Ok(format!("/dev/{disk_name}"))
+}
+
+/// read the disk signature from the MBR of a disk
+fn read_mbr_signature(disk: &str) -> anyhow::Result<PartitionSignature> {
+    let mut sector = [0; 512];
+    std::fs::File::open(disk)?.read_exact(&mut sector)?;
+
+    PartitionSignature::from_mbr(&sector).with_context(|| format!("{disk} has no valid MBR"))
+}
+
+/// get the signature of a partition, from its PARTUUID
+fn get_partition_signature(name: &str) -> anyhow::Result<(PartitionSignature, u8)> {
+    let part_uuid = get_partition_uuid(name).context("Partition has no PARTUUID")?;
+
+    if let Ok(uuid) = uuid::Uuid::parse_str(&part_uuid) {
+        return Ok((PartitionSignature::Gpt(uuid), 0x02));
+    }
+
+    // MBR PARTUUIDs are not unique to the partition: use the disk signature
+    let signature = read_mbr_signature(&get_parent_disk(name)?)?;
+    Ok((signature, 0x01))
+}
+
 /// Partition names are in the form '/dev/sda1', so just take the number at the end
 fn get_partition_number(name: &str) -> Option<u32> {
     name.chars()
@@ -121,7 +160,7 @@ fn get_partition_location(name: &str) -> (u64, u64) {
 
 /// retrieve data needed to generate a EFIHardDrive from the system, from a friendly name of the partition
 pub fn retrieve_efi_partition_data(name: &str) -> anyhow::Result<EFIHardDrive> {
-    let partition_sig = get_partition_uuid(name).unwrap();
+    let (partition_sig, format) = get_partition_signature(name)?;
     let partition_number = get_partition_number(name).unwrap();
     let (partition_start, partition_size) = get_partition_location(name);
 
@@ -130,8 +169,7 @@ pub fn retrieve_efi_partition_data(name: &str) -> anyhow::Result<EFIHardDrive> {
         partition_start,
         partition_size,
         partition_sig,
-        format: 0x02, // no idea
-        sig_type: EFIHardDriveType::Gpt,
+        format,
     })
 }
 
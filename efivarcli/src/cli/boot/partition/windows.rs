@@ -1,6 +1,7 @@
 use anyhow::{Context, Ok};
-use efivar::boot::{EFIHardDrive, EFIHardDriveType};
+use efivar::boot::{EFIHardDrive, PartitionSignature};
 use std::fmt;
+use std::io::Read;
 use std::path::PathBuf;
 use win_partlist::types::PartitionExtra;
 
@@ -37,14 +38,23 @@ pub fn retrieve_efi_partition_data(partition_arg: &Partition) -> anyhow::Result<
         .iter()
         .find(|p| p.partition_number as usize == partition_arg.partition_id)
         .context("Partition not found")?;
-    let partition_win_gpt = match partition_win.extra {
-        PartitionExtra::Gpt(ref gpt) => gpt,
+    let (partition_sig, format) = match partition_win.extra {
+        PartitionExtra::Gpt(ref gpt) => (PartitionSignature::Gpt(gpt.partition_id), 0x02),
+        // MBR partitions are identified by the disk signature
         _ => {
-            anyhow::bail!(
-                "Partition {} on disk {} is not a GPT partition",
-                partition_arg.partition_id,
-                partition_arg.disk_id
-            );
+            // raw disk reads must be a multiple of the sector size, which is 4096 bytes on
+            // 4Kn disks. The MBR is in the first 512 bytes either way
+            let mut sector = [0; 4096];
+            std::fs::File::open(format!(r"\\.\PhysicalDrive{}", partition_arg.disk_id))
+                .and_then(|mut disk| disk.read_exact(&mut sector))
+                .context("Failed to read disk MBR")?;
+            let signature = PartitionSignature::from_mbr(&sector[..512]).with_context(|| {
+                format!(
+                    "Partition {} on disk {} is neither a GPT nor a MBR partition",
+                    partition_arg.partition_id, partition_arg.disk_id
+                )
+            })?;
+            (signature, 0x01)
         }
     };
 
@@ -52,9 +62,8 @@ pub fn retrieve_efi_partition_data(partition_arg: &Partition) -> anyhow::Result<
         partition_number: partition_win.partition_number,
         partition_start: partition_win.starting_offset as u64,
         partition_size: partition_win.partition_length as u64,
-        partition_sig: partition_win_gpt.partition_id,
-        format: 0x02, // no idea
-        sig_type: EFIHardDriveType::Gpt,
+        partition_sig,
+        format,
     })
 }
 
@@ -1,6 +1,6 @@
 use clap::Parser;
 use efivar::{
-    boot::{BootEntry, BootEntryAttributes, FilePath, FilePathList},
+    boot::{BootEntry, BootEntryAttributes, DevicePath, FilePath, FilePathList, OptionalData},
     efi::Variable,
     store::MemoryStore,
     test_utils::assert_var_not_found,
@@ -46,12 +46,13 @@ fn add() {
         BootEntry {
             attributes: BootEntryAttributes::LOAD_OPTION_ACTIVE,
             description: "Some entry".to_owned(),
-            file_path_list: Some(FilePathList {
-                file_path: FilePath {
+            file_path_list: FilePathList::new(vec![
+                // use partition defined earlier
+                DevicePath::HardDrive(setup_entry.file_path_list.hard_drive().unwrap().clone()),
+                DevicePath::FilePath(FilePath {
                     path: "\\a\\b\\c".into()
-                },
-                hard_drive: setup_entry.file_path_list.unwrap().hard_drive // use partition defined earlier
-            }),
+                }),
+            ]),
             optional_data: vec![]
         }
     );
@@ -99,12 +100,13 @@ fn add_set_id() {
         BootEntry {
             attributes: BootEntryAttributes::LOAD_OPTION_ACTIVE,
             description: "Some entry".to_owned(),
-            file_path_list: Some(FilePathList {
-                file_path: FilePath {
+            file_path_list: FilePathList::new(vec![
+                // use partition defined earlier
+                DevicePath::HardDrive(setup_entry.file_path_list.hard_drive().unwrap().clone()),
+                DevicePath::FilePath(FilePath {
                     path: "\\a\\b\\c".into()
-                },
-                hard_drive: setup_entry.file_path_list.unwrap().hard_drive // use partition defined earlier
-            }),
+                }),
+            ]),
             optional_data: vec![]
         }
     );
@@ -152,12 +154,13 @@ fn add_verify_file_path_fix() {
         BootEntry {
             attributes: BootEntryAttributes::LOAD_OPTION_ACTIVE,
             description: "Some entry".to_owned(),
-            file_path_list: Some(FilePathList {
-                file_path: FilePath {
+            file_path_list: FilePathList::new(vec![
+                // use partition defined earlier
+                DevicePath::HardDrive(setup_entry.file_path_list.hard_drive().unwrap().clone()),
+                DevicePath::FilePath(FilePath {
                     path: "\\a\\b\\c".into()
-                },
-                hard_drive: setup_entry.file_path_list.unwrap().hard_drive // use partition defined earlier
-            }),
+                }),
+            ]),
             optional_data: vec![]
         }
     );
@@ -206,3 +209,137 @@ fn add_on_existing() {
         setup_entry
     );
 }
+
+#[test]
+fn add_device_path() {
+    //! Use `efivarcli boot add` with a full device path instead of a partition
+
+    let manager = &mut MemoryStore::new();
+
+    standard_setup(manager, 0x0001);
+
+    let device_path = "PciRoot(0x0)/Pci(0x3,0x0)/MAC(525400123456,0x1)/IPv4(0.0.0.0,0x0,DHCP,0.0.0.0,0.0.0.0,0.0.0.0)";
+
+    // execute `efivarcli boot add`
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from([
+                "efivarcli",
+                "boot",
+                "add",
+                "--device-path",
+                device_path,
+                "--description",
+                "Some entry",
+                "--id",
+                "1000"
+            ]),
+            manager,
+        )
+    );
+
+    // verify inserted entry is right
+    let (data, _) = manager.read(&Variable::new("Boot1000")).unwrap();
+    let entry = BootEntry::parse(data).unwrap();
+    assert_eq!(entry.file_path_list.to_string(), device_path);
+
+    // verify new boot order is right
+    let (data, _) = manager.read(&Variable::new("BootOrder")).unwrap();
+    assert_eq!(data, utils::u16_to_u8(&[0x1000, 0x0001]));
+}
+
+#[test]
+fn add_device_path_conflicts_with_file() {
+    //! `--device-path` cannot be used along with a file
+    assert!(Command::try_parse_from([
+        "efivarcli",
+        "boot",
+        "add",
+        "--device-path",
+        "HD(2,GPT,90364bbd-1000-47fc-8c05-8707e01b4593,0x800,0x100000)/\\a",
+        "--file",
+        "\\a",
+        "--description",
+        "Some entry",
+    ])
+    .is_err());
+}
+
+#[test]
+fn add_optional_data() {
+    //! Build the optional data of the entry from `--args`, `--windows-bcd` or `--optional-data`
+
+    let manager = &mut MemoryStore::new();
+
+    standard_setup(manager, 0x0001);
+
+    for (id, arg, value, expected) in [
+        (
+            "1000",
+            "--args",
+            "initrd=\\initramfs.img quiet",
+            OptionalData::command_line("initrd=\\initramfs.img quiet"),
+        ),
+        (
+            "1001",
+            "--windows-bcd",
+            "9dea862c-5cdd-4e70-acc1-f32b344d4795",
+            OptionalData::windows_boot_manager(
+                "9dea862c-5cdd-4e70-acc1-f32b344d4795".parse().unwrap(),
+            ),
+        ),
+        (
+            "1002",
+            "--optional-data",
+            "01 02ab",
+            OptionalData::Binary(vec![0x01, 0x02, 0xAB]),
+        ),
+    ] {
+        assert_eq!(
+            ExitCode::SUCCESS,
+            crate::run(
+                Command::parse_from([
+                    "efivarcli",
+                    "boot",
+                    "add",
+                    "--file",
+                    "\\a\\b\\c",
+                    "--description",
+                    "Some entry",
+                    arg,
+                    value,
+                    "--id",
+                    id
+                ]),
+                manager,
+            )
+        );
+
+        let entry = BootEntry::read(manager, &Variable::new(&format!("Boot{id}"))).unwrap();
+        assert_eq!(entry.decoded_optional_data(), expected);
+        assert_eq!(entry.optional_data, expected.to_bytes());
+    }
+
+    // optional data builders are exclusive, and hex must be valid
+    for args in [
+        &["--args", "quiet", "--optional-data", "00"][..],
+        &["--optional-data", "0"],
+        &["--windows-bcd", "bootmgr"],
+    ] {
+        assert!(Command::try_parse_from(
+            [
+                "efivarcli",
+                "boot",
+                "add",
+                "--file",
+                "\\a",
+                "--description",
+                "Some entry"
+            ]
+            .iter()
+            .chain(args)
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use clap::Parser;
+use efivar::{
+    boot::{BootEntry, BootEntryAttributes},
+    efi::Variable,
+    store::MemoryStore,
+    utils, VarReader,
+};
+
+use crate::{
+    cli::{boot::tests::standard_setup, Command},
+    exit_code::ExitCode,
+};
+
+#[test]
+fn add_network() {
+    //! Test that `efivarcli boot add-network` creates a PXE entry in front of the boot order
+
+    let manager = &mut MemoryStore::new();
+
+    standard_setup(manager, 0x0001);
+
+    // execute `efivarcli boot add-network`
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from([
+                "efivarcli",
+                "boot",
+                "add-network",
+                "--mac",
+                "52:54:00:12:34:56",
+                "--pci-path",
+                "PciRoot(0x0)/Pci(0x3,0x0)",
+                "--description",
+                "PXE"
+            ]),
+            manager,
+        )
+    );
+
+    // verify inserted entry is right
+    let (data, _) = manager.read(&Variable::new("Boot0002")).unwrap();
+    let entry = BootEntry::parse(data).unwrap();
+    assert_eq!(entry.attributes, BootEntryAttributes::LOAD_OPTION_ACTIVE);
+    assert_eq!(entry.description, "PXE");
+    assert_eq!(
+        entry.file_path_list.to_string(),
+        "PciRoot(0x0)/Pci(0x3,0x0)/MAC(525400123456,0x1)/IPv4(0.0.0.0,0x0,DHCP,0.0.0.0,0.0.0.0,0.0.0.0)"
+    );
+
+    // verify new boot order is right
+    let (data, _) = manager.read(&Variable::new("BootOrder")).unwrap();
+    assert_eq!(data, utils::u16_to_u8(&[0x0002, 0x0001]));
+}
+
+#[test]
+fn add_network_http_ipv6() {
+    //! Use `efivarcli boot add-network` to create an UEFI HTTP boot entry over IPv6
+
+    let manager = &mut MemoryStore::new();
+
+    standard_setup(manager, 0x0001);
+
+    // execute `efivarcli boot add-network`
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from([
+                "efivarcli",
+                "boot",
+                "add-network",
+                "--mac",
+                "52:54:00:12:34:56",
+                "--ipv6",
+                "--uri",
+                "http://[fd00::1]/boot.efi",
+                "--description",
+                "HTTP",
+                "--id",
+                "1000"
+            ]),
+            manager,
+        )
+    );
+
+    // verify inserted entry is right
+    let (data, _) = manager.read(&Variable::new("Boot1000")).unwrap();
+    let entry = BootEntry::parse(data).unwrap();
+    assert_eq!(
+        entry.file_path_list.to_string(),
+        "MAC(525400123456,0x1)/IPv6(::,0x0,Static,::,0x40,::)/Uri(http://[fd00::1]/boot.efi)"
+    );
+}
+
+#[test]
+fn add_network_invalid_args() {
+    //! Invalid MAC addresses and conflicting IP versions are rejected
+    assert!(Command::try_parse_from([
+        "efivarcli",
+        "boot",
+        "add-network",
+        "--mac",
+        "52:54:00:12:34",
+        "--description",
+        "PXE",
+    ])
+    .is_err());
+
+    assert!(Command::try_parse_from([
+        "efivarcli",
+        "boot",
+        "add-network",
+        "--mac",
+        "52:54:00:12:34:56",
+        "--ipv4",
+        "--ipv6",
+        "--description",
+        "PXE",
+    ])
+    .is_err());
+
+    assert!(
+        Command::try_parse_from(["efivarcli", "boot", "add-network", "--description", "PXE",])
+            .is_err()
+    );
+}
@@ -1,6 +1,8 @@
+use std::str::FromStr;
+
 use clap::Parser;
 use efivar::{
-    boot::{BootEntry, BootEntryAttributes},
+    boot::{BootEntry, BootEntryAttributes, BootVarWriter, FilePathList},
     efi::Variable,
     store::MemoryStore,
 };
@@ -92,3 +94,36 @@ fn disable_disabled() {
         BootEntry::read(manager, &Variable::new("Boot0001")).unwrap()
     );
 }
+
+#[test]
+fn enable_keeps_device_path() {
+    //! Enabling an entry that is not on a partition must not lose its device path
+    let manager = &mut MemoryStore::new();
+
+    let mut orig_entry = BootEntry {
+        attributes: BootEntryAttributes::empty(),
+        description: "UEFI PXEv4".to_owned(),
+        file_path_list: FilePathList::from_str(
+            "PciRoot(0x0)/Pci(0x3,0x0)/MAC(525400123456,0x1)/IPv4(0.0.0.0,0x0,DHCP,0.0.0.0,0.0.0.0,0.0.0.0)",
+        )
+        .unwrap(),
+        optional_data: vec![0x4E, 0xAC, 0x08, 0x81],
+    };
+    manager
+        .create_boot_entry(0x0001, orig_entry.clone())
+        .unwrap();
+
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from(["efivarcli", "boot", "enable", "0001",]),
+            manager,
+        )
+    );
+
+    orig_entry.attributes = BootEntryAttributes::LOAD_OPTION_ACTIVE;
+
+    let new_entry = BootEntry::read(manager, &Variable::new("Boot0001")).unwrap();
+
+    assert_eq!(orig_entry, new_entry);
+}
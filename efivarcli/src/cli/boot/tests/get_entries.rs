@@ -1,9 +1,17 @@
 use clap::Parser;
-use efivar::store::MemoryStore;
+use efivar::{
+    boot::{BootEntry, LoadOptionKind},
+    efi::{Variable, VariableFlags},
+    store::MemoryStore,
+    VarWriter,
+};
 
 use crate::{
     cli::{
-        boot::tests::{add_entry, standard_setup},
+        boot::{
+            list::format_broken_var,
+            tests::{add_entry, standard_setup},
+        },
         Command,
     },
     exit_code::ExitCode,
@@ -48,3 +56,56 @@ fn get_entries_not_in_bootorder() {
         crate::run(Command::parse_from(["efivarcli", "boot", "list"]), manager,)
     );
 }
+
+#[test]
+fn get_entries_broken() {
+    let manager = &mut MemoryStore::new();
+
+    standard_setup(manager, 0x0001);
+    // entry with a truncated file path list, not in boot order
+    manager
+        .write(
+            &Variable::new("Boot0002"),
+            VariableFlags::default(),
+            &[
+                0x01, 0x00, 0x00, 0x00, // attributes
+                0x04, 0x00, // file path list length
+                0x41, 0x00, 0x00, 0x00, // description
+                0x7F, 0xFF, // file path list
+            ],
+        )
+        .unwrap();
+
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from(["efivarcli", "boot", "list", "-v"]),
+            manager,
+        )
+    );
+
+    // the error printed for it names the variable, offset and field
+    let var = Variable::new("Boot0002");
+    let err = BootEntry::read(manager, &var).unwrap_err();
+    assert_eq!(
+        format_broken_var(manager, LoadOptionKind::Boot, &var, &err, true),
+        "ID: 0002\n\
+         Error: failed to parse variable 'Boot0002-8be4df61-93ca-11d2-aa0d-00e098032b8c' \
+         at offset 0xA (BootEntry.file_path_list): expected 4 bytes, only 2 available\n\
+         Raw data: 01 00 00 00 04 00 41 00 00 00 7f ff"
+    );
+}
+
+#[test]
+fn get_entries_no_boot_current() {
+    //! Listing entries does not require knowing the active one
+    let manager = &mut MemoryStore::new();
+
+    standard_setup(manager, 0x0001);
+    manager.delete(&Variable::new("BootCurrent")).unwrap();
+
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(Command::parse_from(["efivarcli", "boot", "list"]), manager,)
+    );
+}
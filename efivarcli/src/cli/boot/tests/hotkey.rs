@@ -0,0 +1,143 @@
+use clap::Parser;
+use efivar::{
+    boot::{KeyModifiers, KeyOption},
+    efi::{Variable, VariableFlags},
+    store::MemoryStore,
+    test_utils::assert_var_not_found,
+    VarReader, VarWriter,
+};
+
+use crate::{
+    cli::{
+        boot::tests::{add_entry, standard_setup},
+        Command,
+    },
+    exit_code::ExitCode,
+};
+
+fn set_boot_option_support(manager: &mut MemoryStore, support: u32) {
+    manager
+        .write(
+            &Variable::new("BootOptionSupport"),
+            VariableFlags::default(),
+            &support.to_le_bytes(),
+        )
+        .unwrap();
+}
+
+#[test]
+fn add_list_delete() {
+    let manager = &mut MemoryStore::new();
+
+    standard_setup(manager, 0x0001);
+    set_boot_option_support(manager, 0x0000_0201);
+
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from([
+                "efivarcli",
+                "boot",
+                "hotkey",
+                "add",
+                "0001",
+                "--key",
+                "F12",
+                "--ctrl",
+                "--id",
+                "0002"
+            ]),
+            manager,
+        )
+    );
+
+    let key_option = KeyOption::read(manager, &Variable::new("Key0002")).unwrap();
+    assert_eq!(key_option.to_string(), "Ctrl+F12");
+    assert_eq!(key_option.boot_option, 0x0001);
+    let (data, _) = manager.read(&Variable::new("Boot0001")).unwrap();
+    assert!(key_option.matches(&data));
+
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from(["efivarcli", "boot", "hotkey", "list"]),
+            manager,
+        )
+    );
+
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from(["efivarcli", "boot", "hotkey", "delete", "2"]),
+            manager,
+        )
+    );
+    assert_var_not_found(manager, &Variable::new("Key0002"));
+}
+
+#[test]
+fn add_unsupported() {
+    let manager = &mut MemoryStore::new();
+
+    add_entry(manager, 0x0001, true);
+    // hotkeys supported, but only with a single key
+    set_boot_option_support(manager, 0x0000_0101);
+
+    let args = [
+        "efivarcli",
+        "boot",
+        "hotkey",
+        "add",
+        "0001",
+        "-k",
+        "a",
+        "-k",
+        "b",
+    ];
+    assert_eq!(
+        ExitCode::FAILURE,
+        crate::run(Command::parse_from(args), manager)
+    );
+    assert_var_not_found(manager, &Variable::new("Key0000"));
+
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from(args.iter().chain(&["--force"])),
+            manager
+        )
+    );
+    assert!(KeyOption::read(manager, &Variable::new("Key0000")).is_ok());
+}
+
+#[test]
+fn delete_boot_entry_deletes_hotkeys() {
+    let manager = &mut MemoryStore::new();
+
+    let entries = [
+        (0x0000, 0x0001, standard_setup(manager, 0x0001)),
+        (0x0001, 0x0002, add_entry(manager, 0x0002, true)),
+    ];
+
+    for (key_id, boot_id, entry) in entries {
+        let key_option = KeyOption::new(boot_id, &entry, KeyModifiers::empty(), vec![]).unwrap();
+        manager
+            .write(
+                &Variable::new(&KeyOption::variable_name(key_id)),
+                VariableFlags::default(),
+                &key_option.to_bytes().unwrap(),
+            )
+            .unwrap();
+    }
+
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from(["efivarcli", "boot", "delete", "2"]),
+            manager,
+        )
+    );
+
+    assert!(KeyOption::read(manager, &Variable::new("Key0000")).is_ok());
+    assert_var_not_found(manager, &Variable::new("Key0001"));
+}
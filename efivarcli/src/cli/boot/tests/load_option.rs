@@ -0,0 +1,97 @@
+use clap::Parser;
+use efivar::{
+    boot::{BootEntry, FilePathList},
+    efi::Variable,
+    store::MemoryStore,
+    test_utils::assert_var_not_found,
+    utils, VarReader,
+};
+
+use crate::{cli::Command, exit_code::ExitCode};
+
+#[test]
+fn driver_add_list_delete() {
+    let manager = &mut MemoryStore::new();
+
+    // DriverOrder does not exist until the first driver is added
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from([
+                "efivarcli",
+                "driver",
+                "add",
+                "--device-path",
+                "PciRoot(0x0)/Pci(0x2,0x0)",
+                "--description",
+                "Some driver",
+                "--id",
+                "0003"
+            ]),
+            manager,
+        )
+    );
+
+    let (data, _) = manager.read(&Variable::new("Driver0003")).unwrap();
+    let entry = BootEntry::parse(data).unwrap();
+    assert_eq!(entry.description, "Some driver");
+    assert_eq!(
+        entry.file_path_list,
+        "PciRoot(0x0)/Pci(0x2,0x0)".parse::<FilePathList>().unwrap()
+    );
+
+    let (data, _) = manager.read(&Variable::new("DriverOrder")).unwrap();
+    assert_eq!(data, utils::u16_to_u8(&[0x0003]));
+    assert_var_not_found(manager, &Variable::new("BootOrder"));
+
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from(["efivarcli", "driver", "list", "-v"]),
+            manager,
+        )
+    );
+
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from(["efivarcli", "driver", "delete", "3"]),
+            manager,
+        )
+    );
+
+    assert_var_not_found(manager, &Variable::new("Driver0003"));
+    let (data, _) = manager.read(&Variable::new("DriverOrder")).unwrap();
+    assert_eq!(data, utils::u16_to_u8(&[]));
+}
+
+#[test]
+fn sysprep_order() {
+    let manager = &mut MemoryStore::new();
+
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from(["efivarcli", "sysprep", "order", "add", "1"]),
+            manager,
+        )
+    );
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from(["efivarcli", "sysprep", "order", "add", "2", "-p", "0"]),
+            manager,
+        )
+    );
+
+    let (data, _) = manager.read(&Variable::new("SysPrepOrder")).unwrap();
+    assert_eq!(data, utils::u16_to_u8(&[0x0002, 0x0001]));
+
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from(["efivarcli", "sysprep", "order", "get"]),
+            manager,
+        )
+    );
+}
@@ -2,7 +2,8 @@ use std::str::FromStr;
 
 use efivar::{
     boot::{
-        BootEntry, BootEntryAttributes, EFIHardDrive, EFIHardDriveType, FilePath, FilePathList,
+        BootEntry, BootEntryAttributes, DevicePath, EFIHardDrive, FilePath, FilePathList,
+        LoadOptionKind, PartitionSignature,
     },
     efi::{Variable, VariableFlags},
     store::MemoryStore,
@@ -13,10 +14,14 @@ use uuid::Uuid;
 use crate::cli::boot::add::get_used_ids;
 
 mod add;
+mod add_network;
 mod delete;
 mod enable_disable;
 mod get_entries;
+mod hotkey;
+mod load_option;
 mod next;
+mod timeout;
 
 fn add_entry(manager: &mut dyn VarManager, id: u16, enabled: bool) -> BootEntry {
     // define partition
@@ -24,9 +29,10 @@ fn add_entry(manager: &mut dyn VarManager, id: u16, enabled: bool) -> BootEntry
         partition_number: 1,
         partition_start: 2,
         partition_size: 3,
-        partition_sig: Uuid::from_str("62ca22b7-b071-4bc5-be1d-136a745e7c50").unwrap(),
+        partition_sig: PartitionSignature::Gpt(
+            Uuid::from_str("62ca22b7-b071-4bc5-be1d-136a745e7c50").unwrap(),
+        ),
         format: 5,
-        sig_type: EFIHardDriveType::Gpt,
     };
 
     let entry = BootEntry {
@@ -36,12 +42,12 @@ fn add_entry(manager: &mut dyn VarManager, id: u16, enabled: bool) -> BootEntry
             BootEntryAttributes::empty()
         },
         description: "".to_owned(),
-        file_path_list: Some(FilePathList {
-            file_path: FilePath {
+        file_path_list: FilePathList::new(vec![
+            DevicePath::HardDrive(hard_drive),
+            DevicePath::FilePath(FilePath {
                 path: "somefile".into(),
-            },
-            hard_drive: hard_drive.clone(),
-        }),
+            }),
+        ]),
         optional_data: vec![],
     };
 
@@ -92,7 +98,7 @@ fn get_used_boot_ids() {
         .write(&Variable::new("BootFFFF"), VariableFlags::default(), &[])
         .unwrap();
 
-    let mut used_ids = get_used_ids(manager);
+    let mut used_ids = get_used_ids(manager, LoadOptionKind::Boot);
     used_ids.sort();
 
     assert_eq!(used_ids, vec![0x0001, 0x0500, 0x1000, 0xFFFF]);
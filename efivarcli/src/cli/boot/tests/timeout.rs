@@ -0,0 +1,99 @@
+use clap::Parser;
+use efivar::{
+    efi::{Variable, VariableFlags},
+    store::MemoryStore,
+    test_utils::assert_var_not_found,
+    utils, VarReader, VarWriter,
+};
+
+use crate::{cli::Command, exit_code::ExitCode};
+
+#[test]
+fn set_get_timeout() {
+    let manager = &mut MemoryStore::new();
+
+    for (value, expected) in [("5", 5), ("forever", 0xFFFF), ("65535", 0xFFFF)] {
+        assert_eq!(
+            ExitCode::SUCCESS,
+            crate::run(
+                Command::parse_from(["efivarcli", "boot", "timeout", "set", value]),
+                manager,
+            )
+        );
+
+        let (data, flags) = manager.read(&Variable::new("Timeout")).unwrap();
+        assert_eq!(data, utils::u16_to_u8(&[expected]));
+        assert_eq!(flags, VariableFlags::default());
+
+        assert_eq!(
+            ExitCode::SUCCESS,
+            crate::run(
+                Command::parse_from(["efivarcli", "boot", "timeout", "get"]),
+                manager,
+            )
+        );
+    }
+}
+
+#[test]
+fn set_timeout_out_of_range() {
+    for value in ["65536", "-1", "5s"] {
+        assert!(Command::try_parse_from(["efivarcli", "boot", "timeout", "set", value]).is_err());
+    }
+}
+
+#[test]
+fn get_broken_timeout() {
+    let manager = &mut MemoryStore::new();
+
+    // missing
+    assert_eq!(
+        ExitCode::FAILURE,
+        crate::run(
+            Command::parse_from(["efivarcli", "boot", "timeout", "get"]),
+            manager,
+        )
+    );
+
+    // wrong size
+    manager
+        .write(&Variable::new("Timeout"), VariableFlags::default(), &[0x05])
+        .unwrap();
+    assert_eq!(
+        ExitCode::FAILURE,
+        crate::run(
+            Command::parse_from(["efivarcli", "boot", "timeout", "get"]),
+            manager,
+        )
+    );
+}
+
+#[test]
+fn unset_timeout() {
+    let manager = &mut MemoryStore::new();
+
+    manager
+        .write(
+            &Variable::new("Timeout"),
+            VariableFlags::default(),
+            &utils::u16_to_u8(&[0x0005]),
+        )
+        .unwrap();
+
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from(["efivarcli", "boot", "timeout", "unset"]),
+            manager,
+        )
+    );
+    assert_var_not_found(manager, &Variable::new("Timeout"));
+
+    assert_eq!(
+        ExitCode::FAILURE,
+        crate::run(
+            Command::parse_from(["efivarcli", "boot", "timeout", "unset"]),
+            manager,
+        )
+    );
+}
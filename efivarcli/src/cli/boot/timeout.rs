@@ -0,0 +1,95 @@
+//! This module handles everything related to the 'boot timeout' subcommand
+
+use std::convert::TryFrom;
+
+use clap::Parser;
+use efivar::{
+    globals::{GlobalVarReader, GlobalVarWriter},
+    Error, VarManager,
+};
+
+use crate::exit_code::ExitCode;
+
+/// Timeout value meaning that the boot manager waits for user input
+const TIMEOUT_FOREVER: u16 = 0xFFFF;
+
+#[derive(Parser)]
+pub enum BootTimeoutCommand {
+    /// Unset the Timeout variable, leaving the default to the firmware
+    Unset,
+    /// Get the boot menu timeout
+    Get,
+    /// Set the boot menu timeout
+    Set {
+        /// Seconds to wait before booting, from 0 to 65534. 65535 or "forever" waits for user input
+        #[arg(value_parser = parse_timeout)]
+        seconds: u16,
+    },
+}
+
+fn parse_timeout(text: &str) -> Result<u16, String> {
+    if text.eq_ignore_ascii_case("forever") {
+        return Ok(TIMEOUT_FOREVER);
+    }
+
+    let seconds: u64 = text
+        .parse()
+        .map_err(|_| format!("'{text}' is not a number of seconds"))?;
+    u16::try_from(seconds).map_err(|_| {
+        format!("{seconds} seconds is out of range (0 to {TIMEOUT_FOREVER}, or \"forever\")")
+    })
+}
+
+fn format_timeout(seconds: u16) -> String {
+    if seconds == TIMEOUT_FOREVER {
+        "forever (waiting for user input)".to_owned()
+    } else {
+        format!("{seconds} seconds")
+    }
+}
+
+pub fn run(manager: &mut dyn VarManager, cmd: BootTimeoutCommand) -> ExitCode {
+    match cmd {
+        BootTimeoutCommand::Get => match manager.get_timeout() {
+            Ok(seconds) => {
+                log::info!("Boot menu timeout: {}", format_timeout(seconds));
+                ExitCode::SUCCESS
+            }
+            Err(Error::VarNotFound { var: _ }) => {
+                log::warn!("Timeout is not set");
+                ExitCode::FAILURE
+            }
+            Err(err) => {
+                log::error!("Failed to read Timeout: {err}");
+                ExitCode::FAILURE
+            }
+        },
+        BootTimeoutCommand::Set { seconds } => match manager.set_timeout(seconds) {
+            Ok(()) => {
+                log::info!(
+                    "Boot menu timeout set to {} with success",
+                    format_timeout(seconds)
+                );
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                log::error!("Failed to set Timeout: {err}");
+                ExitCode::FAILURE
+            }
+        },
+        BootTimeoutCommand::Unset => match manager.delete_timeout() {
+            Ok(_) => {
+                log::info!("Timeout unset with success");
+                ExitCode::SUCCESS
+            }
+            Err(Error::VarNotFound { var: _ }) => {
+                log::warn!("Timeout not set");
+                ExitCode::FAILURE
+            }
+            Err(err) => {
+                log::error!("Failed to unset Timeout: {err}");
+                ExitCode::FAILURE
+            }
+        },
+    }
+}
@@ -0,0 +1,63 @@
+//! This module handles the 'firmware-setup' subcommand
+
+use efivar::{
+    globals::{GlobalVarReader, OsIndications, OsIndicationsManager},
+    Error, VarManager,
+};
+
+use crate::exit_code::ExitCode;
+
+fn status(manager: &dyn VarManager) -> ExitCode {
+    let supported = match manager.get_os_indications_supported() {
+        Ok(supported) => supported.contains(OsIndications::BOOT_TO_FW_UI),
+        Err(Error::VarNotFound { .. }) => false,
+        Err(err) => {
+            log::error!("Failed to read OsIndicationsSupported: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let requested = match manager.get_requested_os_indications() {
+        Ok(requested) => requested.contains(OsIndications::BOOT_TO_FW_UI),
+        Err(Error::VarNotFound { .. }) => false,
+        Err(err) => {
+            log::error!("Failed to read OsIndications: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!("Supported: {supported}");
+    println!("Requested for next boot: {requested}");
+
+    ExitCode::SUCCESS
+}
+
+pub fn run(manager: &mut dyn VarManager, clear: bool, show_status: bool) -> ExitCode {
+    if show_status {
+        return status(manager);
+    }
+
+    if clear {
+        match manager.clear_os_indication(OsIndications::BOOT_TO_FW_UI) {
+            Ok(()) => {
+                log::info!("Firmware setup will not be entered on next boot");
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                log::error!("Failed to clear firmware setup request: {err}");
+                ExitCode::FAILURE
+            }
+        }
+    } else {
+        match manager.set_os_indication(OsIndications::BOOT_TO_FW_UI) {
+            Ok(()) => {
+                log::info!("Firmware setup will be entered on next boot");
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                log::error!("Failed to request firmware setup: {err}");
+                ExitCode::FAILURE
+            }
+        }
+    }
+}
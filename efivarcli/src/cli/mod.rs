@@ -1,18 +1,25 @@
 use std::path::PathBuf;
 
 use clap::Parser;
-use efivar::VarManager;
+use efivar::{boot::LoadOptionKind, VarManager};
 
 use crate::exit_code::ExitCode;
 
-use self::boot::BootCommand;
+use self::{
+    boot::{load_option::LoadOptionCommand, BootCommand},
+    mok::MokCommand,
+    secureboot::SecureBootCommand,
+};
 
 pub mod boot;
 pub mod delete;
 pub mod export;
+pub mod firmware_setup;
 pub mod import;
 pub mod list;
+pub mod mok;
 pub mod read;
+pub mod secureboot;
 #[cfg(test)]
 pub mod tests;
 
@@ -64,6 +71,28 @@ pub enum Command {
     /// Manage boot-related variables
     #[command(subcommand)]
     Boot(BootCommand),
+    /// Manage UEFI drivers loaded before boot options (Driver####, DriverOrder)
+    #[command(subcommand)]
+    Driver(LoadOptionCommand),
+    /// Manage system preparation applications launched before boot options (SysPrep####, SysPrepOrder)
+    #[command(subcommand)]
+    Sysprep(LoadOptionCommand),
+    /// Inspect Secure Boot variables and images, and create and enroll custom keys
+    #[command(subcommand)]
+    Secureboot(SecureBootCommand),
+    /// Manage the Machine Owner Keys of shim, and its validation state
+    #[command(subcommand)]
+    Mok(MokCommand),
+    /// Boot into the firmware setup (UEFI settings) on next boot, through OsIndications
+    FirmwareSetup {
+        /// Cancel a previous request
+        #[arg(long, conflicts_with = "status")]
+        clear: bool,
+
+        /// Show whether the firmware supports it, and whether it is requested
+        #[arg(long)]
+        status: bool,
+    },
     /// Export a variable to file
     Export {
         /// Name of the variable to export
@@ -106,6 +135,11 @@ pub fn run(manager: &mut dyn VarManager, cmd: Command) -> ExitCode {
         Command::List { namespace, all } => list::run(manager, namespace, all),
         Command::Delete { name, namespace } => delete::run(manager, &name, namespace),
         Command::Boot(arg) => boot::run(manager, arg),
+        Command::Driver(arg) => boot::load_option::run(manager, LoadOptionKind::Driver, arg),
+        Command::Sysprep(arg) => boot::load_option::run(manager, LoadOptionKind::SysPrep, arg),
+        Command::Secureboot(arg) => secureboot::run(manager, arg),
+        Command::Mok(arg) => mok::run(manager, arg),
+        Command::FirmwareSetup { clear, status } => firmware_setup::run(manager, clear, status),
         Command::Export {
             name,
             namespace,
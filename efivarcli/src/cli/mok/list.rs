@@ -0,0 +1,56 @@
+//! This module handles the 'mok list' subcommand
+
+use efivar::{mok::MokVarReader, secureboot::SignatureDatabase, Error, VarManager};
+
+use crate::{cli::secureboot::list::print_signature, exit_code::ExitCode};
+
+/// reads a list of signatures of shim
+type ReadList = fn(&dyn VarManager) -> efivar::Result<SignatureDatabase>;
+
+pub fn run(manager: &dyn VarManager, verbose: bool) -> ExitCode {
+    let lists: [(&str, ReadList); 4] = [
+        ("MokListRT", |manager| manager.get_mok_list()),
+        ("MokListXRT", |manager| manager.get_mok_list_x()),
+        ("MokNew (pending enrollment)", |manager| {
+            manager.get_mok_new()
+        }),
+        ("MokDel (pending deletion)", |manager| manager.get_mok_del()),
+    ];
+
+    let mut exit_code = ExitCode::SUCCESS;
+
+    for (name, read) in lists {
+        let database = match read(manager) {
+            Ok(database) => database,
+            Err(Error::VarNotFound { .. }) => {
+                println!("{name}: not set");
+                println!();
+                continue;
+            }
+            Err(err) => {
+                log::error!("Failed to read {name}: {err}");
+                exit_code = ExitCode::FAILURE;
+                continue;
+            }
+        };
+
+        println!("{name}: {} signatures", database.signatures().count());
+        for (signature_type, data) in database.signatures() {
+            print_signature(signature_type, data, verbose);
+        }
+        println!();
+    }
+
+    match manager.get_mok_validation_disabled() {
+        Ok(disabled) => println!(
+            "Validation: {}",
+            if disabled { "disabled" } else { "enabled" }
+        ),
+        Err(err) => {
+            log::error!("Failed to read the validation state: {err}");
+            exit_code = ExitCode::FAILURE;
+        }
+    }
+
+    exit_code
+}
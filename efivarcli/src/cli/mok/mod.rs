@@ -0,0 +1,86 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use efivar::VarManager;
+
+use crate::exit_code::ExitCode;
+
+pub mod list;
+pub mod request;
+pub mod sbat;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Parser)]
+pub enum MokCommand {
+    /// List the Machine Owner Keys trusted and refused by shim, and the pending requests
+    List {
+        /// Show more information, such as certificate validity
+        #[arg(short, long)]
+        verbose: bool,
+    },
+    /// Request the enrollment of certificates, confirmed in MokManager on next boot with a
+    /// password read from EFIVARCLI_MOK_PASSWORD, or prompted for
+    Import {
+        /// Certificates to enroll, in PEM or DER format
+        #[arg(value_name = "CERT", required = true)]
+        certificates: Vec<PathBuf>,
+    },
+    /// Request the deletion of enrolled certificates, confirmed in MokManager on next boot with
+    /// a password read from EFIVARCLI_MOK_PASSWORD, or prompted for
+    Delete {
+        /// Certificates to delete, in PEM or DER format
+        #[arg(value_name = "CERT", required = true)]
+        certificates: Vec<PathBuf>,
+    },
+    /// Request shim to stop validating images, confirmed in MokManager on next boot with some
+    /// characters of a password of 8 to 16 characters, read from EFIVARCLI_MOK_PASSWORD or
+    /// prompted for. shim requires the password to be stored in clear until then
+    DisableValidation,
+    /// Show the SBAT revocations applied by shim (SbatLevelRT)
+    Sbat,
+}
+
+/// variable holding the MokManager password, so that it does not appear in the command line
+const PASSWORD_ENV: &str = "EFIVARCLI_MOK_PASSWORD";
+
+/// the password in [`PASSWORD_ENV`], or one typed twice by the user
+fn get_password() -> Option<String> {
+    if let Ok(password) = std::env::var(PASSWORD_ENV) {
+        return Some(password);
+    }
+
+    let prompt = || rpassword::prompt_password("MokManager password: ");
+    let confirm = || rpassword::prompt_password("Confirm password: ");
+    match (prompt(), confirm()) {
+        (Ok(password), Ok(confirmation)) if password == confirmation => Some(password),
+        (Ok(_), Ok(_)) => {
+            log::error!("Passwords do not match");
+            None
+        }
+        (Err(err), _) | (_, Err(err)) => {
+            log::error!("Failed to read the password: {err}");
+            None
+        }
+    }
+}
+
+pub fn run(manager: &mut dyn VarManager, cmd: MokCommand) -> ExitCode {
+    match cmd {
+        MokCommand::List { verbose } => list::run(manager, verbose),
+        MokCommand::Import { certificates } => match get_password() {
+            Some(password) => request::import(manager, &certificates, &password),
+            None => ExitCode::FAILURE,
+        },
+        MokCommand::Delete { certificates } => match get_password() {
+            Some(password) => request::delete(manager, &certificates, &password),
+            None => ExitCode::FAILURE,
+        },
+        MokCommand::DisableValidation => match get_password() {
+            Some(password) => request::disable_validation(manager, &password),
+            None => ExitCode::FAILURE,
+        },
+        MokCommand::Sbat => sbat::run(manager),
+    }
+}
@@ -0,0 +1,117 @@
+//! This module handles the 'mok import', 'mok delete' and 'mok disable-validation' subcommands,
+//! which write requests for MokManager
+
+use std::path::{Path, PathBuf};
+
+use efivar::{
+    mok::{MokVarReader, MokVarWriter},
+    secureboot::{parse_certificate, Certificate, SignatureDatabase, SignatureType},
+    Error, VarManager,
+};
+
+use crate::exit_code::ExitCode;
+
+fn read_certificates(files: &[PathBuf]) -> Option<Vec<Certificate>> {
+    let read = |file: &Path| {
+        std::fs::read(file)
+            .map_err(Error::UnknownIoError)
+            .and_then(|data| parse_certificate(&data))
+    };
+
+    files
+        .iter()
+        .map(|file| {
+            read(file)
+                .map_err(|err| log::error!("Failed to read {}: {err}", file.display()))
+                .ok()
+        })
+        .collect()
+}
+
+/// whether a certificate is in MokListRT
+fn is_enrolled(enrolled: &SignatureDatabase, certificate: &Certificate) -> bool {
+    enrolled.signatures().any(|(signature_type, data)| {
+        signature_type == SignatureType::X509
+            && data
+                .decode(signature_type)
+                .ok()
+                .and_then(|signature| signature.certificate())
+                .is_some_and(|listed| listed.ok().as_ref() == Some(certificate))
+    })
+}
+
+fn enrolled(manager: &dyn VarManager) -> Option<SignatureDatabase> {
+    match manager.get_mok_list() {
+        Ok(database) => Some(database),
+        Err(Error::VarNotFound { .. }) => Some(SignatureDatabase::default()),
+        Err(err) => {
+            log::error!("Failed to read MokListRT: {err}");
+            None
+        }
+    }
+}
+
+fn confirm(result: efivar::Result<()>, action: &str) -> ExitCode {
+    match result {
+        Ok(()) => {
+            println!("Requested {action}. Reboot and confirm it in MokManager with the password");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            log::error!("Failed to request {action}: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+pub fn import(manager: &mut dyn VarManager, files: &[PathBuf], password: &str) -> ExitCode {
+    let (certificates, enrolled) = match (read_certificates(files), enrolled(manager)) {
+        (Some(certificates), Some(enrolled)) => (certificates, enrolled),
+        _ => return ExitCode::FAILURE,
+    };
+
+    let (already_enrolled, certificates): (Vec<_>, Vec<_>) = certificates
+        .into_iter()
+        .partition(|certificate| is_enrolled(&enrolled, certificate));
+    for certificate in already_enrolled {
+        log::warn!(
+            "{} is already enrolled",
+            certificate.tbs_certificate.subject
+        );
+    }
+    if certificates.is_empty() {
+        return ExitCode::SUCCESS;
+    }
+
+    confirm(
+        manager.request_mok_import(&certificates, password),
+        "the enrollment",
+    )
+}
+
+pub fn delete(manager: &mut dyn VarManager, files: &[PathBuf], password: &str) -> ExitCode {
+    let (certificates, enrolled) = match (read_certificates(files), enrolled(manager)) {
+        (Some(certificates), Some(enrolled)) => (certificates, enrolled),
+        _ => return ExitCode::FAILURE,
+    };
+
+    if let Some(certificate) = certificates
+        .iter()
+        .find(|certificate| !is_enrolled(&enrolled, certificate))
+    {
+        log::error!("{} is not enrolled", certificate.tbs_certificate.subject);
+        return ExitCode::FAILURE;
+    }
+
+    confirm(
+        manager.request_mok_delete(&certificates, password),
+        "the deletion",
+    )
+}
+
+pub fn disable_validation(manager: &mut dyn VarManager, password: &str) -> ExitCode {
+    confirm(
+        manager.request_disable_validation(password),
+        "to disable validation",
+    )
+}
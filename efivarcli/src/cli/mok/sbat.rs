@@ -0,0 +1,25 @@
+//! This module handles the 'mok sbat' subcommand
+
+use efivar::{mok::MokVarReader, Error, VarManager};
+
+use crate::exit_code::ExitCode;
+
+pub fn run(manager: &dyn VarManager) -> ExitCode {
+    match manager.get_sbat_level() {
+        Ok(level) => {
+            print!("{level}");
+            if !level.ends_with('\n') {
+                println!();
+            }
+            ExitCode::SUCCESS
+        }
+        Err(Error::VarNotFound { .. }) => {
+            println!("SbatLevelRT: not set");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            log::error!("Failed to read SbatLevelRT: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
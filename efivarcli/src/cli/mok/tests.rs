@@ -0,0 +1,121 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use efivar::{
+    efi::VariableFlags,
+    mok::{MokVarReader, MokVariable},
+    secureboot::SignatureDatabase,
+    store::MemoryStore,
+    VarReader, VarWriter,
+};
+
+use super::request;
+use crate::{
+    cli::{tests::TEST_CERT_PATH, Command},
+    exit_code::ExitCode,
+};
+
+#[test]
+fn list_sbat() {
+    let manager = &mut MemoryStore::new();
+
+    for args in [
+        &["efivarcli", "mok", "list"][..],
+        &["efivarcli", "mok", "sbat"],
+    ] {
+        assert_eq!(
+            ExitCode::SUCCESS,
+            crate::run(Command::parse_from(args), manager)
+        );
+    }
+
+    manager
+        .write(
+            &MokVariable::SBAT_LEVEL_RT.variable(),
+            VariableFlags::BOOTSERVICE_ACCESS | VariableFlags::RUNTIME_ACCESS,
+            b"sbat,1,2022052400\ngrub,2\n",
+        )
+        .unwrap();
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(Command::parse_from(["efivarcli", "mok", "sbat"]), manager)
+    );
+}
+
+#[test]
+fn import_delete() {
+    let manager = &mut MemoryStore::new();
+    // the password is prompted for, so run the requests directly
+    let run = |manager: &mut MemoryStore, command: &str| {
+        let certificates = [PathBuf::from(TEST_CERT_PATH)];
+        match command {
+            "import" => request::import(manager, &certificates, "password"),
+            _ => request::delete(manager, &certificates, "password"),
+        }
+    };
+
+    // not enrolled
+    assert_eq!(ExitCode::FAILURE, run(manager, "delete"));
+
+    assert_eq!(ExitCode::SUCCESS, run(manager, "import"));
+    let request = manager.get_mok_new().unwrap();
+    assert_eq!(request.lists.len(), 1);
+    assert!(manager.read(&MokVariable::MOK_AUTH.variable()).is_ok());
+
+    // enroll it, as MokManager would
+    manager
+        .write(
+            &MokVariable::MOK_LIST_RT.variable(),
+            MokVariable::MOK_LIST_RT.attributes,
+            &request.to_bytes().unwrap(),
+        )
+        .unwrap();
+    manager.delete(&MokVariable::MOK_NEW.variable()).unwrap();
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(Command::parse_from(["efivarcli", "mok", "list"]), manager)
+    );
+
+    // already enrolled
+    assert_eq!(ExitCode::SUCCESS, run(manager, "import"));
+    assert!(manager.get_mok_new().is_err());
+
+    assert_eq!(ExitCode::SUCCESS, run(manager, "delete"));
+    assert_eq!(
+        manager.get_mok_del().unwrap(),
+        SignatureDatabase::parse(
+            &manager
+                .read(&MokVariable::MOK_LIST_RT.variable())
+                .unwrap()
+                .0
+        )
+        .unwrap()
+    );
+
+    assert!(Command::try_parse_from(["efivarcli", "mok", "import"]).is_err());
+    // the password is never taken from the command line
+    assert!(Command::try_parse_from([
+        "efivarcli",
+        "mok",
+        "import",
+        TEST_CERT_PATH,
+        "--password",
+        "password"
+    ])
+    .is_err());
+}
+
+#[test]
+fn disable_validation() {
+    let manager = &mut MemoryStore::new();
+
+    assert_eq!(
+        ExitCode::FAILURE,
+        request::disable_validation(manager, "short")
+    );
+    assert_eq!(
+        ExitCode::SUCCESS,
+        request::disable_validation(manager, "password")
+    );
+    assert!(manager.read(&MokVariable::MOK_SB.variable()).is_ok());
+}
@@ -0,0 +1,138 @@
+//! This module handles the 'secureboot certs' subcommand
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use efivar::{
+    secureboot::{
+        CertificateExpiry, DatabaseCertificate, EfiTime, SecureBootVariable,
+        MICROSOFT_UEFI_CA_2023, WINDOWS_UEFI_CA_2023,
+    },
+    VarManager,
+};
+
+use super::list::hex;
+use crate::exit_code::ExitCode;
+
+/// CAs replacing the Microsoft 2011 ones, expected in db
+const CA_2023: [&str; 2] = [WINDOWS_UEFI_CA_2023, MICROSOFT_UEFI_CA_2023];
+
+fn format_time(time: SystemTime) -> String {
+    EfiTime::from_unix_time(time.duration_since(UNIX_EPOCH).unwrap_or_default()).to_string()
+}
+
+fn print_human(certificates: &[DatabaseCertificate], now: SystemTime, warning: Duration) {
+    for certificate in certificates {
+        let info = match &certificate.info {
+            Ok(info) => info,
+            Err(err) => {
+                println!("{}: invalid certificate: {err}", certificate.variable);
+                println!();
+                continue;
+            }
+        };
+
+        println!("{}: {}", certificate.variable, info.subject);
+        println!("  Owner: {}", certificate.owner);
+        println!("  Issuer: {}", info.issuer);
+        println!("  Serial number: {}", info.serial_number);
+        println!("  Not before: {}", format_time(info.not_before));
+        println!("  Not after: {}", format_time(info.not_after));
+        println!("  SHA-1 thumbprint: {}", hex(&info.thumbprint));
+        println!("  Status: {}", info.expiry(now, warning));
+        println!();
+    }
+
+    for name in CA_2023 {
+        println!(
+            "{name} in db: {}",
+            if DatabaseCertificate::contains(certificates, SecureBootVariable::Db, name) {
+                "present"
+            } else {
+                "missing"
+            }
+        );
+    }
+}
+
+pub(super) fn to_json(
+    certificates: &[DatabaseCertificate],
+    now: SystemTime,
+    warning: Duration,
+) -> serde_json::Value {
+    let certificates_json = certificates
+        .iter()
+        .map(|certificate| match &certificate.info {
+            Ok(info) => serde_json::json!({
+                "variable": certificate.variable.name(),
+                "owner": certificate.owner.to_string(),
+                "subject": info.subject,
+                "issuer": info.issuer,
+                "serial_number": info.serial_number,
+                "not_before": format_time(info.not_before),
+                "not_after": format_time(info.not_after),
+                "sha1_thumbprint": hex(&info.thumbprint),
+                "status": info.expiry(now, warning).name(),
+            }),
+            Err(err) => serde_json::json!({
+                "variable": certificate.variable.name(),
+                "owner": certificate.owner.to_string(),
+                "error": err.to_string(),
+            }),
+        })
+        .collect::<Vec<_>>();
+
+    let ca_2023 = CA_2023
+        .iter()
+        .map(|name| {
+            (
+                name.to_string(),
+                DatabaseCertificate::contains(certificates, SecureBootVariable::Db, name).into(),
+            )
+        })
+        .collect::<serde_json::Map<_, _>>();
+
+    serde_json::json!({
+        "certificates": certificates_json,
+        "ca_2023_in_db": ca_2023,
+    })
+}
+
+pub fn run(manager: &dyn VarManager, warn_days: u64, json: bool) -> ExitCode {
+    let certificates = match DatabaseCertificate::read_all(manager) {
+        Ok(certificates) => certificates,
+        Err(err) => {
+            log::error!("Failed to read the signature databases: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let now = SystemTime::now();
+    let warning = Duration::from_secs(warn_days * 24 * 60 * 60);
+
+    for certificate in &certificates {
+        if let Ok(info) = &certificate.info {
+            match info.expiry(now, warning) {
+                CertificateExpiry::Expired => log::warn!(
+                    "{}: {} expired on {}",
+                    certificate.variable,
+                    info.subject,
+                    format_time(info.not_after)
+                ),
+                CertificateExpiry::ExpiringSoon => log::warn!(
+                    "{}: {} expires on {}",
+                    certificate.variable,
+                    info.subject,
+                    format_time(info.not_after)
+                ),
+                CertificateExpiry::Valid => {}
+            }
+        }
+    }
+
+    if json {
+        println!("{}", to_json(&certificates, now, warning));
+    } else {
+        print_human(&certificates, now, warning);
+    }
+
+    ExitCode::SUCCESS
+}
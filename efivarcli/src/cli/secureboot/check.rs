@@ -0,0 +1,73 @@
+//! This module handles the 'secureboot check' subcommand
+
+use std::path::Path;
+
+use efivar::{
+    secureboot::{AuthenticodeImage, ImageMatch},
+    VarManager,
+};
+
+use super::list::hex;
+use crate::exit_code::ExitCode;
+
+fn print_matches(database: &str, matches: &[ImageMatch]) {
+    if matches.is_empty() {
+        println!("{database}: no match");
+    }
+    for found in matches {
+        println!("{database}: matched by {found}");
+    }
+}
+
+pub fn run(manager: &dyn VarManager, file: &Path) -> ExitCode {
+    let image = match std::fs::read(file)
+        .map_err(efivar::Error::UnknownIoError)
+        .and_then(|data| AuthenticodeImage::parse(&data))
+    {
+        Ok(image) => image,
+        Err(err) => {
+            log::error!("Failed to read {}: {err}", file.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!("Authenticode SHA-256: {}", hex(&image.hash));
+    if image.signatures.is_empty() {
+        println!("Signatures: none");
+    }
+    for signature in &image.signatures {
+        match &signature.status {
+            Ok(()) => println!("Signature: valid"),
+            Err(err) => println!("Signature: invalid ({err})"),
+        }
+        for certificate in &signature.chain {
+            println!("  Subject: {}", certificate.tbs_certificate.subject);
+            println!("  Issuer: {}", certificate.tbs_certificate.issuer);
+        }
+    }
+
+    let check = match image.check_variables(manager) {
+        Ok(check) => check,
+        Err(err) => {
+            log::error!("Failed to read the signature databases: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    print_matches("db", &check.allowed_by);
+    print_matches("dbx", &check.revoked_by);
+
+    if check.is_allowed() {
+        println!("Result: allowed");
+        ExitCode::SUCCESS
+    } else {
+        println!(
+            "Result: {}",
+            if check.revoked_by.is_empty() {
+                "not allowed by db"
+            } else {
+                "revoked by dbx"
+            }
+        );
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,77 @@
+//! This module handles the 'secureboot drift' subcommand
+
+use efivar::{
+    secureboot::{DatabaseDrift, DriftChange, DriftEntry, Signature, DRIFT_VARIABLES},
+    VarManager,
+};
+
+use super::list::hex;
+use crate::exit_code::ExitCode;
+
+/// a one-line description of a signature: the subject of certificates, the hash of hashes
+fn describe(entry: &DriftEntry) -> String {
+    let signature = match entry.signature.decode(entry.signature_type) {
+        Ok(signature) => signature,
+        Err(err) => return format!("{}: {err}", entry.signature_type),
+    };
+
+    let description = match &signature {
+        Signature::X509(_) => match signature.certificate().unwrap() {
+            Ok(certificate) => certificate.tbs_certificate.subject.to_string(),
+            Err(err) => err.to_string(),
+        },
+        Signature::Sha256(hash) => hex(hash),
+        Signature::X509Sha256 { hash, .. } => hex(hash),
+        Signature::X509Sha384 { hash, .. } => hex(hash),
+        Signature::X509Sha512 { hash, .. } => hex(hash),
+        Signature::Rsa2048(data) | Signature::Unknown(data) => hex(data),
+    };
+
+    format!("{} {description}", entry.signature_type)
+}
+
+fn print_drift(drift: &DatabaseDrift) {
+    if drift.is_empty() {
+        println!(
+            "{}: matches {}",
+            drift.variable,
+            drift.variable.default_variable().name()
+        );
+        return;
+    }
+
+    println!(
+        "{}: {} added, {} removed",
+        drift.variable,
+        drift.count(DriftChange::Added),
+        drift.count(DriftChange::Removed)
+    );
+    for (owner, entries) in drift.by_owner() {
+        println!("  Owner {owner}:");
+        for entry in entries {
+            let sign = match entry.change {
+                DriftChange::Added => '+',
+                DriftChange::Removed => '-',
+            };
+            println!("    {sign} {}", describe(entry));
+        }
+    }
+}
+
+pub fn run(manager: &dyn VarManager) -> ExitCode {
+    for variable in DRIFT_VARIABLES.iter().copied() {
+        match DatabaseDrift::read(manager, variable) {
+            Ok(Some(drift)) => print_drift(&drift),
+            Ok(None) => println!(
+                "{variable}: no {} to compare with",
+                variable.default_variable().name()
+            ),
+            Err(err) => {
+                log::error!("Failed to compare {variable} with its default: {err}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    ExitCode::SUCCESS
+}
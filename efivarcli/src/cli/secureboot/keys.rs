@@ -0,0 +1,125 @@
+//! This module handles the 'secureboot create-keys' and 'secureboot enroll-keys' subcommands
+
+use std::path::{Path, PathBuf};
+
+use efivar::{
+    secureboot::{
+        parse_certificate, EfiTime, SecureBootKeys, SecureBootMode, SecureBootState, SignatureList,
+        MICROSOFT_OWNER_GUID,
+    },
+    VarManager,
+};
+
+use crate::exit_code::ExitCode;
+
+pub fn create(dir: &Path) -> ExitCode {
+    let keys = match SecureBootKeys::generate() {
+        Ok(keys) => keys,
+        Err(err) => {
+            log::error!("Failed to generate keys: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Err(err) = keys.write(dir) {
+        log::error!("Failed to write keys to {}: {err}", dir.display());
+        return ExitCode::FAILURE;
+    }
+
+    println!("Created PK, KEK and db keys in {}", dir.display());
+    println!("Owner GUID: {}", keys.owner);
+    ExitCode::SUCCESS
+}
+
+/// read Microsoft certificates, each in their own list
+fn read_microsoft_certificates(files: &[PathBuf]) -> Result<Vec<SignatureList>, String> {
+    files
+        .iter()
+        .map(|file| {
+            std::fs::read(file)
+                .map_err(|err| err.to_string())
+                .and_then(|data| parse_certificate(&data).map_err(|err| err.to_string()))
+                .and_then(|certificate| {
+                    SignatureList::x509(*MICROSOFT_OWNER_GUID, &certificate)
+                        .map_err(|err| err.to_string())
+                })
+                .map_err(|err| format!("{}: {err}", file.display()))
+        })
+        .collect()
+}
+
+/// whether enrolling may go on: in Setup Mode, or when forced
+fn check_setup_mode(manager: &dyn VarManager, force: bool) -> bool {
+    let reason = match SecureBootState::read(manager) {
+        Ok(state) if state.mode == SecureBootMode::Setup => return true,
+        Ok(state) => format!("the firmware is in {} mode, not in Setup Mode", state.mode),
+        Err(err) => format!("failed to read the Secure Boot state: {err}"),
+    };
+
+    if force {
+        log::warn!("{reason}, enrolling anyway");
+        true
+    } else {
+        log::error!("{reason}. Use --force to enroll anyway");
+        false
+    }
+}
+
+pub fn enroll(
+    manager: &mut dyn VarManager,
+    dir: &Path,
+    microsoft_kek: &[PathBuf],
+    microsoft_db: &[PathBuf],
+    force: bool,
+) -> ExitCode {
+    if !check_setup_mode(manager, force) {
+        return ExitCode::FAILURE;
+    }
+
+    let keys = match SecureBootKeys::read(dir) {
+        Ok(keys) => keys,
+        Err(err) => {
+            log::error!("Failed to read keys from {}: {err}", dir.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let (extra_kek, extra_db) = match (
+        read_microsoft_certificates(microsoft_kek),
+        read_microsoft_certificates(microsoft_db),
+    ) {
+        (Ok(extra_kek), Ok(extra_db)) => (extra_kek, extra_db),
+        (Err(err), _) | (_, Err(err)) => {
+            log::error!("Failed to read Microsoft certificate {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let updates = match keys.signed_updates(EfiTime::now(), &extra_kek, &extra_db) {
+        Ok(updates) => updates,
+        Err(err) => {
+            log::error!("Failed to sign updates: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // keep the signed updates, so that they can be enrolled with other tools
+    for (variable, payload) in &updates {
+        let file = dir.join(format!("{variable}.auth"));
+        let res = payload
+            .to_bytes()
+            .map_err(|err| err.to_string())
+            .and_then(|bytes| std::fs::write(&file, bytes).map_err(|err| err.to_string()));
+        if let Err(err) = res {
+            log::error!("Failed to write {}: {err}", file.display());
+            return ExitCode::FAILURE;
+        }
+    }
+
+    if let Err(err) = SecureBootKeys::enroll(manager, &updates) {
+        log::error!("Failed to enroll keys: {err}");
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
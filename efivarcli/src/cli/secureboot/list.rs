@@ -0,0 +1,110 @@
+//! This module handles the 'secureboot list' subcommand
+
+use efivar::{
+    secureboot::{SecureBootVariable, Signature, SignatureData, SignatureDatabase, SignatureType},
+    Error, VarManager,
+};
+
+use crate::exit_code::ExitCode;
+
+pub(super) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// prints a signature to the console
+pub(crate) fn print_signature(signature_type: SignatureType, data: &SignatureData, verbose: bool) {
+    println!();
+    println!("Type: {signature_type}");
+    println!("Owner: {}", data.owner);
+
+    let signature = match data.decode(signature_type) {
+        Ok(signature) => signature,
+        Err(err) => {
+            println!("Error: {err}");
+            return;
+        }
+    };
+
+    match &signature {
+        Signature::Sha256(hash) => println!("Hash: {}", hex(hash)),
+        Signature::Rsa2048(modulus) => println!("Modulus: {}", hex(modulus)),
+        Signature::X509(_) => match signature.certificate().unwrap() {
+            Ok(certificate) => {
+                let tbs = &certificate.tbs_certificate;
+                println!("Subject: {}", tbs.subject);
+                println!("Issuer: {}", tbs.issuer);
+                if verbose {
+                    println!("Serial number: {}", tbs.serial_number);
+                    println!("Not before: {}", tbs.validity.not_before);
+                    println!("Not after: {}", tbs.validity.not_after);
+                }
+            }
+            Err(err) => println!("Error: {err}"),
+        },
+        Signature::X509Sha256 {
+            hash,
+            revocation_time,
+        } => print_certificate_hash(hash, revocation_time),
+        Signature::X509Sha384 {
+            hash,
+            revocation_time,
+        } => print_certificate_hash(hash, revocation_time),
+        Signature::X509Sha512 {
+            hash,
+            revocation_time,
+        } => print_certificate_hash(hash, revocation_time),
+        Signature::Unknown(data) => {
+            if verbose {
+                println!("Data: {}", hex(data));
+            }
+        }
+    }
+}
+
+fn print_certificate_hash(hash: &[u8], revocation_time: &efivar::secureboot::EfiTime) {
+    println!("Certificate hash: {}", hex(hash));
+    if revocation_time.is_zero() {
+        println!("Revoked since: always");
+    } else {
+        println!("Revoked since: {revocation_time}");
+    }
+}
+
+pub fn run(
+    manager: &dyn VarManager,
+    database: Option<SecureBootVariable>,
+    verbose: bool,
+) -> ExitCode {
+    let variables = match database {
+        Some(variable) => vec![variable],
+        None => SecureBootVariable::ALL.to_vec(),
+    };
+
+    let mut exit_code = ExitCode::SUCCESS;
+
+    for (i, variable) in variables.into_iter().enumerate() {
+        if i != 0 {
+            println!();
+        }
+
+        let database = match SignatureDatabase::read(manager, variable) {
+            Ok(database) => database,
+            Err(Error::VarNotFound { .. }) => {
+                println!("{variable}: not set");
+                continue;
+            }
+            Err(err) => {
+                log::error!("Failed to read {variable}: {err}");
+                exit_code = ExitCode::FAILURE;
+                continue;
+            }
+        };
+
+        println!("{variable}: {} signatures", database.signatures().count());
+        for (signature_type, data) in database.signatures() {
+            print_signature(signature_type, data, verbose);
+        }
+    }
+
+    exit_code
+}
@@ -0,0 +1,126 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use efivar::{secureboot::SecureBootVariable, VarManager};
+
+use crate::exit_code::ExitCode;
+
+/// upper bound of `secureboot certs --warn-days`: 100 years
+const MAX_WARN_DAYS: u64 = 36525;
+
+pub mod certs;
+pub mod check;
+pub mod drift;
+pub mod keys;
+pub mod list;
+pub mod sbat_check;
+pub mod status;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Parser)]
+pub enum SecureBootCommand {
+    /// List the signatures of the Secure Boot databases (PK, KEK, db, dbx, dbt, dbr)
+    #[command(alias = "get")]
+    List {
+        /// Only list this database
+        #[arg(value_name = "DATABASE")]
+        database: Option<SecureBootVariable>,
+
+        /// Show more information, such as certificate validity and raw hashes of unknown types
+        #[arg(short, long)]
+        verbose: bool,
+    },
+    /// Show the X.509 certificates of PK, KEK, db and dbx, warning about the expired or
+    /// soon-to-expire ones, and whether the 2023 Microsoft UEFI CAs are enrolled in db
+    Certs {
+        /// Warn about certificates expiring within this number of days, up to 100 years
+        #[arg(
+            long,
+            value_name = "DAYS",
+            default_value_t = 90,
+            value_parser = clap::value_parser!(u64).range(..=MAX_WARN_DAYS)
+        )]
+        warn_days: u64,
+
+        /// Print the certificates as JSON, for scripts
+        #[arg(long)]
+        json: bool,
+    },
+    /// Compute the Authenticode hash of an EFI image, and check it against db and dbx. Exits
+    /// with a failure if firmware would refuse to run the image
+    Check {
+        /// PE/COFF image, such as a bootloader or a kernel
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// Compare PK, KEK, db and dbx with their factory defaults (PKDefault, KEKDefault, dbDefault
+    /// and dbxDefault), listing the signatures added or removed by owner GUID
+    Drift,
+    /// Generate PK, KEK and db key pairs, along with an owner GUID, into a directory
+    CreateKeys {
+        /// Directory to write the keys to
+        #[arg(short, long, value_name = "DIR")]
+        dir: PathBuf,
+    },
+    /// Enroll the keys created by create-keys, in the order db, KEK then PK. The signed updates
+    /// are also written to the key directory as .auth files
+    EnrollKeys {
+        /// Directory holding the keys
+        #[arg(short, long, value_name = "DIR")]
+        dir: PathBuf,
+
+        /// Microsoft KEK certificate to enroll along with the KEK, in PEM or DER format. Can be
+        /// repeated
+        #[arg(long, value_name = "FILE")]
+        microsoft_kek: Vec<PathBuf>,
+
+        /// Microsoft db certificate to enroll along with the db key, in PEM or DER format. Can be
+        /// repeated
+        #[arg(long, value_name = "FILE")]
+        microsoft_db: Vec<PathBuf>,
+
+        /// Enroll even if the firmware is not in Setup Mode
+        #[arg(long)]
+        force: bool,
+    },
+    /// Check the SBAT metadata of an EFI image against the revocations of shim (SbatLevelRT).
+    /// Exits with a failure if shim would refuse to load the image
+    SbatCheck {
+        /// PE/COFF image, such as a bootloader
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// SBAT level to check against instead of SbatLevelRT, such as one about to be rolled
+        /// out, as CSV
+        #[arg(long, value_name = "LEVEL_FILE")]
+        level: Option<PathBuf>,
+    },
+    /// Show whether Secure Boot is enabled, and the mode the platform is in
+    Status {
+        /// Print the state as JSON, for scripts
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+pub fn run(manager: &mut dyn VarManager, cmd: SecureBootCommand) -> ExitCode {
+    match cmd {
+        SecureBootCommand::List { database, verbose } => list::run(manager, database, verbose),
+        SecureBootCommand::SbatCheck { file, level } => {
+            sbat_check::run(manager, &file, level.as_deref())
+        }
+        SecureBootCommand::Status { json } => status::run(manager, json),
+        SecureBootCommand::Certs { warn_days, json } => certs::run(manager, warn_days, json),
+        SecureBootCommand::Check { file } => check::run(manager, &file),
+        SecureBootCommand::Drift => drift::run(manager),
+        SecureBootCommand::CreateKeys { dir } => keys::create(&dir),
+        SecureBootCommand::EnrollKeys {
+            dir,
+            microsoft_kek,
+            microsoft_db,
+            force,
+        } => keys::enroll(manager, &dir, &microsoft_kek, &microsoft_db, force),
+    }
+}
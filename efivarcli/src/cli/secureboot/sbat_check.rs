@@ -0,0 +1,73 @@
+//! This module handles the 'secureboot sbat-check' subcommand
+
+use std::path::Path;
+
+use efivar::{
+    mok::{read_sbat_level, ImageSbat, SbatEntry},
+    Error, VarManager,
+};
+
+use crate::exit_code::ExitCode;
+
+/// the SBAT level in a file, or the one of SbatLevelRT
+fn read_level(manager: &dyn VarManager, level: Option<&Path>) -> efivar::Result<Vec<SbatEntry>> {
+    match level {
+        Some(file) => SbatEntry::parse_csv(
+            &std::fs::read(file).map_err(Error::UnknownIoError)?,
+            "SBAT level",
+        ),
+        None => read_sbat_level(manager),
+    }
+}
+
+fn print_entries(entries: &[SbatEntry]) {
+    for entry in entries {
+        println!("  {},{}", entry.component, entry.generation);
+    }
+}
+
+pub fn run(manager: &dyn VarManager, file: &Path, level: Option<&Path>) -> ExitCode {
+    let image = match std::fs::read(file)
+        .map_err(Error::UnknownIoError)
+        .and_then(|data| ImageSbat::parse(&data))
+    {
+        Ok(image) => image,
+        Err(err) => {
+            log::error!("Failed to read {}: {err}", file.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let level = match read_level(manager, level) {
+        Ok(level) => level,
+        Err(err) => {
+            log::error!("Failed to read the SBAT level: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!("SBAT level:");
+    print_entries(&level);
+    match &image.entries {
+        Some(entries) => {
+            println!("Image SBAT:");
+            print_entries(entries);
+        }
+        None => println!("Image SBAT: no .sbat section"),
+    }
+
+    let check = image.check(&level);
+    for revocation in &check.revocations {
+        println!(
+            "Revoked: {} generation {} is older than {}",
+            revocation.component, revocation.image_generation, revocation.required_generation
+        );
+    }
+
+    if check.is_allowed() {
+        println!("Result: allowed");
+        ExitCode::SUCCESS
+    } else {
+        println!("Result: refused by shim");
+        ExitCode::FAILURE
+    }
+}
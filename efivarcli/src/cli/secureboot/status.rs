@@ -0,0 +1,62 @@
+//! This module handles the 'secureboot status' subcommand
+
+use efivar::{secureboot::SecureBootState, VarManager};
+use itertools::Itertools;
+
+use crate::exit_code::ExitCode;
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+fn print_human(state: &SecureBootState) {
+    println!(
+        "Secure Boot: {}",
+        if state.enabled { "enabled" } else { "disabled" }
+    );
+    println!("Mode: {}", state.mode);
+    println!("Keys enrolled: {}", yes_no(state.keys_enrolled()));
+    match state.vendor_keys_modified {
+        Some(modified) => println!("Vendor keys modified: {}", yes_no(modified)),
+        None => println!("Vendor keys modified: unknown"),
+    }
+    match &state.signature_support {
+        Some(types) => println!("Signature support: {}", types.iter().join(", ")),
+        None => println!("Signature support: unknown"),
+    }
+}
+
+pub(super) fn to_json(state: &SecureBootState) -> serde_json::Value {
+    serde_json::json!({
+        "enabled": state.enabled,
+        "mode": state.mode.name(),
+        "keys_enrolled": state.keys_enrolled(),
+        "vendor_keys_modified": state.vendor_keys_modified,
+        "signature_support": state
+            .signature_support
+            .as_ref()
+            .map(|types| types.iter().map(ToString::to_string).collect::<Vec<_>>()),
+    })
+}
+
+pub fn run(manager: &dyn VarManager, json: bool) -> ExitCode {
+    let state = match SecureBootState::read(manager) {
+        Ok(state) => state,
+        Err(err) => {
+            log::error!("Failed to read the Secure Boot state: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if json {
+        println!("{}", to_json(&state));
+    } else {
+        print_human(&state);
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,402 @@
+use std::time::Duration;
+
+use clap::Parser;
+use efivar::{
+    efi::{Variable, VariableFlags},
+    secureboot::{
+        DatabaseCertificate, SecureBootState, SecureBootVariable, SignatureData, SignatureDatabase,
+        SignatureList, SignatureType,
+    },
+    store::{FileStore, MemoryStore},
+    VarWriter,
+};
+use uuid::Uuid;
+
+use super::{certs, status};
+use crate::{
+    cli::{
+        tests::{single_signature_database, SIGNED_IMAGE_PATH, TEST_CERT, TEST_CERT_PATH},
+        Command,
+    },
+    exit_code::ExitCode,
+};
+
+fn write_database(manager: &mut MemoryStore, variable: SecureBootVariable, db: &SignatureDatabase) {
+    manager
+        .write(
+            &variable.variable(),
+            VariableFlags::default(),
+            &db.to_bytes().unwrap(),
+        )
+        .unwrap();
+}
+
+#[test]
+fn list() {
+    let manager = &mut MemoryStore::new();
+    let owner = Uuid::from_u128(0x77fa9abd_0359_4d32_bd60_28f4e78f784b);
+
+    let db = SignatureDatabase {
+        lists: vec![
+            SignatureList::new(
+                SignatureType::X509,
+                vec![SignatureData {
+                    owner,
+                    data: TEST_CERT.to_vec(),
+                }],
+            )
+            .unwrap(),
+            SignatureList::new(
+                SignatureType::Sha256,
+                vec![SignatureData {
+                    owner,
+                    data: vec![0xAB; 32],
+                }],
+            )
+            .unwrap(),
+        ],
+    };
+    write_database(manager, SecureBootVariable::Db, &db);
+
+    for args in [
+        &["efivarcli", "secureboot", "list"][..],
+        &["efivarcli", "secureboot", "list", "-v"],
+        &["efivarcli", "secureboot", "list", "db"],
+        &["efivarcli", "secureboot", "list", "PK"],
+    ] {
+        assert_eq!(
+            ExitCode::SUCCESS,
+            crate::run(Command::parse_from(args), manager)
+        );
+    }
+}
+
+#[test]
+fn list_invalid() {
+    let manager = &mut MemoryStore::new();
+    manager
+        .write(
+            &SecureBootVariable::Dbx.variable(),
+            VariableFlags::default(),
+            &[0x00; 10],
+        )
+        .unwrap();
+
+    assert_eq!(
+        ExitCode::FAILURE,
+        crate::run(
+            Command::parse_from(["efivarcli", "secureboot", "list", "dbx"]),
+            manager,
+        )
+    );
+    assert!(Command::try_parse_from(["efivarcli", "secureboot", "list", "foo"]).is_err());
+}
+
+#[test]
+fn status() {
+    let manager = &mut MemoryStore::new();
+
+    assert_eq!(
+        ExitCode::FAILURE,
+        crate::run(
+            Command::parse_from(["efivarcli", "secureboot", "status"]),
+            manager,
+        )
+    );
+
+    for (name, value) in [("SecureBoot", 1), ("SetupMode", 0), ("VendorKeys", 1)] {
+        manager
+            .write(
+                &Variable::new(name),
+                VariableFlags::BOOTSERVICE_ACCESS | VariableFlags::RUNTIME_ACCESS,
+                &[value],
+            )
+            .unwrap();
+    }
+
+    for args in [
+        &["efivarcli", "secureboot", "status"][..],
+        &["efivarcli", "secureboot", "status", "--json"],
+    ] {
+        assert_eq!(
+            ExitCode::SUCCESS,
+            crate::run(Command::parse_from(args), manager)
+        );
+    }
+
+    let state = SecureBootState::read(manager).unwrap();
+    assert_eq!(
+        status::to_json(&state),
+        serde_json::json!({
+            "enabled": true,
+            "mode": "user",
+            "keys_enrolled": true,
+            "vendor_keys_modified": false,
+            "signature_support": null,
+        })
+    );
+}
+
+fn set_setup_mode(manager: &mut dyn VarWriter, setup_mode: u8) {
+    manager
+        .write(
+            &Variable::new("SetupMode"),
+            VariableFlags::BOOTSERVICE_ACCESS | VariableFlags::RUNTIME_ACCESS,
+            &[setup_mode],
+        )
+        .unwrap();
+}
+
+#[test]
+fn create_enroll_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    let keys_dir = dir.path().join("keys");
+    let keys_dir = keys_dir.to_str().unwrap();
+    let store = dir.path().join("store.toml");
+
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from(["efivarcli", "secureboot", "create-keys", "-d", keys_dir]),
+            &mut MemoryStore::new(),
+        )
+    );
+    // existing keys are not overwritten
+    assert_eq!(
+        ExitCode::FAILURE,
+        crate::run(
+            Command::parse_from(["efivarcli", "secureboot", "create-keys", "-d", keys_dir]),
+            &mut MemoryStore::new(),
+        )
+    );
+
+    let enroll = [
+        "efivarcli",
+        "secureboot",
+        "enroll-keys",
+        "--dir",
+        keys_dir,
+        "--microsoft-db",
+        TEST_CERT_PATH,
+    ];
+
+    // rehearse on a file store
+    {
+        let manager = &mut FileStore::new(store.clone());
+
+        // not in Setup Mode
+        assert_eq!(
+            ExitCode::FAILURE,
+            crate::run(Command::parse_from(enroll), manager)
+        );
+        set_setup_mode(manager, 0);
+        assert_eq!(
+            ExitCode::FAILURE,
+            crate::run(Command::parse_from(enroll), manager)
+        );
+
+        set_setup_mode(manager, 1);
+        assert_eq!(
+            ExitCode::SUCCESS,
+            crate::run(Command::parse_from(enroll), manager)
+        );
+    }
+
+    let manager = &mut FileStore::new(store);
+    let db = SignatureDatabase::read(manager, SecureBootVariable::Db).unwrap();
+    assert_eq!(db.lists.len(), 2);
+    assert_eq!(db.lists[1].signatures[0].data, TEST_CERT);
+    for variable in ["db", "KEK", "PK"] {
+        assert!(dir
+            .path()
+            .join("keys")
+            .join(format!("{variable}.auth"))
+            .exists());
+    }
+
+    set_setup_mode(manager, 0);
+    let forced = [&enroll[..], &["--force"]].concat();
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(Command::parse_from(forced), manager)
+    );
+}
+
+#[test]
+fn check() {
+    let manager = &mut MemoryStore::new();
+    let check = |manager: &mut MemoryStore| {
+        crate::run(
+            Command::parse_from(["efivarcli", "secureboot", "check", SIGNED_IMAGE_PATH]),
+            manager,
+        )
+    };
+
+    // empty db
+    assert_eq!(ExitCode::FAILURE, check(manager));
+
+    let db = single_signature_database(SignatureType::X509, TEST_CERT);
+    // the test certificate issued the signer
+    write_database(manager, SecureBootVariable::Db, &db);
+    assert_eq!(ExitCode::SUCCESS, check(manager));
+
+    write_database(manager, SecureBootVariable::Dbx, &db);
+    assert_eq!(ExitCode::FAILURE, check(manager));
+
+    assert_eq!(
+        ExitCode::FAILURE,
+        crate::run(
+            Command::parse_from(["efivarcli", "secureboot", "check", TEST_CERT_PATH]),
+            manager,
+        )
+    );
+}
+
+#[test]
+fn certs() {
+    let manager = &mut MemoryStore::new();
+    let db = single_signature_database(SignatureType::X509, TEST_CERT);
+    write_database(manager, SecureBootVariable::Db, &db);
+    write_database(manager, SecureBootVariable::Kek, &db);
+
+    // KEK then db
+    let certificates = DatabaseCertificate::read_all(manager).unwrap();
+    let not_after = certificates[0].info.as_ref().unwrap().not_after;
+    let warning = Duration::from_secs(90 * 24 * 60 * 60);
+    for (now, status) in [
+        (not_after - 2 * warning, "valid"),
+        (not_after - warning / 2, "expiring soon"),
+        (not_after, "expired"),
+    ] {
+        let json = certs::to_json(&certificates, now, warning);
+        assert_eq!(json["certificates"][0]["variable"], "KEK");
+        assert_eq!(json["certificates"][1]["status"], status);
+        assert_eq!(
+            json["ca_2023_in_db"],
+            serde_json::json!({
+                "Windows UEFI CA 2023": false,
+                "Microsoft UEFI CA 2023": false,
+            })
+        );
+    }
+
+    for args in [
+        &["efivarcli", "secureboot", "certs"][..],
+        &["efivarcli", "secureboot", "certs", "--json"],
+        &["efivarcli", "secureboot", "certs", "--warn-days", "36525"],
+    ] {
+        assert_eq!(
+            ExitCode::SUCCESS,
+            crate::run(Command::parse_from(args), manager)
+        );
+    }
+    assert!(Command::try_parse_from([
+        "efivarcli",
+        "secureboot",
+        "certs",
+        "--warn-days",
+        "300000000000000"
+    ])
+    .is_err());
+
+    manager
+        .write(
+            &SecureBootVariable::Pk.variable(),
+            VariableFlags::default(),
+            &[0x00; 10],
+        )
+        .unwrap();
+    assert_eq!(
+        ExitCode::FAILURE,
+        crate::run(
+            Command::parse_from(["efivarcli", "secureboot", "certs"]),
+            manager,
+        )
+    );
+}
+
+#[test]
+fn drift() {
+    let manager = &mut MemoryStore::new();
+    let drift = |manager: &mut MemoryStore| {
+        crate::run(
+            Command::parse_from(["efivarcli", "secureboot", "drift"]),
+            manager,
+        )
+    };
+
+    // no defaults
+    assert_eq!(ExitCode::SUCCESS, drift(manager));
+
+    for (variable, value) in [
+        (
+            SecureBootVariable::Db.default_variable(),
+            single_signature_database(SignatureType::X509, TEST_CERT),
+        ),
+        (
+            SecureBootVariable::Dbx.default_variable(),
+            single_signature_database(SignatureType::Sha256, &[0xAB; 32]),
+        ),
+        (
+            SecureBootVariable::Dbx.variable(),
+            single_signature_database(SignatureType::Sha256, &[0xAB; 32]),
+        ),
+        (
+            SecureBootVariable::Db.variable(),
+            single_signature_database(SignatureType::Sha256, &[0xCD; 32]),
+        ),
+    ] {
+        manager
+            .write(
+                &variable,
+                VariableFlags::default(),
+                &value.to_bytes().unwrap(),
+            )
+            .unwrap();
+    }
+    assert_eq!(ExitCode::SUCCESS, drift(manager));
+
+    manager
+        .write(
+            &SecureBootVariable::Kek.default_variable(),
+            VariableFlags::default(),
+            &[0x00; 10],
+        )
+        .unwrap();
+    assert_eq!(ExitCode::FAILURE, drift(manager));
+}
+
+#[test]
+fn sbat_check() {
+    let dir = tempfile::tempdir().unwrap();
+    let level_file = dir.path().join("level.csv");
+    std::fs::write(&level_file, "sbat,1,2023012900\ngrub,4\n").unwrap();
+
+    let manager = &mut MemoryStore::new();
+    let check = |manager: &mut MemoryStore, args: &[&str]| {
+        crate::run(
+            Command::parse_from(
+                ["efivarcli", "secureboot", "sbat-check", SIGNED_IMAGE_PATH]
+                    .iter()
+                    .chain(args),
+            ),
+            manager,
+        )
+    };
+
+    // SbatLevelRT is not set
+    assert_eq!(ExitCode::FAILURE, check(manager, &[]));
+
+    manager
+        .write(
+            &efivar::mok::MokVariable::SBAT_LEVEL_RT.variable(),
+            VariableFlags::BOOTSERVICE_ACCESS | VariableFlags::RUNTIME_ACCESS,
+            b"sbat,1,2022052400\ngrub,2\n",
+        )
+        .unwrap();
+    assert_eq!(ExitCode::SUCCESS, check(manager, &[]));
+    assert_eq!(
+        ExitCode::FAILURE,
+        check(manager, &["--level", level_file.to_str().unwrap()])
+    );
+}
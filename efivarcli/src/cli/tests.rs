@@ -2,15 +2,44 @@ use std::{fs::File, io::Write};
 
 use efivar::{
     efi::{Variable, VariableFlags},
+    secureboot::{SignatureData, SignatureDatabase, SignatureList, SignatureType},
     store::MemoryStore,
     test_utils::assert_var_not_found,
     VarReader, VarWriter,
 };
+use uuid::Uuid;
 
 use crate::{cli::Command, exit_code::ExitCode};
 
 use super::*;
 
+/// DER certificate of the efivar test key
+pub const TEST_CERT: &[u8] = include_bytes!("../../../efivar/src/secureboot/tests/test_cert.der");
+/// path of [`TEST_CERT`] in the PEM format
+pub const TEST_CERT_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../efivar/src/secureboot/tests/test_cert.pem"
+);
+/// path of an EFI image signed by a certificate issued by [`TEST_CERT`]
+pub const SIGNED_IMAGE_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../efivar/src/secureboot/tests/signed.efi"
+);
+
+/// a signature database holding a single signature, owned by the nil GUID
+pub fn single_signature_database(signature_type: SignatureType, data: &[u8]) -> SignatureDatabase {
+    SignatureDatabase {
+        lists: vec![SignatureList::new(
+            signature_type,
+            vec![SignatureData {
+                owner: Uuid::nil(),
+                data: data.to_vec(),
+            }],
+        )
+        .unwrap()],
+    }
+}
+
 #[test]
 fn list() {
     let manager = &mut MemoryStore::new();
@@ -146,6 +175,43 @@ fn export() {
     );
 }
 
+#[test]
+fn export_import_authenticated() {
+    //! An exported authenticated variable holds its data without a signature, and imports as is
+
+    let mut manager = MemoryStore::new();
+    let attributes = VariableFlags::NON_VOLATILE
+        | VariableFlags::BOOTSERVICE_ACCESS
+        | VariableFlags::RUNTIME_ACCESS
+        | VariableFlags::TIME_BASED_AUTHENTICATED_WRITE_ACCESS;
+    manager
+        .write(&Variable::new("PK"), attributes, &[0xA1, 0x59, 0xC0, 0xA5])
+        .unwrap();
+
+    let tmpdir = tempfile::tempdir().unwrap();
+    let file_path = tmpdir.path().join("PK.bin");
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from(["efivarcli", "export", "PK", file_path.to_str().unwrap()]),
+            &mut manager
+        )
+    );
+
+    let mut imported = MemoryStore::new();
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from(["efivarcli", "import", file_path.to_str().unwrap(), "PK"]),
+            &mut imported
+        )
+    );
+    assert_eq!(
+        imported.read(&Variable::new("PK")).unwrap(),
+        manager.read(&Variable::new("PK")).unwrap()
+    );
+}
+
 #[test]
 fn export_no_var() {
     //! Try `efivarcli export` with a variable that doesn't exist
@@ -274,3 +340,63 @@ fn read_non_existent() {
         )
     );
 }
+
+#[test]
+fn firmware_setup() {
+    //! Request and cancel booting into the firmware setup, keeping other OsIndications bits
+
+    let mut manager = MemoryStore::new();
+
+    // unsupported by the firmware
+    assert_eq!(
+        ExitCode::FAILURE,
+        crate::run(
+            Command::parse_from(["efivarcli", "firmware-setup"]),
+            &mut manager
+        )
+    );
+    assert_var_not_found(&mut manager, &Variable::new("OsIndications"));
+
+    manager
+        .write(
+            &Variable::new("OsIndicationsSupported"),
+            VariableFlags::BOOTSERVICE_ACCESS | VariableFlags::RUNTIME_ACCESS,
+            &0x05u64.to_le_bytes(),
+        )
+        .unwrap();
+    manager
+        .write(
+            &Variable::new("OsIndications"),
+            VariableFlags::default(),
+            &0x04u64.to_le_bytes(),
+        )
+        .unwrap();
+
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from(["efivarcli", "firmware-setup"]),
+            &mut manager
+        )
+    );
+    let (data, _) = manager.read(&Variable::new("OsIndications")).unwrap();
+    assert_eq!(data, 0x05u64.to_le_bytes());
+
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from(["efivarcli", "firmware-setup", "--status"]),
+            &mut manager
+        )
+    );
+
+    assert_eq!(
+        ExitCode::SUCCESS,
+        crate::run(
+            Command::parse_from(["efivarcli", "firmware-setup", "--clear"]),
+            &mut manager
+        )
+    );
+    let (data, _) = manager.read(&Variable::new("OsIndications")).unwrap();
+    assert_eq!(data, 0x04u64.to_le_bytes());
+}
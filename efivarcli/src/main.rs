@@ -15,6 +15,15 @@ struct Opt {
     #[arg(short, long, value_name = "FILE", env = "EFIBOOT_STORE")]
     file_store: Option<PathBuf>,
 
+    /// edk2 NVRAM image, such as OVMF_VARS.fd, to use for variable storage instead of the system
+    #[arg(
+        long,
+        value_name = "FILE",
+        env = "EFIBOOT_OVMF_VARS",
+        conflicts_with = "file_store"
+    )]
+    ovmf_vars: Option<PathBuf>,
+
     #[command(subcommand)]
     cmd: Command,
 }
@@ -38,6 +47,14 @@ fn main() -> std::process::ExitCode {
 
     let manager = &mut *if let Some(filename) = opts.file_store {
         efivar::file_store(filename)
+    } else if let Some(filename) = opts.ovmf_vars {
+        match efivar::ovmf_vars(&filename) {
+            Ok(manager) => manager,
+            Err(err) => {
+                log::error!("Failed to open {}: {err}", filename.display());
+                return ExitCode::FAILURE.into();
+            }
+        }
     } else {
         efivar::system().expect("Failed to instanciate variable manager")
     };